@@ -0,0 +1,163 @@
+use crate::calculations::watch_rules::{parse_hhmm, window_contains};
+use crate::models::notification::NotificationRecord;
+use chrono::{DateTime, Timelike, Utc};
+
+/// Whether `now` falls in the daily quiet window `start..end` ("HH:MM") read
+/// in the IANA `timezone`. Unknown zones fall back to UTC.
+pub fn quiet_hours_active(start: &str, end: &str, timezone: &str, now: DateTime<Utc>) -> bool {
+    let (Some(start), Some(end)) = (parse_hhmm(start), parse_hhmm(end)) else {
+        return false;
+    };
+    let tz: chrono_tz::Tz = timezone.parse().unwrap_or(chrono_tz::UTC);
+    let local = now.with_timezone(&tz);
+    window_contains(start, end, (local.hour() * 60 + local.minute()) as i32)
+}
+
+/// Title and body of one digest toast for held notifications from a single
+/// source, e.g. "7 quakes ≥ M5.0 in the last hour".
+pub fn digest_summary(source: &str, records: &[NotificationRecord], now: i64) -> (String, String) {
+    let Some(strongest) = records.iter().max_by(|a, b| {
+        a.severity
+            .unwrap_or(f64::MIN)
+            .total_cmp(&b.severity.unwrap_or(f64::MIN))
+            .then(a.created_at.cmp(&b.created_at))
+    }) else {
+        return (String::new(), String::new());
+    };
+    if records.len() == 1 {
+        return (strongest.title.clone(), strongest.body.clone());
+    }
+
+    let oldest = records.iter().map(|r| r.created_at).min().unwrap_or(now);
+    let period = describe_period(now - oldest);
+    let weakest = records
+        .iter()
+        .filter_map(|r| r.severity)
+        .min_by(f64::total_cmp);
+    let title = match (source, weakest) {
+        ("earthquake", Some(min)) => {
+            format!("{} quakes ≥ M{:.1} in {}", records.len(), min, period)
+        }
+        _ => format!("{} {} in {}", records.len(), source_label(source), period),
+    };
+    let body = format!("Strongest: {} ({})", strongest.title, strongest.body);
+    (title, body)
+}
+
+fn source_label(source: &str) -> String {
+    match source {
+        "earthquake" => "earthquake alerts".to_string(),
+        "kp" => "geomagnetic storm alerts".to_string(),
+        "gdacs" => "GDACS alerts".to_string(),
+        "eonet" => "natural event alerts".to_string(),
+        "flare" => "solar flare alerts".to_string(),
+        other => format!("{} alerts", other),
+    }
+}
+
+fn describe_period(secs: i64) -> String {
+    let minutes = (secs.max(60) + 59) / 60;
+    match minutes {
+        0..=59 => format!("the last {} minutes", minutes),
+        60..=89 => "the last hour".to_string(),
+        _ => format!("the last {} hours", (minutes + 30) / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn record(
+        title: &str,
+        body: &str,
+        severity: Option<f64>,
+        created_at: i64,
+    ) -> NotificationRecord {
+        NotificationRecord {
+            id: 0,
+            source: "earthquake".to_string(),
+            event_id: title.to_string(),
+            rule: "magnitude_threshold".to_string(),
+            title: title.to_string(),
+            body: body.to_string(),
+            latitude: None,
+            longitude: None,
+            suppressed: true,
+            held: true,
+            severity,
+            acknowledged_at: None,
+            created_at,
+        }
+    }
+
+    #[test]
+    fn quiet_hours_follow_the_observer_timezone() {
+        // 06:00 UTC is 23:00 the previous day in Los Angeles (PDT)
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 6, 0, 0).unwrap();
+        assert!(quiet_hours_active(
+            "22:00",
+            "07:00",
+            "America/Los_Angeles",
+            now
+        ));
+        assert!(!quiet_hours_active("22:00", "07:00", "Asia/Tokyo", now));
+        // Unknown zones read the window in UTC
+        assert!(quiet_hours_active("05:00", "07:00", "Mars/Olympus", now));
+        assert!(!quiet_hours_active("05:00", "bad", "UTC", now));
+    }
+
+    #[test]
+    fn quiet_hours_respect_daylight_saving() {
+        // 07:30 UTC is 08:30 in Berlin in winter but 09:30 in summer
+        let winter = Utc.with_ymd_and_hms(2024, 1, 15, 7, 30, 0).unwrap();
+        let summer = Utc.with_ymd_and_hms(2024, 7, 15, 7, 30, 0).unwrap();
+        assert!(quiet_hours_active(
+            "08:00",
+            "09:00",
+            "Europe/Berlin",
+            winter
+        ));
+        assert!(!quiet_hours_active(
+            "08:00",
+            "09:00",
+            "Europe/Berlin",
+            summer
+        ));
+    }
+
+    #[test]
+    fn digest_names_the_strongest_event() {
+        let now = 10_000;
+        let records = vec![
+            record(
+                "Earthquake M5.2",
+                "Off the coast of Chile",
+                Some(5.2),
+                now - 3700,
+            ),
+            record(
+                "Earthquake M6.1",
+                "near Hualien, Taiwan",
+                Some(6.1),
+                now - 2000,
+            ),
+            record("Earthquake M5.0", "Tonga", Some(5.0), now - 600),
+        ];
+        let (title, body) = digest_summary("earthquake", &records, now);
+        assert_eq!(title, "3 quakes ≥ M5.0 in the last hour");
+        assert_eq!(body, "Strongest: Earthquake M6.1 (near Hualien, Taiwan)");
+
+        let (title, _) = digest_summary("gdacs", &records[..2], now - 2300);
+        assert_eq!(title, "2 GDACS alerts in the last 24 minutes");
+    }
+
+    #[test]
+    fn single_held_event_keeps_its_text() {
+        let records = vec![record("Earthquake M5.2", "Tonga", Some(5.2), 0)];
+        let (title, body) = digest_summary("earthquake", &records, 7200);
+        assert_eq!(title, "Earthquake M5.2");
+        assert_eq!(body, "Tonga");
+    }
+}
@@ -0,0 +1,200 @@
+use crate::calculations::orbit::Propagator;
+use crate::models::satellite::Conjunction;
+
+/// Coarse sampling interval (seconds) used to bracket close approaches.
+const COARSE_STEP_SECS: i64 = 60;
+
+/// Margin (km) added to the apogee/perigee overlap filter so near-miss shells are still screened.
+const SHELL_PAD_KM: f64 = 20.0;
+
+pub struct TrackedObject {
+    pub id: String,
+    pub name: String,
+    pub propagator: Propagator,
+}
+
+/// Screen every pair of tracked objects for close approaches over `days` from
+/// `start_unix`, returning those whose miss distance is below `threshold_km`.
+pub fn screen_conjunctions(
+    objects: &[TrackedObject],
+    start_unix: i64,
+    days: f64,
+    threshold_km: f64,
+) -> Vec<Conjunction> {
+    let steps = ((days * 86400.0) as i64 / COARSE_STEP_SECS).max(1) as usize;
+
+    // Sample every object once; pairs then only compare cached positions
+    let samples: Vec<Vec<Option<[f64; 3]>>> = objects
+        .iter()
+        .map(|obj| {
+            (0..=steps)
+                .map(|i| {
+                    let t = (start_unix + i as i64 * COARSE_STEP_SECS) as f64;
+                    obj.propagator.state_at(t).map(|(pos, _)| pos)
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut conjunctions = Vec::new();
+
+    for a in 0..objects.len() {
+        for b in (a + 1)..objects.len() {
+            let (primary, secondary) = (&objects[a], &objects[b]);
+            if !shells_overlap(primary, secondary, threshold_km) {
+                continue;
+            }
+
+            let distances: Vec<Option<f64>> = samples[a]
+                .iter()
+                .zip(&samples[b])
+                .map(|(pa, pb)| Some(distance(&(*pa)?, &(*pb)?)))
+                .collect();
+
+            for i in 1..steps {
+                let (Some(before), Some(here), Some(after)) =
+                    (distances[i - 1], distances[i], distances[i + 1])
+                else {
+                    continue;
+                };
+                if !(here < before && here <= after) {
+                    continue;
+                }
+
+                let t0 = (start_unix + (i as i64 - 1) * COARSE_STEP_SECS) as f64;
+                let t1 = (start_unix + (i as i64 + 1) * COARSE_STEP_SECS) as f64;
+
+                // Skip minima that cannot reach the threshold even at maximum closing speed
+                if let Some(v_rel) = relative_speed(primary, secondary, t0 + COARSE_STEP_SECS as f64) {
+                    if here - v_rel * COARSE_STEP_SECS as f64 > threshold_km {
+                        continue;
+                    }
+                }
+
+                if let Some(c) = refine_approach(primary, secondary, t0, t1) {
+                    if c.miss_distance_km <= threshold_km {
+                        conjunctions.push(c);
+                    }
+                }
+            }
+        }
+    }
+
+    conjunctions.sort_by_key(|c| c.tca);
+    conjunctions
+}
+
+/// Apogee/perigee filter: two orbits can only meet if their altitude shells overlap.
+fn shells_overlap(a: &TrackedObject, b: &TrackedObject, threshold_km: f64) -> bool {
+    let (perigee_a, apogee_a) = a.propagator.shell_km();
+    let (perigee_b, apogee_b) = b.propagator.shell_km();
+    let gap = perigee_a.max(perigee_b) - apogee_a.min(apogee_b);
+    gap <= threshold_km + SHELL_PAD_KM
+}
+
+/// Golden-section search for the time of closest approach within [t0, t1].
+fn refine_approach(
+    primary: &TrackedObject,
+    secondary: &TrackedObject,
+    t0: f64,
+    t1: f64,
+) -> Option<Conjunction> {
+    let separation = |t: f64| -> Option<f64> {
+        let (pa, _) = primary.propagator.state_at(t)?;
+        let (pb, _) = secondary.propagator.state_at(t)?;
+        Some(distance(&pa, &pb))
+    };
+    let tca = golden_section_min(|t| separation(t).unwrap_or(f64::INFINITY), t0, t1, 1e-3);
+
+    let (pa, va) = primary.propagator.state_at(tca)?;
+    let (pb, vb) = secondary.propagator.state_at(tca)?;
+
+    Some(Conjunction {
+        // The pair alone; TCA drifts between screenings, so notifications
+        // match a repeat of the same event by a tolerance around it instead
+        id: format!("{}:{}", primary.id, secondary.id),
+        primary_id: primary.id.clone(),
+        primary_name: primary.name.clone(),
+        secondary_id: secondary.id.clone(),
+        secondary_name: secondary.name.clone(),
+        tca: tca.round() as i64,
+        miss_distance_km: distance(&pa, &pb),
+        relative_velocity_kms: distance(&va, &vb),
+    })
+}
+
+fn relative_speed(a: &TrackedObject, b: &TrackedObject, t: f64) -> Option<f64> {
+    let (_, va) = a.propagator.state_at(t)?;
+    let (_, vb) = b.propagator.state_at(t)?;
+    Some(distance(&va, &vb))
+}
+
+fn golden_section_min<F: Fn(f64) -> f64>(f: F, mut lo: f64, mut hi: f64, tolerance: f64) -> f64 {
+    let ratio = (5.0_f64.sqrt() - 1.0) / 2.0;
+    let mut x1 = hi - ratio * (hi - lo);
+    let mut x2 = lo + ratio * (hi - lo);
+    let mut f1 = f(x1);
+    let mut f2 = f(x2);
+
+    while hi - lo > tolerance {
+        if f1 < f2 {
+            hi = x2;
+            x2 = x1;
+            f2 = f1;
+            x1 = hi - ratio * (hi - lo);
+            f1 = f(x1);
+        } else {
+            lo = x1;
+            x1 = x2;
+            f1 = f2;
+            x2 = lo + ratio * (hi - lo);
+            f2 = f(x2);
+        }
+    }
+
+    (lo + hi) / 2.0
+}
+
+fn distance(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    let dx = a[0] - b[0];
+    let dy = a[1] - b[1];
+    let dz = a[2] - b[2];
+    (dx * dx + dy * dy + dz * dz).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn golden_section_finds_parabola_minimum() {
+        let t = golden_section_min(|x| (x - 37.25).powi(2) + 4.0, 0.0, 120.0, 1e-4);
+        assert!((t - 37.25).abs() < 1e-3);
+    }
+
+    #[test]
+    fn shells_far_apart_are_filtered() {
+        let iss = TrackedObject {
+            id: "sat-25544".into(),
+            name: "ISS".into(),
+            propagator: Propagator::from_tle(
+                "ISS",
+                "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927",
+                "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537",
+            )
+            .expect("valid TLE"),
+        };
+        let gps = TrackedObject {
+            id: "sat-24876".into(),
+            name: "GPS BIIR-2".into(),
+            propagator: Propagator::from_tle(
+                "GPS BIIR-2",
+                "1 24876U 97035A   08264.19755256  .00000023  00000-0  10000-3 0  6368",
+                "2 24876  55.4839 127.2356 0036914  74.2359 286.2148  2.00566297 82044",
+            )
+            .expect("valid TLE"),
+        };
+        assert!(!shells_overlap(&iss, &gps, 10.0));
+        assert!(shells_overlap(&iss, &iss, 10.0));
+    }
+}
@@ -0,0 +1,177 @@
+/// Altitude (km) below which an object is treated as reentered.
+pub const REENTRY_ALTITUDE_KM: f64 = 120.0;
+
+/// Effective atmospheric density scale height (km) for low Earth orbit.
+const SCALE_HEIGHT_KM: f64 = 50.0;
+
+/// Shortest history span (days) a regression fit is trusted over.
+const MIN_HISTORY_SPAN_DAYS: f64 = 0.5;
+
+/// Semi-major axis change (km) beyond the drag trend that counts as a maneuver.
+/// Well above TLE fitting noise, well below a typical ISS reboost (1-2 km).
+pub const MANEUVER_THRESHOLD_KM: f64 = 0.4;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ElementSample {
+    pub epoch_unix: f64,
+    pub perigee_km: f64,
+    pub apogee_km: f64,
+}
+
+/// Mean-altitude change (km/day, negative when decaying). Uses a least-squares
+/// fit over stored element sets when they span enough time, otherwise the
+/// TLE's own first derivative of mean motion (TLE field value, i.e. ndot/2).
+pub fn decay_rate_km_per_day(
+    history: &[ElementSample],
+    semi_major_axis_km: f64,
+    mean_motion_rev_day: f64,
+    mean_motion_dot_half: f64,
+) -> f64 {
+    if let Some(rate) = fitted_rate(history) {
+        return rate;
+    }
+
+    if mean_motion_rev_day <= 0.0 {
+        return 0.0;
+    }
+    // da/dt = -(2/3) * a * ndot / n
+    -(2.0 / 3.0) * semi_major_axis_km * (2.0 * mean_motion_dot_half) / mean_motion_rev_day
+}
+
+fn fitted_rate(history: &[ElementSample]) -> Option<f64> {
+    if history.len() < 2 {
+        return None;
+    }
+
+    let first = history
+        .iter()
+        .map(|s| s.epoch_unix)
+        .fold(f64::INFINITY, f64::min);
+    let last = history
+        .iter()
+        .map(|s| s.epoch_unix)
+        .fold(f64::NEG_INFINITY, f64::max);
+    if (last - first) / 86400.0 < MIN_HISTORY_SPAN_DAYS {
+        return None;
+    }
+
+    let points: Vec<(f64, f64)> = history
+        .iter()
+        .map(|s| {
+            (
+                (s.epoch_unix - first) / 86400.0,
+                mean_altitude(s),
+            )
+        })
+        .collect();
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let sxx: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    let sxy: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+
+    if sxx <= 0.0 {
+        return None;
+    }
+    Some(sxy / sxx)
+}
+
+/// Days until perigee falls to [`REENTRY_ALTITUDE_KM`], assuming the decay rate
+/// grows exponentially as the orbit sinks into denser atmosphere.
+pub fn days_to_reentry(perigee_km: f64, rate_km_per_day: f64) -> Option<f64> {
+    if !rate_km_per_day.is_finite() || rate_km_per_day >= 0.0 {
+        return None;
+    }
+    let drop = perigee_km - REENTRY_ALTITUDE_KM;
+    if drop <= 0.0 {
+        return Some(0.0);
+    }
+
+    // dh/dt = r * exp((h0 - h) / H)  =>  t = H / |r| * (1 - exp(-drop / H))
+    Some(SCALE_HEIGHT_KM / rate_km_per_day.abs() * (1.0 - (-drop / SCALE_HEIGHT_KM).exp()))
+}
+
+/// Element-set pairs whose semi-major axis changed by more than the
+/// surrounding drag trend explains. Returns (epoch of the later set, jump km).
+pub fn detect_maneuvers(history: &[ElementSample]) -> Vec<(f64, f64)> {
+    let steps: Vec<(f64, f64, f64)> = history
+        .windows(2)
+        .filter(|w| w[1].epoch_unix > w[0].epoch_unix)
+        .map(|w| {
+            let dt_days = (w[1].epoch_unix - w[0].epoch_unix) / 86400.0;
+            let delta = mean_altitude(&w[1]) - mean_altitude(&w[0]);
+            (w[1].epoch_unix, delta, dt_days)
+        })
+        .collect();
+    if steps.is_empty() {
+        return Vec::new();
+    }
+
+    // Median per-day drift is robust to the handful of burns in the history
+    let mut rates: Vec<f64> = steps.iter().map(|&(_, delta, dt)| delta / dt).collect();
+    rates.sort_by(|a, b| a.total_cmp(b));
+    let trend = rates[rates.len() / 2];
+
+    steps
+        .into_iter()
+        .map(|(epoch, delta, dt)| (epoch, delta - trend * dt))
+        .filter(|&(_, jump)| jump.abs() > MANEUVER_THRESHOLD_KM)
+        .collect()
+}
+
+fn mean_altitude(sample: &ElementSample) -> f64 {
+    (sample.perigee_km + sample.apogee_km) / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(day: f64, mean_alt: f64) -> ElementSample {
+        ElementSample {
+            epoch_unix: day * 86400.0,
+            perigee_km: mean_alt - 5.0,
+            apogee_km: mean_alt + 5.0,
+        }
+    }
+
+    #[test]
+    fn fitted_rate_follows_history() {
+        let history = [sample(0.0, 300.0), sample(1.0, 298.0), sample(2.0, 296.0)];
+        let rate = decay_rate_km_per_day(&history, 6670.0, 16.0, 0.0);
+        assert!((rate + 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn short_history_falls_back_to_mean_motion_derivative() {
+        let history = [sample(0.0, 300.0)];
+        let rate = decay_rate_km_per_day(&history, 6670.0, 16.0, 0.001);
+        assert!(rate < 0.0);
+    }
+
+    #[test]
+    fn reentry_time_shrinks_with_faster_decay() {
+        assert_eq!(days_to_reentry(300.0, 0.1), None);
+        let slow = days_to_reentry(200.0, -1.0).unwrap();
+        let fast = days_to_reentry(200.0, -4.0).unwrap();
+        assert!(fast < slow);
+        assert!((fast - 12.5 * (1.0 - (-1.6_f64).exp())).abs() < 1e-9);
+        assert_eq!(days_to_reentry(110.0, -1.0), Some(0.0));
+    }
+
+    #[test]
+    fn reboost_stands_out_from_drag_trend() {
+        let history = [
+            sample(0.0, 420.0),
+            sample(1.0, 419.9),
+            sample(2.0, 419.8),
+            sample(3.0, 421.5),
+            sample(4.0, 421.4),
+            sample(6.0, 421.2),
+        ];
+        let maneuvers = detect_maneuvers(&history);
+        assert_eq!(maneuvers.len(), 1);
+        assert_eq!(maneuvers[0].0, 3.0 * 86400.0);
+        assert!((maneuvers[0].1 - 1.8).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,160 @@
+use crate::calculations::orbit::{great_circle_km, initial_bearing};
+use crate::models::geocode::{Place, PlaceLabel};
+use crate::notifications::azimuth_to_cardinal;
+use std::sync::LazyLock;
+
+const CITIES_CSV: &str = include_str!("../../cities.csv");
+
+/// Within this distance a point is labelled with the city name alone.
+const CITY_RADIUS_KM: f64 = 25.0;
+
+/// Beyond this distance the nearest city says nothing useful about a location.
+const MAX_LABEL_DISTANCE_KM: f64 = 2500.0;
+
+static CITIES: LazyLock<Vec<Place>> = LazyLock::new(|| parse_cities(CITIES_CSV));
+
+fn parse_cities(csv: &str) -> Vec<Place> {
+    csv.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(',').collect();
+            if fields.len() < 6 {
+                return None;
+            }
+            Some(Place {
+                name: fields[0].to_string(),
+                country_code: fields[1].to_string(),
+                country: fields[2].to_string(),
+                latitude: fields[3].parse().ok()?,
+                longitude: fields[4].parse().ok()?,
+                population: fields[5].parse().ok(),
+                source: "gazetteer".to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Cities matching a free-text query such as "catania" or "san jose, costa rica",
+/// best matches first and larger cities breaking ties.
+pub fn search(query: &str, limit: usize) -> Vec<Place> {
+    let mut parts = query.splitn(2, ',');
+    let name = parts.next().unwrap_or_default().trim().to_lowercase();
+    let country = parts.next().map(|c| c.trim().to_lowercase());
+    if name.is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches: Vec<(u8, &Place)> = CITIES
+        .iter()
+        .filter(|place| match &country {
+            Some(c) => {
+                place.country.to_lowercase().starts_with(c.as_str())
+                    || place.country_code.eq_ignore_ascii_case(c)
+            }
+            None => true,
+        })
+        .filter_map(|place| {
+            let candidate = place.name.to_lowercase();
+            let rank = if candidate == name {
+                0
+            } else if candidate.starts_with(&name) {
+                1
+            } else if candidate.contains(&name) {
+                2
+            } else {
+                return None;
+            };
+            Some((rank, place))
+        })
+        .collect();
+
+    matches.sort_by(|a, b| {
+        a.0.cmp(&b.0).then(
+            b.1.population
+                .unwrap_or(0)
+                .cmp(&a.1.population.unwrap_or(0)),
+        )
+    });
+    matches
+        .into_iter()
+        .take(limit)
+        .map(|(_, place)| place.clone())
+        .collect()
+}
+
+pub fn nearest(lat: f64, lon: f64) -> Option<(&'static Place, f64)> {
+    CITIES
+        .iter()
+        .map(|place| {
+            let distance = great_circle_km(place.latitude, place.longitude, lat, lon);
+            (place, distance)
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Human-readable label relative to the nearest city, USGS style.
+pub fn describe(lat: f64, lon: f64) -> Option<PlaceLabel> {
+    let (place, distance_km) = nearest(lat, lon)?;
+    if distance_km > MAX_LABEL_DISTANCE_KM {
+        return None;
+    }
+
+    let city = format!("{}, {}", place.name, place.country);
+    let label = if distance_km <= CITY_RADIUS_KM {
+        city
+    } else {
+        let bearing = initial_bearing(place.latitude, place.longitude, lat, lon).to_degrees();
+        format!(
+            "{:.0} km {} of {}",
+            distance_km,
+            azimuth_to_cardinal(bearing),
+            city
+        )
+    };
+
+    Some(PlaceLabel {
+        label,
+        place: place.clone(),
+        distance_km,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_gazetteer_parses() {
+        assert!(CITIES.len() > 300);
+        assert!(CITIES
+            .iter()
+            .all(|p| p.latitude.abs() <= 90.0 && p.longitude.abs() <= 180.0));
+    }
+
+    #[test]
+    fn search_ranks_exact_matches_and_filters_by_country() {
+        let results = search("san jose", 5);
+        assert_eq!(results[0].country_code, "US");
+
+        let costa_rica = search("San Jose, Costa Rica", 5);
+        assert_eq!(costa_rica.len(), 1);
+        assert_eq!(costa_rica[0].country_code, "CR");
+
+        assert!(search("  ", 5).is_empty());
+    }
+
+    #[test]
+    fn describe_labels_relative_to_nearest_city() {
+        let label = describe(37.75, 15.0).unwrap();
+        assert_eq!(label.place.name, "Catania");
+        assert!(
+            label.label.ends_with("of Catania, Italy"),
+            "{}",
+            label.label
+        );
+
+        let downtown = describe(35.69, 139.69).unwrap();
+        assert_eq!(downtown.label, "Tokyo, Japan");
+    }
+}
@@ -0,0 +1,699 @@
+use crate::calculations::orbit::{great_circle_km, initial_bearing};
+use crate::models::watchlist::WatchShape;
+
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// Whether a point lies inside (or within the buffer of) a watch shape.
+pub fn contains(shape: &WatchShape, lat: f64, lon: f64) -> bool {
+    match shape {
+        WatchShape::Circle {
+            latitude,
+            longitude,
+            radius_km,
+        } => great_circle_km(*latitude, *longitude, lat, lon) <= *radius_km,
+        WatchShape::Polygon { rings } => polygon_contains(rings, lat, lon),
+        WatchShape::MultiPolygon { polygons } => polygons
+            .iter()
+            .any(|rings| polygon_contains(rings, lat, lon)),
+        WatchShape::BoundingBox {
+            min_lon,
+            min_lat,
+            max_lon,
+            max_lat,
+        } => {
+            let in_lat = lat >= *min_lat && lat <= *max_lat;
+            // min_lon > max_lon means the box wraps across the antimeridian
+            let in_lon = if min_lon <= max_lon {
+                lon >= *min_lon && lon <= *max_lon
+            } else {
+                lon >= *min_lon || lon <= *max_lon
+            };
+            in_lat && in_lon
+        }
+        WatchShape::Corridor { line, buffer_km } => {
+            distance_to_polyline_km(line, lat, lon).is_some_and(|d| d <= *buffer_km)
+        }
+    }
+}
+
+/// Representative centre and enclosing radius (km), used for map labels and
+/// the legacy point/radius fields.
+pub fn center_and_radius(shape: &WatchShape) -> (f64, f64, f64) {
+    let points: Vec<[f64; 2]> = match shape {
+        WatchShape::Circle {
+            latitude,
+            longitude,
+            radius_km,
+        } => return (*latitude, *longitude, *radius_km),
+        WatchShape::Polygon { rings } => rings.first().cloned().unwrap_or_default(),
+        WatchShape::MultiPolygon { polygons } => polygons
+            .iter()
+            .filter_map(|rings| rings.first())
+            .flatten()
+            .copied()
+            .collect(),
+        WatchShape::BoundingBox {
+            min_lon,
+            min_lat,
+            max_lon,
+            max_lat,
+        } => {
+            let max_lon = if max_lon < min_lon {
+                max_lon + 360.0
+            } else {
+                *max_lon
+            };
+            vec![
+                [*min_lon, *min_lat],
+                [max_lon, *min_lat],
+                [max_lon, *max_lat],
+                [*min_lon, *max_lat],
+            ]
+        }
+        WatchShape::Corridor { line, .. } => line.clone(),
+    };
+    if points.is_empty() {
+        return (0.0, 0.0, 0.0);
+    }
+
+    let unwrapped = unwrap_longitudes(&points);
+    let n = unwrapped.len() as f64;
+    let lat = unwrapped.iter().map(|p| p[1]).sum::<f64>() / n;
+    let lon = normalize_lon(unwrapped.iter().map(|p| p[0]).sum::<f64>() / n);
+
+    let buffer = match shape {
+        WatchShape::Corridor { buffer_km, .. } => *buffer_km,
+        _ => 0.0,
+    };
+    let radius = points
+        .iter()
+        .map(|p| great_circle_km(lat, lon, p[1], p[0]))
+        .fold(0.0, f64::max)
+        + buffer;
+    (lat, lon, radius)
+}
+
+/// Whether two shapes share any ground: either one holds a point of the
+/// other, or their outlines come within the shapes' buffers of each other,
+/// which catches a corridor crossing a polygon or a circle overlapping an edge.
+pub fn overlaps(a: &WatchShape, b: &WatchShape) -> bool {
+    let (a_lat, a_lon, a_radius) = center_and_radius(a);
+    let (b_lat, b_lon, b_radius) = center_and_radius(b);
+    // Enclosing circles with a little slack for edges drawn in lon/lat
+    if great_circle_km(a_lat, a_lon, b_lat, b_lon) > (a_radius + b_radius) * 1.01 + 1.0 {
+        return false;
+    }
+
+    let inside = |points: Vec<[f64; 2]>, shape: &WatchShape| {
+        points.iter().any(|p| contains(shape, p[1], p[0]))
+    };
+    if inside(sample_points(a), b) || inside(sample_points(b), a) {
+        return true;
+    }
+
+    let (a_edges, a_buffer) = outline(a);
+    let (b_edges, b_buffer) = outline(b);
+    a_edges.iter().any(|ea| {
+        b_edges
+            .iter()
+            .any(|eb| segment_distance_km(*ea, *eb) <= a_buffer + b_buffer)
+    })
+}
+
+/// Outline segments as [lon, lat] pairs, and the buffer (km) around them. A
+/// circle is a single zero-length segment buffered by its radius.
+fn outline(shape: &WatchShape) -> (Vec<[[f64; 2]; 2]>, f64) {
+    fn ring_edges(ring: &[[f64; 2]]) -> impl Iterator<Item = [[f64; 2]; 2]> + '_ {
+        // Includes the closing edge, which is zero-length for a closed ring
+        ring.windows(2).map(|w| [w[0], w[1]]).chain(
+            ring.first()
+                .zip(ring.last())
+                .map(|(first, last)| [*last, *first]),
+        )
+    }
+
+    match shape {
+        WatchShape::Circle {
+            latitude,
+            longitude,
+            radius_km,
+        } => {
+            let centre = [*longitude, *latitude];
+            (vec![[centre, centre]], *radius_km)
+        }
+        WatchShape::Polygon { rings } => (rings.iter().flat_map(|r| ring_edges(r)).collect(), 0.0),
+        WatchShape::MultiPolygon { polygons } => (
+            polygons
+                .iter()
+                .flatten()
+                .flat_map(|r| ring_edges(r))
+                .collect(),
+            0.0,
+        ),
+        WatchShape::BoundingBox {
+            min_lon,
+            min_lat,
+            max_lon,
+            max_lat,
+        } => {
+            let corners = [
+                [*min_lon, *min_lat],
+                [*max_lon, *min_lat],
+                [*max_lon, *max_lat],
+                [*min_lon, *max_lat],
+            ];
+            (ring_edges(&corners).collect(), 0.0)
+        }
+        WatchShape::Corridor { line, buffer_km } => {
+            (line.windows(2).map(|w| [w[0], w[1]]).collect(), *buffer_km)
+        }
+    }
+}
+
+/// Shortest distance (km) between two [lon, lat] segments: zero when they
+/// cross, otherwise the closest an endpoint of one comes to the other.
+fn segment_distance_km(a: [[f64; 2]; 2], b: [[f64; 2]; 2]) -> f64 {
+    if segments_cross(a, b) {
+        return 0.0;
+    }
+    [
+        distance_to_segment_km(a[0], a[1], b[0][1], b[0][0]),
+        distance_to_segment_km(a[0], a[1], b[1][1], b[1][0]),
+        distance_to_segment_km(b[0], b[1], a[0][1], a[0][0]),
+        distance_to_segment_km(b[0], b[1], a[1][1], a[1][0]),
+    ]
+    .into_iter()
+    .fold(f64::INFINITY, f64::min)
+}
+
+/// Whether the segments properly cross, treating lon/lat as planar after
+/// unwrapping them together so segments near the antimeridian compare.
+fn segments_cross(a: [[f64; 2]; 2], b: [[f64; 2]; 2]) -> bool {
+    let p = unwrap_longitudes(&[a[0], a[1], b[0], b[1]]);
+    let orient = |o: [f64; 2], q: [f64; 2], r: [f64; 2]| {
+        (q[0] - o[0]) * (r[1] - o[1]) - (q[1] - o[1]) * (r[0] - o[0])
+    };
+    // Each segment's endpoints lie strictly on opposite sides of the other
+    orient(p[2], p[3], p[0]) * orient(p[2], p[3], p[1]) < 0.0
+        && orient(p[0], p[1], p[2]) * orient(p[0], p[1], p[3]) < 0.0
+}
+
+/// Centre plus outline vertices, as [lon, lat].
+fn sample_points(shape: &WatchShape) -> Vec<[f64; 2]> {
+    let (lat, lon, _) = center_and_radius(shape);
+    let mut points = vec![[lon, lat]];
+    match shape {
+        WatchShape::Circle { .. } => {}
+        WatchShape::Polygon { rings } => points.extend(rings.iter().take(1).flatten()),
+        WatchShape::MultiPolygon { polygons } => {
+            points.extend(polygons.iter().filter_map(|rings| rings.first()).flatten())
+        }
+        WatchShape::BoundingBox {
+            min_lon,
+            min_lat,
+            max_lon,
+            max_lat,
+        } => points.extend([
+            [*min_lon, *min_lat],
+            [*max_lon, *min_lat],
+            [*max_lon, *max_lat],
+            [*min_lon, *max_lat],
+        ]),
+        WatchShape::Corridor { line, .. } => points.extend(line),
+    }
+    points
+}
+
+pub fn validate_shape(shape: &WatchShape) -> Result<(), String> {
+    let valid_point = |p: &[f64; 2]| {
+        p[0].is_finite() && p[1].is_finite() && p[0].abs() <= 180.0 && p[1].abs() <= 90.0
+    };
+    let validate_rings = |rings: &Vec<Vec<[f64; 2]>>| -> Result<(), String> {
+        if rings.is_empty() {
+            return Err("Polygon needs an outer ring".to_string());
+        }
+        for ring in rings {
+            if ring.len() < 3 {
+                return Err("Polygon rings need at least 3 points".to_string());
+            }
+            if !ring.iter().all(valid_point) {
+                return Err("Polygon coordinates out of range".to_string());
+            }
+        }
+        Ok(())
+    };
+
+    match shape {
+        WatchShape::Circle {
+            latitude,
+            longitude,
+            radius_km,
+        } => {
+            if !valid_point(&[*longitude, *latitude]) {
+                return Err("Coordinates out of range".to_string());
+            }
+            if !radius_km.is_finite() || *radius_km <= 0.0 || *radius_km > 20000.0 {
+                return Err("Radius must be between 0 and 20000 km".to_string());
+            }
+        }
+        WatchShape::Polygon { rings } => validate_rings(rings)?,
+        WatchShape::MultiPolygon { polygons } => {
+            if polygons.is_empty() {
+                return Err("MultiPolygon needs at least one polygon".to_string());
+            }
+            for rings in polygons {
+                validate_rings(rings)?;
+            }
+        }
+        WatchShape::BoundingBox {
+            min_lon,
+            min_lat,
+            max_lon,
+            max_lat,
+        } => {
+            if !valid_point(&[*min_lon, *min_lat]) || !valid_point(&[*max_lon, *max_lat]) {
+                return Err("Bounding box coordinates out of range".to_string());
+            }
+            if min_lat >= max_lat {
+                return Err("Bounding box south edge must be below its north edge".to_string());
+            }
+        }
+        WatchShape::Corridor { line, buffer_km } => {
+            if line.len() < 2 {
+                return Err("Corridor line needs at least 2 points".to_string());
+            }
+            if !line.iter().all(valid_point) {
+                return Err("Corridor coordinates out of range".to_string());
+            }
+            if !buffer_km.is_finite() || *buffer_km <= 0.0 || *buffer_km > 5000.0 {
+                return Err("Corridor buffer must be between 0 and 5000 km".to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Watch shapes from a GeoJSON FeatureCollection, Feature or bare geometry,
+/// paired with the feature's name when it has one. Lines become corridors of
+/// `buffer_km` and points become circles of `radius_km`.
+pub fn shapes_from_geojson(
+    value: &serde_json::Value,
+    buffer_km: f64,
+    radius_km: f64,
+) -> Result<Vec<(Option<String>, WatchShape)>, String> {
+    let mut shapes = Vec::new();
+    match value.get("type").and_then(|t| t.as_str()) {
+        Some("FeatureCollection") => {
+            let features = value
+                .get("features")
+                .and_then(|f| f.as_array())
+                .ok_or("FeatureCollection has no features")?;
+            for feature in features {
+                collect_feature(feature, buffer_km, radius_km, &mut shapes)?;
+            }
+        }
+        Some("Feature") => collect_feature(value, buffer_km, radius_km, &mut shapes)?,
+        Some(_) => collect_geometry(value, None, buffer_km, radius_km, &mut shapes)?,
+        None => return Err("Not a GeoJSON object".to_string()),
+    }
+
+    if shapes.is_empty() {
+        return Err("No polygon, line or point geometries found".to_string());
+    }
+    Ok(shapes)
+}
+
+fn collect_feature(
+    feature: &serde_json::Value,
+    buffer_km: f64,
+    radius_km: f64,
+    shapes: &mut Vec<(Option<String>, WatchShape)>,
+) -> Result<(), String> {
+    let name = feature.get("properties").and_then(|props| {
+        ["name", "NAME", "ADMIN", "title"]
+            .iter()
+            .find_map(|key| props.get(*key).and_then(|v| v.as_str()))
+            .map(String::from)
+    });
+    match feature.get("geometry") {
+        Some(geometry) if !geometry.is_null() => {
+            collect_geometry(geometry, name, buffer_km, radius_km, shapes)
+        }
+        _ => Ok(()),
+    }
+}
+
+fn collect_geometry(
+    geometry: &serde_json::Value,
+    name: Option<String>,
+    buffer_km: f64,
+    radius_km: f64,
+    shapes: &mut Vec<(Option<String>, WatchShape)>,
+) -> Result<(), String> {
+    let coords = geometry.get("coordinates");
+    let shape = match geometry.get("type").and_then(|t| t.as_str()) {
+        Some("Polygon") => WatchShape::Polygon {
+            rings: parse_rings(coords).ok_or("Invalid Polygon coordinates")?,
+        },
+        Some("MultiPolygon") => WatchShape::MultiPolygon {
+            polygons: coords
+                .and_then(|c| c.as_array())
+                .and_then(|polys| polys.iter().map(|p| parse_rings(Some(p))).collect())
+                .ok_or("Invalid MultiPolygon coordinates")?,
+        },
+        Some("LineString") => WatchShape::Corridor {
+            line: parse_positions(coords).ok_or("Invalid LineString coordinates")?,
+            buffer_km,
+        },
+        Some("Point") => {
+            let [lon, lat] = coords
+                .and_then(parse_position)
+                .ok_or("Invalid Point coordinates")?;
+            WatchShape::Circle {
+                latitude: lat,
+                longitude: lon,
+                radius_km,
+            }
+        }
+        Some("GeometryCollection") => {
+            let members = geometry
+                .get("geometries")
+                .and_then(|g| g.as_array())
+                .ok_or("GeometryCollection has no geometries")?;
+            for member in members {
+                collect_geometry(member, name.clone(), buffer_km, radius_km, shapes)?;
+            }
+            return Ok(());
+        }
+        Some(other) => {
+            log::warn!("Skipping unsupported GeoJSON geometry: {}", other);
+            return Ok(());
+        }
+        None => return Err("Geometry without a type".to_string()),
+    };
+
+    validate_shape(&shape)?;
+    shapes.push((name, shape));
+    Ok(())
+}
+
+fn parse_position(value: &serde_json::Value) -> Option<[f64; 2]> {
+    let pair = value.as_array()?;
+    Some([pair.first()?.as_f64()?, pair.get(1)?.as_f64()?])
+}
+
+fn parse_positions(value: Option<&serde_json::Value>) -> Option<Vec<[f64; 2]>> {
+    value?.as_array()?.iter().map(parse_position).collect()
+}
+
+fn parse_rings(value: Option<&serde_json::Value>) -> Option<Vec<Vec<[f64; 2]>>> {
+    value?
+        .as_array()?
+        .iter()
+        .map(|ring| parse_positions(Some(ring)))
+        .collect()
+}
+
+/// Even-odd test against the outer ring minus any holes. Rings are [lon, lat].
+fn polygon_contains(rings: &[Vec<[f64; 2]>], lat: f64, lon: f64) -> bool {
+    let Some(outer) = rings.first() else {
+        return false;
+    };
+    ring_contains(outer, lat, lon) && !rings[1..].iter().any(|hole| ring_contains(hole, lat, lon))
+}
+
+fn ring_contains(ring: &[[f64; 2]], lat: f64, lon: f64) -> bool {
+    // Unwrap so edges crossing the antimeridian stay short, then test the
+    // point at each 360-degree shift the unwrapped ring may cover
+    let ring = unwrap_longitudes(ring);
+    [lon, lon + 360.0, lon - 360.0]
+        .iter()
+        .any(|&x| crossing_test(&ring, x, lat))
+}
+
+fn crossing_test(ring: &[[f64; 2]], x: f64, y: f64) -> bool {
+    let mut inside = false;
+    let n = ring.len();
+    let mut j = n - 1;
+    for i in 0..n {
+        let (xi, yi) = (ring[i][0], ring[i][1]);
+        let (xj, yj) = (ring[j][0], ring[j][1]);
+        if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+fn unwrap_longitudes(points: &[[f64; 2]]) -> Vec<[f64; 2]> {
+    let mut out: Vec<[f64; 2]> = Vec::with_capacity(points.len());
+    for p in points {
+        let lon = match out.last() {
+            Some(prev) => {
+                let mut lon = p[0];
+                while lon - prev[0] > 180.0 {
+                    lon -= 360.0;
+                }
+                while lon - prev[0] < -180.0 {
+                    lon += 360.0;
+                }
+                lon
+            }
+            None => p[0],
+        };
+        out.push([lon, p[1]]);
+    }
+    out
+}
+
+fn normalize_lon(lon: f64) -> f64 {
+    ((lon + 180.0) % 360.0 + 360.0) % 360.0 - 180.0
+}
+
+/// Shortest great-circle distance (km) from a point to a [lon, lat] polyline.
+fn distance_to_polyline_km(line: &[[f64; 2]], lat: f64, lon: f64) -> Option<f64> {
+    line.windows(2)
+        .map(|seg| distance_to_segment_km(seg[0], seg[1], lat, lon))
+        .min_by(|a, b| a.total_cmp(b))
+}
+
+fn distance_to_segment_km(a: [f64; 2], b: [f64; 2], lat: f64, lon: f64) -> f64 {
+    let to_a = great_circle_km(a[1], a[0], lat, lon);
+    let to_b = great_circle_km(b[1], b[0], lat, lon);
+    let length = great_circle_km(a[1], a[0], b[1], b[0]);
+    if length < 1e-6 {
+        return to_a;
+    }
+
+    // Cross-track and along-track distances relative to the great circle through a and b
+    let d13 = to_a / EARTH_RADIUS_KM;
+    let theta13 = initial_bearing(a[1], a[0], lat, lon);
+    let theta12 = initial_bearing(a[1], a[0], b[1], b[0]);
+    let cross = (d13.sin() * (theta13 - theta12).sin())
+        .clamp(-1.0, 1.0)
+        .asin();
+    let along = (d13.cos() / cross.cos()).clamp(-1.0, 1.0).acos() * EARTH_RADIUS_KM;
+
+    if (theta13 - theta12).cos() > 0.0 && along <= length {
+        (cross * EARTH_RADIUS_KM).abs()
+    } else {
+        to_a.min(to_b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(min_lon: f64, min_lat: f64, max_lon: f64, max_lat: f64) -> Vec<[f64; 2]> {
+        vec![
+            [min_lon, min_lat],
+            [max_lon, min_lat],
+            [max_lon, max_lat],
+            [min_lon, max_lat],
+            [min_lon, min_lat],
+        ]
+    }
+
+    #[test]
+    fn polygon_with_hole() {
+        let shape = WatchShape::Polygon {
+            rings: vec![square(0.0, 0.0, 10.0, 10.0), square(4.0, 4.0, 6.0, 6.0)],
+        };
+        assert!(contains(&shape, 2.0, 2.0));
+        assert!(!contains(&shape, 5.0, 5.0));
+        assert!(!contains(&shape, 12.0, 5.0));
+    }
+
+    #[test]
+    fn polygon_across_antimeridian() {
+        // Fiji-ish box written the way GeoJSON exporters often do, jumping from 178 to -178
+        let shape = WatchShape::Polygon {
+            rings: vec![vec![
+                [176.0, -20.0],
+                [-178.0, -20.0],
+                [-178.0, -15.0],
+                [176.0, -15.0],
+                [176.0, -20.0],
+            ]],
+        };
+        assert!(contains(&shape, -17.5, 179.5));
+        assert!(contains(&shape, -17.5, -179.5));
+        assert!(!contains(&shape, -17.5, 0.0));
+        assert!(!contains(&shape, -17.5, 170.0));
+    }
+
+    #[test]
+    fn bounding_box_wraps() {
+        let shape = WatchShape::BoundingBox {
+            min_lon: 170.0,
+            min_lat: -50.0,
+            max_lon: -170.0,
+            max_lat: -30.0,
+        };
+        assert!(contains(&shape, -40.0, 175.0));
+        assert!(contains(&shape, -40.0, -175.0));
+        assert!(!contains(&shape, -40.0, 0.0));
+    }
+
+    #[test]
+    fn corridor_buffers_polyline() {
+        // Roughly along the equator from 0 to 10 degrees east
+        let shape = WatchShape::Corridor {
+            line: vec![[0.0, 0.0], [10.0, 0.0]],
+            buffer_km: 100.0,
+        };
+        assert!(contains(&shape, 0.5, 5.0)); // ~56 km off the line
+        assert!(!contains(&shape, 2.0, 5.0)); // ~222 km off the line
+        assert!(!contains(&shape, 0.0, 12.0)); // past the end
+    }
+
+    #[test]
+    fn center_of_wrapping_box_is_on_antimeridian_side() {
+        let shape = WatchShape::BoundingBox {
+            min_lon: 170.0,
+            min_lat: -50.0,
+            max_lon: -170.0,
+            max_lat: -30.0,
+        };
+        let (lat, lon, radius) = center_and_radius(&shape);
+        assert!((lat + 40.0).abs() < 1e-9);
+        assert!(lon.abs() > 179.0);
+        assert!(radius > 0.0);
+    }
+
+    #[test]
+    fn shapes_overlap_either_way() {
+        let square = WatchShape::Polygon {
+            rings: vec![vec![
+                [-98.0, 34.0],
+                [-96.0, 34.0],
+                [-96.0, 36.0],
+                [-98.0, 36.0],
+                [-98.0, 34.0],
+            ]],
+        };
+        let small_circle = WatchShape::Circle {
+            latitude: 35.0,
+            longitude: -97.0,
+            radius_km: 5.0,
+        };
+        let big_circle = WatchShape::Circle {
+            latitude: 35.0,
+            longitude: -97.0,
+            radius_km: 1000.0,
+        };
+        let far = WatchShape::Circle {
+            latitude: 45.0,
+            longitude: 10.0,
+            radius_km: 50.0,
+        };
+        assert!(overlaps(&square, &small_circle));
+        assert!(overlaps(&big_circle, &square));
+        assert!(!overlaps(&square, &far));
+    }
+
+    #[test]
+    fn crossing_segments_have_zero_distance() {
+        let a = [[0.0, 0.0], [2.0, 2.0]];
+        let b = [[0.0, 2.0], [2.0, 0.0]];
+        assert!(segments_cross(a, b));
+        assert_eq!(segment_distance_km(a, b), 0.0);
+
+        // Parallel a degree of latitude apart
+        let c = [[0.0, 1.0], [2.0, 1.0]];
+        let d = [[0.0, 0.0], [2.0, 0.0]];
+        assert!(!segments_cross(c, d));
+        assert!((segment_distance_km(c, d) - 111.2).abs() < 1.0);
+    }
+
+    #[test]
+    fn corridor_crossing_polygon_overlaps() {
+        let square = WatchShape::Polygon {
+            rings: vec![square(0.0, 0.0, 2.0, 2.0)],
+        };
+        // Both ends well outside the square, the line passes straight through
+        let crossing = WatchShape::Corridor {
+            line: vec![[-3.0, 1.0], [5.0, 1.0]],
+            buffer_km: 10.0,
+        };
+        let beside = WatchShape::Corridor {
+            line: vec![[-3.0, 4.0], [5.0, 4.0]],
+            buffer_km: 10.0,
+        };
+        assert!(overlaps(&crossing, &square));
+        assert!(overlaps(&square, &crossing));
+        assert!(!overlaps(&beside, &square));
+    }
+
+    #[test]
+    fn circle_reaching_over_polygon_edge_overlaps() {
+        let square = WatchShape::Polygon {
+            rings: vec![square(0.0, 0.0, 2.0, 2.0)],
+        };
+        // Centre ~55 km east of the square's east edge
+        let reaching = WatchShape::Circle {
+            latitude: 1.0,
+            longitude: 2.5,
+            radius_km: 80.0,
+        };
+        let short = WatchShape::Circle {
+            latitude: 1.0,
+            longitude: 2.5,
+            radius_km: 30.0,
+        };
+        assert!(overlaps(&reaching, &square));
+        assert!(!overlaps(&short, &square));
+    }
+
+    #[test]
+    fn geojson_import_reads_named_features() {
+        let doc = serde_json::json!({
+            "type": "FeatureCollection",
+            "features": [
+                {
+                    "type": "Feature",
+                    "properties": { "name": "Box" },
+                    "geometry": {
+                        "type": "Polygon",
+                        "coordinates": [[[0, 0], [1, 0], [1, 1], [0, 1], [0, 0]]]
+                    }
+                },
+                {
+                    "type": "Feature",
+                    "properties": {},
+                    "geometry": { "type": "LineString", "coordinates": [[0, 0], [5, 5]] }
+                }
+            ]
+        });
+        let shapes = shapes_from_geojson(&doc, 50.0, 100.0).unwrap();
+        assert_eq!(shapes.len(), 2);
+        assert_eq!(shapes[0].0.as_deref(), Some("Box"));
+        assert!(matches!(shapes[1].1, WatchShape::Corridor { buffer_km, .. } if buffer_km == 50.0));
+
+        let bad =
+            serde_json::json!({ "type": "Polygon", "coordinates": [[[0, 0], [200, 0], [1, 1]]] });
+        assert!(shapes_from_geojson(&bad, 50.0, 100.0).is_err());
+    }
+}
@@ -1,2 +1,9 @@
+pub mod alert_policy;
+pub mod conjunction;
+pub mod decay;
+pub mod gazetteer;
+pub mod geometry;
 pub mod orbit;
+pub mod rule_engine;
 pub mod terminator;
+pub mod watch_rules;
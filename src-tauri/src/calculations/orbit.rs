@@ -1,11 +1,88 @@
-use crate::models::satellite::{OrbitTrack, PassPrediction, SatellitePosition};
+use crate::models::satellite::{
+    CoverageWindow, OrbitTrack, PassPrediction, SatelliteCoverage, SatellitePosition, SwathSample,
+};
 use chrono::{Datelike, Timelike};
 use sgp4::{Constants, Elements, MinutesSinceEpoch};
 use std::f64::consts::PI;
 
 const EARTH_RADIUS_KM: f64 = 6371.0;
+const EARTH_MU_KM3_S2: f64 = 398600.4418;
 const DEG: f64 = 180.0 / PI;
 
+/// Elevation (degrees) above which a satellite counts as in view of an observer.
+pub const MIN_ELEVATION_DEG: f64 = 5.0;
+
+/// Number of vertices used to draw a footprint circle.
+const FOOTPRINT_SEGMENTS: usize = 72;
+
+/// Upper bound on a pass's duration, used to look back for an AOS before now.
+const MAX_PASS_SECS: i64 = 1800;
+
+/// A parsed element set ready for repeated propagation.
+pub struct Propagator {
+    pub elements: Elements,
+    constants: Constants,
+    epoch_unix: f64,
+}
+
+impl Propagator {
+    pub fn from_tle(name: &str, line1: &str, line2: &str) -> Option<Self> {
+        let elements = Elements::from_tle(
+            Some(name.to_string()),
+            line1.as_bytes(),
+            line2.as_bytes(),
+        ).ok()?;
+        let constants = Constants::from_elements(&elements).ok()?;
+        let epoch_unix = tle_epoch_to_unix(&elements);
+
+        Some(Self {
+            elements,
+            constants,
+            epoch_unix,
+        })
+    }
+
+    pub fn epoch_unix(&self) -> f64 {
+        self.epoch_unix
+    }
+
+    /// TEME position (km) and velocity (km/s) at a unix time in seconds.
+    pub fn state_at(&self, timestamp_unix: f64) -> Option<([f64; 3], [f64; 3])> {
+        let minutes_since = (timestamp_unix - self.epoch_unix) / 60.0;
+        let prediction = self
+            .constants
+            .propagate(MinutesSinceEpoch(minutes_since))
+            .ok()?;
+        Some((prediction.position, prediction.velocity))
+    }
+
+    /// Sub-satellite latitude, longitude (degrees) and altitude (km) at a unix time.
+    pub fn geodetic_at(&self, timestamp_unix: i64) -> Option<(f64, f64, f64)> {
+        let (pos, _) = self.state_at(timestamp_unix as f64)?;
+        Some(eci_to_geodetic(pos[0], pos[1], pos[2], gmst_from_unix(timestamp_unix)))
+    }
+
+    /// Perigee and apogee altitudes (km) of the mean orbit.
+    pub fn shell_km(&self) -> (f64, f64) {
+        orbit_shell_km(self.elements.mean_motion, self.elements.eccentricity)
+    }
+}
+
+/// Semi-major axis (km) for a mean motion in revolutions per day.
+pub fn semi_major_axis_km(mean_motion_rev_day: f64) -> f64 {
+    let n = mean_motion_rev_day * 2.0 * PI / 86400.0;
+    (EARTH_MU_KM3_S2 / (n * n)).cbrt()
+}
+
+/// Perigee and apogee altitudes (km) for a mean motion (rev/day) and eccentricity.
+pub fn orbit_shell_km(mean_motion_rev_day: f64, eccentricity: f64) -> (f64, f64) {
+    let a = semi_major_axis_km(mean_motion_rev_day);
+    (
+        a * (1.0 - eccentricity) - EARTH_RADIUS_KM,
+        a * (1.0 + eccentricity) - EARTH_RADIUS_KM,
+    )
+}
+
 pub fn propagate_position(
     id: &str,
     name: &str,
@@ -33,6 +110,8 @@ pub fn propagate_position(
 
     let vel_kmh = (vel[0] * vel[0] + vel[1] * vel[1] + vel[2] * vel[2]).sqrt() * 3600.0;
 
+    let footprint_radius = footprint_radius_km(alt, MIN_ELEVATION_DEG);
+
     Some(SatellitePosition {
         id: id.to_string(),
         name: name.to_string(),
@@ -41,6 +120,8 @@ pub fn propagate_position(
         altitude_km: alt,
         velocity_kmh: vel_kmh,
         timestamp: timestamp_unix,
+        footprint_radius_km: footprint_radius,
+        footprint: footprint_circle(lat, lon, footprint_radius),
     })
 }
 
@@ -118,66 +199,288 @@ pub fn predict_passes(
     let obs_lat_rad = observer_lat / DEG;
     let obs_lon_rad = observer_lon / DEG;
 
+    // Elevation and azimuth (radians) of the satellite at time t
+    let look_angles = |t: i64| -> Option<(f64, f64)> {
+        let minutes_since = (t as f64 - epoch_unix) / 60.0;
+        let prediction = constants.propagate(MinutesSinceEpoch(minutes_since)).ok()?;
+        let gmst = gmst_from_unix(t);
+        let (sat_lat, sat_lon, sat_alt) = eci_to_geodetic(
+            prediction.position[0],
+            prediction.position[1],
+            prediction.position[2],
+            gmst,
+        );
+        let el = elevation_angle(
+            obs_lat_rad,
+            obs_lon_rad,
+            sat_lat / DEG,
+            sat_lon / DEG,
+            sat_alt,
+        );
+        let az = azimuth(obs_lat_rad, obs_lon_rad, sat_lat / DEG, sat_lon / DEG);
+        Some((el, az))
+    };
+    let above = |t: i64| -> bool {
+        look_angles(t)
+            .map(|(el, _)| el > MIN_ELEVATION_DEG)
+            .unwrap_or(false)
+    };
+    let azimuth_at = |t: i64| -> f64 { look_angles(t).map(|(_, az)| az).unwrap_or(0.0) };
+
     let mut passes = Vec::new();
     let mut in_pass = false;
     let mut pass_start = 0_i64;
     let mut max_el = 0.0_f64;
-    let mut start_az = 0.0_f64;
+    let mut max_az = 0.0_f64;
 
-    let mut t = now;
+    // Start the scan a little in the past so a pass already under way keeps
+    // its real AOS, and refine every horizon crossing to the second. AOS and
+    // LOS then do not depend on when the prediction was run.
+    let mut prev = now - MAX_PASS_SECS;
+    let mut t = prev;
     while t < end_time {
-        let minutes_since = (t as f64 - epoch_unix) / 60.0;
-
-        if let Ok(prediction) = constants.propagate(MinutesSinceEpoch(minutes_since)) {
-            let gmst = gmst_from_unix(t);
-            let (sat_lat, sat_lon, sat_alt) = eci_to_geodetic(
-                prediction.position[0],
-                prediction.position[1],
-                prediction.position[2],
-                gmst,
-            );
-
-            let el = elevation_angle(
-                obs_lat_rad,
-                obs_lon_rad,
-                sat_lat / DEG,
-                sat_lon / DEG,
-                sat_alt,
-            );
-
-            if el > 5.0 {
+        if let Some((el, az)) = look_angles(t) {
+            if el > MIN_ELEVATION_DEG {
                 if !in_pass {
                     in_pass = true;
-                    pass_start = t;
+                    pass_start = if t > prev {
+                        refine_transition(&above, prev, t, false)
+                    } else {
+                        t
+                    };
                     max_el = el;
-                    start_az = azimuth(obs_lat_rad, obs_lon_rad, sat_lat / DEG, sat_lon / DEG);
+                    max_az = az;
                 }
                 if el > max_el {
                     max_el = el;
+                    max_az = az;
                 }
             } else if in_pass {
                 in_pass = false;
-                passes.push(PassPrediction {
-                    satellite_id: id.to_string(),
-                    name: name.to_string(),
-                    start_time: pass_start,
-                    end_time: t,
-                    max_elevation: max_el,
-                    start_azimuth: start_az * DEG,
-                    is_visible: max_el > 10.0,
-                });
-                if passes.len() >= 10 {
-                    break;
+                let pass_end = refine_transition(&above, prev, t, true);
+                if pass_end > now {
+                    passes.push(PassPrediction {
+                        satellite_id: id.to_string(),
+                        name: name.to_string(),
+                        start_time: pass_start,
+                        end_time: pass_end,
+                        max_elevation: max_el,
+                        start_azimuth: azimuth_at(pass_start) * DEG,
+                        max_azimuth: max_az * DEG,
+                        end_azimuth: azimuth_at(pass_end) * DEG,
+                        is_visible: max_el > 10.0,
+                        cloud_cover_pct: None,
+                    });
+                    if passes.len() >= 10 {
+                        break;
+                    }
                 }
             }
         }
 
+        prev = t;
         t += step;
     }
 
     passes
 }
 
+/// Compute the ground swath a satellite covers over a time window, and the
+/// intervals during which `target` (lat, lon) lies inside its footprint.
+#[allow(clippy::too_many_arguments)]
+pub fn predict_coverage(
+    id: &str,
+    name: &str,
+    line1: &str,
+    line2: &str,
+    start_unix: i64,
+    duration_mins: i64,
+    min_elevation_deg: f64,
+    target: (f64, f64),
+) -> Option<SatelliteCoverage> {
+    let elements = Elements::from_tle(
+        Some(name.to_string()),
+        line1.as_bytes(),
+        line2.as_bytes(),
+    ).ok()?;
+
+    let constants = Constants::from_elements(&elements).ok()?;
+    let epoch_unix = tle_epoch_to_unix(&elements);
+    let end_unix = start_unix + duration_mins * 60;
+
+    // Sub-satellite point and footprint radius at time t
+    let sample = |t: i64| -> Option<(f64, f64, f64)> {
+        let minutes_since = (t as f64 - epoch_unix) / 60.0;
+        let prediction = constants.propagate(MinutesSinceEpoch(minutes_since)).ok()?;
+        let gmst = gmst_from_unix(t);
+        let (lat, lon, alt) = eci_to_geodetic(
+            prediction.position[0],
+            prediction.position[1],
+            prediction.position[2],
+            gmst,
+        );
+        Some((lat, lon, footprint_radius_km(alt, min_elevation_deg)))
+    };
+    let (target_lat, target_lon) = target;
+    let covered = |t: i64| -> bool {
+        sample(t)
+            .map(|(lat, lon, radius)| {
+                great_circle_km(lat, lon, target_lat, target_lon) <= radius
+            })
+            .unwrap_or(false)
+    };
+
+    // Swath: sub-satellite track with footprint radius, every 30s
+    let mut swath = Vec::new();
+    let mut t = start_unix;
+    while t <= end_unix {
+        if let Some((lat, lon, radius)) = sample(t) {
+            swath.push(SwathSample {
+                timestamp: t,
+                latitude: lat,
+                longitude: lon,
+                radius_km: radius,
+            });
+        }
+        t += 30;
+    }
+    if swath.is_empty() {
+        return None;
+    }
+    let (left_edge, right_edge) = swath_edges(&swath);
+
+    // Coverage windows: coarse 10s scan, then bisect each transition to 1s
+    let step = 10_i64;
+    let mut windows = Vec::new();
+    let mut in_view = covered(start_unix);
+    let mut enter = start_unix;
+    let mut prev = start_unix;
+    let mut t = start_unix + step;
+    while t <= end_unix {
+        let now_in_view = covered(t);
+        if now_in_view != in_view {
+            let edge = refine_transition(&covered, prev, t, in_view);
+            if now_in_view {
+                enter = edge;
+            } else {
+                windows.push(CoverageWindow {
+                    enter_time: enter,
+                    exit_time: edge,
+                });
+            }
+            in_view = now_in_view;
+        }
+        prev = t;
+        t += step;
+    }
+    if in_view {
+        windows.push(CoverageWindow {
+            enter_time: enter,
+            exit_time: end_unix,
+        });
+    }
+
+    Some(SatelliteCoverage {
+        satellite_id: id.to_string(),
+        name: name.to_string(),
+        start_time: start_unix,
+        end_time: end_unix,
+        min_elevation: min_elevation_deg,
+        swath,
+        left_edge,
+        right_edge,
+        windows,
+    })
+}
+
+/// Ground radius (km) of the region from which a satellite at `alt_km` is
+/// seen above `min_elevation_deg`.
+pub fn footprint_radius_km(alt_km: f64, min_elevation_deg: f64) -> f64 {
+    let el = min_elevation_deg / DEG;
+    let ratio = EARTH_RADIUS_KM / (EARTH_RADIUS_KM + alt_km.max(0.0));
+    // Earth-central angle between the sub-satellite point and the footprint edge
+    let half_angle = (ratio * el.cos()).clamp(-1.0, 1.0).acos() - el;
+    EARTH_RADIUS_KM * half_angle.max(0.0)
+}
+
+/// Closed [lat, lon] ring of points `radius_km` from the given centre.
+pub fn footprint_circle(lat: f64, lon: f64, radius_km: f64) -> Vec<[f64; 2]> {
+    let angular = radius_km / EARTH_RADIUS_KM;
+    (0..=FOOTPRINT_SEGMENTS)
+        .map(|i| {
+            let bearing = 2.0 * PI * i as f64 / FOOTPRINT_SEGMENTS as f64;
+            destination_point(lat, lon, bearing, angular)
+        })
+        .collect()
+}
+
+/// Left and right boundaries of the swath, offset perpendicular to the track.
+fn swath_edges(swath: &[SwathSample]) -> (Vec<[f64; 2]>, Vec<[f64; 2]>) {
+    let mut left = Vec::with_capacity(swath.len());
+    let mut right = Vec::with_capacity(swath.len());
+
+    for (i, s) in swath.iter().enumerate() {
+        let (from, to) = if i + 1 < swath.len() {
+            (s, &swath[i + 1])
+        } else if i > 0 {
+            (&swath[i - 1], s)
+        } else {
+            (s, s)
+        };
+        let heading = initial_bearing(from.latitude, from.longitude, to.latitude, to.longitude);
+        let angular = s.radius_km / EARTH_RADIUS_KM;
+        left.push(destination_point(s.latitude, s.longitude, heading - PI / 2.0, angular));
+        right.push(destination_point(s.latitude, s.longitude, heading + PI / 2.0, angular));
+    }
+
+    (left, right)
+}
+
+/// Bisect between `lo` (state `lo_state`) and `hi` to find the second at which coverage flips.
+fn refine_transition<F: Fn(i64) -> bool>(covered: &F, mut lo: i64, mut hi: i64, lo_state: bool) -> i64 {
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if covered(mid) == lo_state {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    hi
+}
+
+fn destination_point(lat: f64, lon: f64, bearing: f64, angular_dist: f64) -> [f64; 2] {
+    let lat1 = lat / DEG;
+    let lon1 = lon / DEG;
+    let lat2 = (lat1.sin() * angular_dist.cos()
+        + lat1.cos() * angular_dist.sin() * bearing.cos())
+    .clamp(-1.0, 1.0)
+    .asin();
+    let lon2 = lon1
+        + (bearing.sin() * angular_dist.sin() * lat1.cos())
+            .atan2(angular_dist.cos() - lat1.sin() * lat2.sin());
+
+    let lon2 = ((lon2 * DEG + 180.0) % 360.0 + 360.0) % 360.0 - 180.0;
+    [lat2 * DEG, lon2]
+}
+
+/// Initial great-circle bearing (radians, clockwise from north) from point 1 to point 2.
+pub fn initial_bearing(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let (lat1, lat2) = (lat1 / DEG, lat2 / DEG);
+    let dlon = (lon2 - lon1) / DEG;
+    let y = dlon.sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlon.cos();
+    y.atan2(x)
+}
+
+pub fn great_circle_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let dlat = (lat2 - lat1) / DEG;
+    let dlon = (lon2 - lon1) / DEG;
+    let a = (dlat / 2.0).sin().powi(2)
+        + (lat1 / DEG).cos() * (lat2 / DEG).cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.clamp(0.0, 1.0).sqrt().asin()
+}
+
 fn tle_epoch_to_unix(elements: &Elements) -> f64 {
     let dt = elements.datetime;
     let Some(date) = chrono::NaiveDate::from_ymd_opt(
@@ -250,3 +553,49 @@ fn azimuth(obs_lat: f64, obs_lon: f64, sat_lat: f64, sat_lon: f64) -> f64 {
     let x = obs_lat.cos() * sat_lat.sin() - obs_lat.sin() * sat_lat.cos() * dlon.cos();
     y.atan2(x)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn footprint_radius_matches_horizon_geometry() {
+        // ISS at ~420 km sees a horizon circle roughly 2250 km in radius
+        let horizon = footprint_radius_km(420.0, 0.0);
+        assert!(horizon > 2200.0 && horizon < 2300.0, "got {}", horizon);
+
+        // Raising the minimum elevation shrinks the footprint
+        let masked = footprint_radius_km(420.0, 10.0);
+        assert!(masked < horizon);
+        assert_eq!(footprint_radius_km(420.0, 90.0), 0.0);
+    }
+
+    #[test]
+    fn footprint_circle_is_equidistant_from_centre() {
+        let ring = footprint_circle(51.5, 179.0, 1500.0);
+        assert_eq!(ring.len(), FOOTPRINT_SEGMENTS + 1);
+        for p in &ring {
+            let d = great_circle_km(51.5, 179.0, p[0], p[1]);
+            assert!((d - 1500.0).abs() < 1.0, "distance {}", d);
+            assert!(p[1] >= -180.0 && p[1] <= 180.0);
+        }
+    }
+
+    #[test]
+    fn orbit_shell_from_iss_elements() {
+        let prop = Propagator::from_tle(
+            "ISS (ZARYA)",
+            "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927",
+            "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537",
+        )
+        .expect("valid TLE");
+        let (perigee, apogee) = prop.shell_km();
+        assert!(perigee > 340.0 && perigee < apogee && apogee < 380.0);
+    }
+
+    #[test]
+    fn refine_transition_finds_exact_second() {
+        let covered = |t: i64| t >= 1_037;
+        assert_eq!(refine_transition(&covered, 1_030, 1_040, false), 1_037);
+    }
+}
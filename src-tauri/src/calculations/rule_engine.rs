@@ -0,0 +1,1030 @@
+//! Expression language for user alert rules, e.g.
+//! `earthquake.magnitude >= 6 && earthquake.depth < 70 && distance(home) < 1000`.
+//!
+//! Rules are parsed and type-checked when saved, then evaluated against a
+//! [`RuleEvent`] built from each incoming feed item. Every field in a rule must
+//! belong to the same event kind, which decides the events the rule sees.
+
+use crate::models::asteroid::Asteroid;
+use crate::models::earthquake::Earthquake;
+use crate::models::eonet::NaturalEvent;
+use crate::models::gdacs::GdacsAlert;
+use crate::models::solar_event::{CoronalMassEjection, SolarFlare};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Earthquake,
+    Kp,
+    Asteroid,
+    Flare,
+    Cme,
+    Gdacs,
+    Eonet,
+}
+
+impl EventKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EventKind::Earthquake => "earthquake",
+            EventKind::Kp => "kp",
+            EventKind::Asteroid => "asteroid",
+            EventKind::Flare => "flare",
+            EventKind::Cme => "cme",
+            EventKind::Gdacs => "gdacs",
+            EventKind::Eonet => "eonet",
+        }
+    }
+
+    /// Whether events of this kind carry a position for `distance()`.
+    fn has_location(self) -> bool {
+        matches!(
+            self,
+            EventKind::Earthquake | EventKind::Gdacs | EventKind::Eonet
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Type {
+    Number,
+    Text,
+    Bool,
+}
+
+impl Type {
+    fn name(self) -> &'static str {
+        match self {
+            Type::Number => "number",
+            Type::Text => "string",
+            Type::Bool => "boolean",
+        }
+    }
+}
+
+/// Fields rules may reference, with their event kind and type.
+const FIELDS: &[(&str, EventKind, Type)] = &[
+    ("earthquake.magnitude", EventKind::Earthquake, Type::Number),
+    ("earthquake.depth", EventKind::Earthquake, Type::Number),
+    ("earthquake.latitude", EventKind::Earthquake, Type::Number),
+    ("earthquake.longitude", EventKind::Earthquake, Type::Number),
+    ("earthquake.place", EventKind::Earthquake, Type::Text),
+    ("earthquake.tsunami", EventKind::Earthquake, Type::Bool),
+    ("kp", EventKind::Kp, Type::Number),
+    ("asteroid.name", EventKind::Asteroid, Type::Text),
+    ("asteroid.diameter_km", EventKind::Asteroid, Type::Number),
+    (
+        "asteroid.miss_distance_km",
+        EventKind::Asteroid,
+        Type::Number,
+    ),
+    (
+        "asteroid.miss_distance_lunar",
+        EventKind::Asteroid,
+        Type::Number,
+    ),
+    ("asteroid.velocity_kps", EventKind::Asteroid, Type::Number),
+    ("asteroid.hazardous", EventKind::Asteroid, Type::Bool),
+    ("asteroid.orbit_class", EventKind::Asteroid, Type::Text),
+    (
+        "asteroid.impact_probability",
+        EventKind::Asteroid,
+        Type::Number,
+    ),
+    ("asteroid.torino", EventKind::Asteroid, Type::Number),
+    // The class as text, e.g. "X2.1"; compare intensity (peak X-ray flux in
+    // W/m²) for ordering, since "X10" < "X2" as strings
+    ("flare.class", EventKind::Flare, Type::Text),
+    ("flare.intensity", EventKind::Flare, Type::Number),
+    ("cme.speed", EventKind::Cme, Type::Number),
+    ("cme.earth_directed", EventKind::Cme, Type::Bool),
+    ("gdacs.level", EventKind::Gdacs, Type::Text),
+    ("gdacs.type", EventKind::Gdacs, Type::Text),
+    ("gdacs.country", EventKind::Gdacs, Type::Text),
+    ("gdacs.latitude", EventKind::Gdacs, Type::Number),
+    ("gdacs.longitude", EventKind::Gdacs, Type::Number),
+    ("eonet.category", EventKind::Eonet, Type::Text),
+    ("eonet.title", EventKind::Eonet, Type::Text),
+    ("eonet.latitude", EventKind::Eonet, Type::Number),
+    ("eonet.longitude", EventKind::Eonet, Type::Number),
+    ("eonet.magnitude", EventKind::Eonet, Type::Number),
+];
+
+fn field(name: &str) -> Option<(EventKind, Type)> {
+    FIELDS
+        .iter()
+        .find(|(n, _, _)| *n == name)
+        .map(|(_, kind, ty)| (*kind, *ty))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Number(f64),
+    Text(String),
+    Bool(bool),
+}
+
+// -- Lexer --
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Text(String),
+    Ident(String),
+    True,
+    False,
+    And,
+    Or,
+    Not,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    LParen,
+    RParen,
+    Comma,
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Number(n) => format!("number {}", n),
+        Token::Text(s) => format!("string \"{}\"", s),
+        Token::Ident(name) => format!("'{}'", name),
+        Token::True => "'true'".to_string(),
+        Token::False => "'false'".to_string(),
+        Token::And => "'&&'".to_string(),
+        Token::Or => "'||'".to_string(),
+        Token::Not => "'!'".to_string(),
+        Token::Eq => "'=='".to_string(),
+        Token::Ne => "'!='".to_string(),
+        Token::Lt => "'<'".to_string(),
+        Token::Le => "'<='".to_string(),
+        Token::Gt => "'>'".to_string(),
+        Token::Ge => "'>='".to_string(),
+        Token::Plus => "'+'".to_string(),
+        Token::Minus => "'-'".to_string(),
+        Token::Star => "'*'".to_string(),
+        Token::Slash => "'/'".to_string(),
+        Token::LParen => "'('".to_string(),
+        Token::RParen => "')'".to_string(),
+        Token::Comma => "','".to_string(),
+    }
+}
+
+/// Tokens paired with their character offset for error messages.
+fn tokenize(input: &str) -> Result<Vec<(Token, usize)>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let two = |next: char| chars.get(i + 1) == Some(&next);
+        let token = match c {
+            '&' if two('&') => {
+                i += 2;
+                Token::And
+            }
+            '|' if two('|') => {
+                i += 2;
+                Token::Or
+            }
+            '=' if two('=') => {
+                i += 2;
+                Token::Eq
+            }
+            '!' if two('=') => {
+                i += 2;
+                Token::Ne
+            }
+            '<' if two('=') => {
+                i += 2;
+                Token::Le
+            }
+            '>' if two('=') => {
+                i += 2;
+                Token::Ge
+            }
+            '!' | '<' | '>' | '+' | '-' | '*' | '/' | '(' | ')' | ',' => {
+                i += 1;
+                match c {
+                    '!' => Token::Not,
+                    '<' => Token::Lt,
+                    '>' => Token::Gt,
+                    '+' => Token::Plus,
+                    '-' => Token::Minus,
+                    '*' => Token::Star,
+                    '/' => Token::Slash,
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    _ => Token::Comma,
+                }
+            }
+            '"' | '\'' => {
+                let mut text = String::new();
+                i += 1;
+                loop {
+                    match chars.get(i) {
+                        Some(&q) if q == c => break,
+                        Some(&ch) => text.push(ch),
+                        None => return Err(format!("Unterminated string at position {}", start)),
+                    }
+                    i += 1;
+                }
+                i += 1;
+                Token::Text(text)
+            }
+            _ if c.is_ascii_digit() || c == '.' => {
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                // Exponent, e.g. 1e-4
+                if matches!(chars.get(i), Some('e' | 'E')) {
+                    let digits = match chars.get(i + 1) {
+                        Some('+' | '-') => i + 2,
+                        _ => i + 1,
+                    };
+                    if chars.get(digits).is_some_and(|c| c.is_ascii_digit()) {
+                        i = digits;
+                        while i < chars.len() && chars[i].is_ascii_digit() {
+                            i += 1;
+                        }
+                    }
+                }
+                let text: String = chars[start..i].iter().collect();
+                let n = text
+                    .parse()
+                    .map_err(|_| format!("Invalid number '{}' at position {}", text, start))?;
+                Token::Number(n)
+            }
+            _ if c.is_ascii_alphabetic() || c == '_' => {
+                while i < chars.len()
+                    && (chars[i].is_ascii_alphanumeric() || chars[i] == '_' || chars[i] == '.')
+                {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                match word.as_str() {
+                    "true" => Token::True,
+                    "false" => Token::False,
+                    _ => Token::Ident(word),
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "Unexpected character '{}' at position {}",
+                    c, start
+                ))
+            }
+        };
+        tokens.push((token, start));
+    }
+    Ok(tokens)
+}
+
+// -- Parser --
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinaryOp {
+    And,
+    Or,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Literal(Value),
+    Field(String),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    /// `distance(location)` in km from the event to a named location
+    Distance(String),
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    len: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(t, _)| t.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn position(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.len, |(_, p)| *p)
+    }
+
+    fn unexpected(&self) -> String {
+        match self.peek() {
+            Some(token) => format!(
+                "Unexpected {} at position {}",
+                describe(token),
+                self.position()
+            ),
+            None => "Unexpected end of rule".to_string(),
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), String> {
+        if self.peek() == Some(&token) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected {}: {}",
+                describe(&token),
+                self.unexpected()
+            ))
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut left = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            let right = self.and()?;
+            left = Expr::Binary(BinaryOp::Or, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut left = self.comparison()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            let right = self.comparison()?;
+            left = Expr::Binary(BinaryOp::And, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    /// Comparisons do not chain: `1 < x < 3` is an error.
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let left = self.additive()?;
+        let op = match self.peek() {
+            Some(Token::Eq) => BinaryOp::Eq,
+            Some(Token::Ne) => BinaryOp::Ne,
+            Some(Token::Lt) => BinaryOp::Lt,
+            Some(Token::Le) => BinaryOp::Le,
+            Some(Token::Gt) => BinaryOp::Gt,
+            Some(Token::Ge) => BinaryOp::Ge,
+            _ => return Ok(left),
+        };
+        self.pos += 1;
+        let right = self.additive()?;
+        Ok(Expr::Binary(op, Box::new(left), Box::new(right)))
+    }
+
+    fn additive(&mut self) -> Result<Expr, String> {
+        let mut left = self.multiplicative()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Plus) => BinaryOp::Add,
+                Some(Token::Minus) => BinaryOp::Sub,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            let right = self.multiplicative()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Star) => BinaryOp::Mul,
+                Some(Token::Slash) => BinaryOp::Div,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            let right = self.unary()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        match self.peek() {
+            Some(Token::Not) => {
+                self.pos += 1;
+                Ok(Expr::Not(Box::new(self.unary()?)))
+            }
+            Some(Token::Minus) => {
+                self.pos += 1;
+                Ok(Expr::Neg(Box::new(self.unary()?)))
+            }
+            _ => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        let position = self.position();
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Literal(Value::Number(n))),
+            Some(Token::Text(s)) => Ok(Expr::Literal(Value::Text(s))),
+            Some(Token::True) => Ok(Expr::Literal(Value::Bool(true))),
+            Some(Token::False) => Ok(Expr::Literal(Value::Bool(false))),
+            Some(Token::LParen) => {
+                let inner = self.or()?;
+                self.expect(Token::RParen)?;
+                Ok(inner)
+            }
+            Some(Token::Ident(name)) if self.peek() == Some(&Token::LParen) => {
+                self.pos += 1;
+                if name != "distance" {
+                    return Err(format!(
+                        "Unknown function '{}' at position {}",
+                        name, position
+                    ));
+                }
+                // Location names may be bare words or quoted strings
+                let location = match self.next() {
+                    Some(Token::Ident(loc)) | Some(Token::Text(loc)) => loc,
+                    _ => {
+                        self.pos -= 1;
+                        return Err(format!(
+                            "distance() takes a location name: {}",
+                            self.unexpected()
+                        ));
+                    }
+                };
+                self.expect(Token::RParen)?;
+                Ok(Expr::Distance(location))
+            }
+            Some(Token::Ident(name)) => Ok(Expr::Field(name)),
+            _ => {
+                self.pos -= 1;
+                Err(self.unexpected())
+            }
+        }
+    }
+}
+
+// -- Type checking --
+
+struct Checker<'a> {
+    kind: Option<EventKind>,
+    locations: &'a [String],
+}
+
+impl Checker<'_> {
+    fn bind_kind(&mut self, kind: EventKind, name: &str) -> Result<(), String> {
+        match self.kind {
+            Some(existing) if existing != kind => Err(format!(
+                "'{}' belongs to {} events but the rule already uses {} fields",
+                name,
+                kind.as_str(),
+                existing.as_str()
+            )),
+            _ => {
+                self.kind = Some(kind);
+                Ok(())
+            }
+        }
+    }
+
+    fn check(&mut self, expr: &Expr) -> Result<Type, String> {
+        match expr {
+            Expr::Literal(Value::Number(_)) => Ok(Type::Number),
+            Expr::Literal(Value::Text(_)) => Ok(Type::Text),
+            Expr::Literal(Value::Bool(_)) => Ok(Type::Bool),
+            Expr::Field(name) => {
+                let (kind, ty) = field(name).ok_or_else(|| format!("Unknown field '{}'", name))?;
+                self.bind_kind(kind, name)?;
+                Ok(ty)
+            }
+            Expr::Distance(location) => {
+                let known = location.eq_ignore_ascii_case("home")
+                    || self
+                        .locations
+                        .iter()
+                        .any(|l| l.eq_ignore_ascii_case(location));
+                if !known {
+                    return Err(format!("Unknown location '{}' in distance()", location));
+                }
+                Ok(Type::Number)
+            }
+            Expr::Not(inner) => {
+                self.expect_type(inner, Type::Bool, "'!'")?;
+                Ok(Type::Bool)
+            }
+            Expr::Neg(inner) => {
+                self.expect_type(inner, Type::Number, "'-'")?;
+                Ok(Type::Number)
+            }
+            Expr::Binary(op, left, right) => match op {
+                BinaryOp::And | BinaryOp::Or => {
+                    let symbol = if *op == BinaryOp::And { "'&&'" } else { "'||'" };
+                    self.expect_type(left, Type::Bool, symbol)?;
+                    self.expect_type(right, Type::Bool, symbol)?;
+                    Ok(Type::Bool)
+                }
+                BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div => {
+                    self.expect_type(left, Type::Number, "arithmetic")?;
+                    self.expect_type(right, Type::Number, "arithmetic")?;
+                    Ok(Type::Number)
+                }
+                _ => {
+                    let l = self.check(left)?;
+                    let r = self.check(right)?;
+                    if l != r {
+                        return Err(format!("Cannot compare {} with {}", l.name(), r.name()));
+                    }
+                    let ordering = !matches!(op, BinaryOp::Eq | BinaryOp::Ne);
+                    if ordering && l == Type::Bool {
+                        return Err("Booleans can only be compared with == or !=".to_string());
+                    }
+                    Ok(Type::Bool)
+                }
+            },
+        }
+    }
+
+    fn expect_type(&mut self, expr: &Expr, expected: Type, context: &str) -> Result<(), String> {
+        let actual = self.check(expr)?;
+        if actual != expected {
+            return Err(format!(
+                "{} needs a {}, found a {}",
+                context,
+                expected.name(),
+                actual.name()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A parsed, type-checked rule.
+#[derive(Debug, Clone)]
+pub struct CompiledRule {
+    expr: Expr,
+    pub kind: EventKind,
+}
+
+/// Parse and validate `source`. `locations` are the names `distance()` may
+/// refer to besides `home`.
+pub fn compile(source: &str, locations: &[String]) -> Result<CompiledRule, String> {
+    let tokens = tokenize(source)?;
+    if tokens.is_empty() {
+        return Err("Rule is empty".to_string());
+    }
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        len: source.chars().count(),
+    };
+    let expr = parser.or()?;
+    if parser.peek().is_some() {
+        return Err(parser.unexpected());
+    }
+
+    let mut checker = Checker {
+        kind: None,
+        locations,
+    };
+    let ty = checker.check(&expr)?;
+    if ty != Type::Bool {
+        return Err(format!("Rule must be a condition, not a {}", ty.name()));
+    }
+    let kind = checker
+        .kind
+        .ok_or_else(|| "Rule must reference at least one event field".to_string())?;
+    if contains_distance(&expr) && !kind.has_location() {
+        return Err(format!(
+            "distance() is not available for {} events",
+            kind.as_str()
+        ));
+    }
+    Ok(CompiledRule { expr, kind })
+}
+
+fn contains_distance(expr: &Expr) -> bool {
+    match expr {
+        Expr::Distance(_) => true,
+        Expr::Not(inner) | Expr::Neg(inner) => contains_distance(inner),
+        Expr::Binary(_, left, right) => contains_distance(left) || contains_distance(right),
+        _ => false,
+    }
+}
+
+// -- Evaluation --
+
+/// One feed item as seen by rules.
+#[derive(Debug, Clone)]
+pub struct RuleEvent {
+    pub kind: EventKind,
+    pub id: String,
+    pub title: String,
+    pub body: String,
+    pub location: Option<(f64, f64)>,
+    fields: HashMap<&'static str, Value>,
+}
+
+impl RuleEvent {
+    fn new(kind: EventKind, id: String, title: String, body: String) -> Self {
+        RuleEvent {
+            kind,
+            id,
+            title,
+            body,
+            location: None,
+            fields: HashMap::new(),
+        }
+    }
+
+    fn with(mut self, name: &'static str, value: Value) -> Self {
+        self.fields.insert(name, value);
+        self
+    }
+
+    fn at(mut self, lat: f64, lon: f64) -> Self {
+        self.location = Some((lat, lon));
+        self
+    }
+
+    pub fn earthquake(q: &Earthquake) -> Self {
+        RuleEvent::new(
+            EventKind::Earthquake,
+            q.id.clone(),
+            format!("Earthquake M{:.1}", q.magnitude),
+            q.place.clone(),
+        )
+        .with("earthquake.magnitude", Value::Number(q.magnitude))
+        .with("earthquake.depth", Value::Number(q.depth))
+        .with("earthquake.latitude", Value::Number(q.latitude))
+        .with("earthquake.longitude", Value::Number(q.longitude))
+        .with("earthquake.place", Value::Text(q.place.clone()))
+        .with("earthquake.tsunami", Value::Bool(q.tsunami))
+        .at(q.latitude, q.longitude)
+    }
+
+    /// `timestamp` identifies the Kp reading so a rule fires once per reading.
+    pub fn kp(kp: f64, timestamp: &str) -> Self {
+        RuleEvent::new(
+            EventKind::Kp,
+            timestamp.to_string(),
+            format!("Kp Index {:.1}", kp),
+            format!("Geomagnetic Kp index reached {:.1}", kp),
+        )
+        .with("kp", Value::Number(kp))
+    }
+
+    pub fn asteroid(a: &Asteroid) -> Self {
+        // Objects off the Sentry list have no known impact solutions
+        let (probability, torino) = a.impact_risk.as_ref().map_or((0.0, 0.0), |r| {
+            (r.impact_probability, f64::from(r.torino_max))
+        });
+        let event = RuleEvent::new(
+            EventKind::Asteroid,
+            a.id.clone(),
+            format!("Asteroid {}", a.name.replace(['(', ')'], "")),
+            format!(
+                "Close approach at {:.1} lunar distances ({:.0} km)",
+                a.miss_distance_lunar, a.miss_distance_km
+            ),
+        )
+        .with("asteroid.name", Value::Text(a.name.clone()))
+        .with("asteroid.diameter_km", Value::Number(a.diameter_km_max))
+        .with(
+            "asteroid.miss_distance_km",
+            Value::Number(a.miss_distance_km),
+        )
+        .with(
+            "asteroid.miss_distance_lunar",
+            Value::Number(a.miss_distance_lunar),
+        )
+        .with("asteroid.velocity_kps", Value::Number(a.velocity_kps))
+        .with("asteroid.hazardous", Value::Bool(a.is_hazardous))
+        .with("asteroid.impact_probability", Value::Number(probability))
+        .with("asteroid.torino", Value::Number(torino));
+        match &a.orbit_class {
+            Some(class) => event.with("asteroid.orbit_class", Value::Text(class.code.clone())),
+            None => event,
+        }
+    }
+
+    pub fn flare(f: &SolarFlare) -> Self {
+        let event = RuleEvent::new(
+            EventKind::Flare,
+            f.id.clone(),
+            format!("Solar Flare: {}", f.class_type),
+            format!("Peak time: {}", f.peak_time),
+        )
+        .with("flare.class", Value::Text(f.class_type.clone()));
+        match f.peak_flux() {
+            Some(flux) => event.with("flare.intensity", Value::Number(flux)),
+            None => event,
+        }
+    }
+
+    pub fn cme(c: &CoronalMassEjection) -> Self {
+        let event = RuleEvent::new(
+            EventKind::Cme,
+            c.id.clone(),
+            "Coronal Mass Ejection".to_string(),
+            format!("Started {}", c.start_time),
+        )
+        .with("cme.earth_directed", Value::Bool(c.is_earth_directed));
+        match c.speed_kps {
+            Some(speed) => event.with("cme.speed", Value::Number(speed)),
+            None => event,
+        }
+    }
+
+    pub fn gdacs(a: &GdacsAlert) -> Self {
+        RuleEvent::new(
+            EventKind::Gdacs,
+            format!("{}:{}", a.id, a.severity.to_ascii_lowercase()),
+            format!("GDACS {} alert", a.severity),
+            a.title.clone(),
+        )
+        .with("gdacs.level", Value::Text(a.severity.to_ascii_lowercase()))
+        .with("gdacs.type", Value::Text(a.alert_type.clone()))
+        .with("gdacs.country", Value::Text(a.country.clone()))
+        .with("gdacs.latitude", Value::Number(a.latitude))
+        .with("gdacs.longitude", Value::Number(a.longitude))
+        .at(a.latitude, a.longitude)
+    }
+
+    pub fn eonet(e: &NaturalEvent) -> Self {
+        let event = RuleEvent::new(
+            EventKind::Eonet,
+            e.id.clone(),
+            e.category.clone(),
+            e.title.clone(),
+        )
+        .with("eonet.category", Value::Text(e.category_id.clone()))
+        .with("eonet.title", Value::Text(e.title.clone()))
+        .with("eonet.latitude", Value::Number(e.latitude))
+        .with("eonet.longitude", Value::Number(e.longitude))
+        .at(e.latitude, e.longitude);
+        match e.magnitude_value {
+            Some(magnitude) => event.with("eonet.magnitude", Value::Number(magnitude)),
+            None => event,
+        }
+    }
+}
+
+/// Named locations for `distance()`, keyed by lowercase name. `home` is
+/// the active observer profile.
+pub type Locations = HashMap<String, (f64, f64)>;
+
+impl CompiledRule {
+    /// Missing values (an unknown location, a CME without a speed) make the
+    /// rule not match rather than fail.
+    pub fn matches(&self, event: &RuleEvent, locations: &Locations) -> bool {
+        event.kind == self.kind
+            && matches!(eval(&self.expr, event, locations), Some(Value::Bool(true)))
+    }
+}
+
+fn eval(expr: &Expr, event: &RuleEvent, locations: &Locations) -> Option<Value> {
+    match expr {
+        Expr::Literal(v) => Some(v.clone()),
+        Expr::Field(name) => event.fields.get(name.as_str()).cloned(),
+        Expr::Distance(location) => {
+            let (lat, lon) = event.location?;
+            let (home_lat, home_lon) = locations.get(&location.to_lowercase())?;
+            Some(Value::Number(super::orbit::great_circle_km(
+                *home_lat, *home_lon, lat, lon,
+            )))
+        }
+        Expr::Not(inner) => match eval(inner, event, locations)? {
+            Value::Bool(b) => Some(Value::Bool(!b)),
+            _ => None,
+        },
+        Expr::Neg(inner) => match eval(inner, event, locations)? {
+            Value::Number(n) => Some(Value::Number(-n)),
+            _ => None,
+        },
+        Expr::Binary(BinaryOp::And, left, right) => {
+            // Short-circuit so `false && <missing>` is still false
+            match eval(left, event, locations) {
+                Some(Value::Bool(false)) => Some(Value::Bool(false)),
+                Some(Value::Bool(true)) => eval(right, event, locations),
+                _ => None,
+            }
+        }
+        Expr::Binary(BinaryOp::Or, left, right) => match eval(left, event, locations) {
+            Some(Value::Bool(true)) => Some(Value::Bool(true)),
+            _ => eval(right, event, locations),
+        },
+        Expr::Binary(op, left, right) => {
+            let l = eval(left, event, locations)?;
+            let r = eval(right, event, locations)?;
+            binary(*op, l, r)
+        }
+    }
+}
+
+fn binary(op: BinaryOp, left: Value, right: Value) -> Option<Value> {
+    use std::cmp::Ordering;
+
+    let ordering = match (&left, &right) {
+        (Value::Number(a), Value::Number(b)) => {
+            match op {
+                BinaryOp::Add => return Some(Value::Number(a + b)),
+                BinaryOp::Sub => return Some(Value::Number(a - b)),
+                BinaryOp::Mul => return Some(Value::Number(a * b)),
+                BinaryOp::Div if *b != 0.0 => return Some(Value::Number(a / b)),
+                BinaryOp::Div => return None,
+                _ => {}
+            }
+            a.partial_cmp(b)?
+        }
+        (Value::Text(a), Value::Text(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        _ => return None,
+    };
+
+    let result = match op {
+        BinaryOp::Eq => ordering == Ordering::Equal,
+        BinaryOp::Ne => ordering != Ordering::Equal,
+        BinaryOp::Lt => ordering == Ordering::Less,
+        BinaryOp::Le => ordering != Ordering::Greater,
+        BinaryOp::Gt => ordering == Ordering::Greater,
+        BinaryOp::Ge => ordering != Ordering::Less,
+        _ => return None,
+    };
+    Some(Value::Bool(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quake(magnitude: f64, depth: f64, lat: f64, lon: f64) -> RuleEvent {
+        RuleEvent::earthquake(&Earthquake {
+            id: "us7000test".to_string(),
+            magnitude,
+            latitude: lat,
+            longitude: lon,
+            depth,
+            place: "Test".to_string(),
+            time: 0,
+            tsunami: false,
+            title: "Test".to_string(),
+            alert: None,
+        })
+    }
+
+    fn home() -> Locations {
+        // Tokyo
+        HashMap::from([("home".to_string(), (35.68, 139.69))])
+    }
+
+    #[test]
+    fn parses_with_precedence() {
+        let rule = compile("kp >= 5 + 1 * 2 || !(kp < 3) && kp != 4", &[]).unwrap();
+        assert_eq!(rule.kind, EventKind::Kp);
+        match rule.expr {
+            Expr::Binary(BinaryOp::Or, left, right) => {
+                assert!(matches!(*left, Expr::Binary(BinaryOp::Ge, _, _)));
+                assert!(matches!(*right, Expr::Binary(BinaryOp::And, _, _)));
+            }
+            other => panic!("unexpected tree {:?}", other),
+        }
+    }
+
+    #[test]
+    fn earthquake_rule_with_distance() {
+        let rule = compile(
+            "earthquake.magnitude >= 6 && earthquake.depth < 70 && distance(home) < 1000",
+            &[],
+        )
+        .unwrap();
+        // Off the coast of Honshu, ~300 km from Tokyo
+        assert!(rule.matches(&quake(6.4, 30.0, 37.5, 142.0), &home()));
+        assert!(!rule.matches(&quake(5.9, 30.0, 37.5, 142.0), &home()));
+        assert!(!rule.matches(&quake(6.4, 120.0, 37.5, 142.0), &home()));
+        // Chile
+        assert!(!rule.matches(&quake(7.5, 30.0, -33.0, -71.0), &home()));
+        // Unknown location never matches
+        assert!(!rule.matches(&quake(6.4, 30.0, 37.5, 142.0), &HashMap::new()));
+    }
+
+    #[test]
+    fn rules_only_see_their_event_kind() {
+        let rule = compile("kp >= 7", &[]).unwrap();
+        assert!(rule.matches(&RuleEvent::kp(7.3, "2024-05-10 18:00"), &home()));
+        assert!(!rule.matches(&RuleEvent::kp(6.0, "2024-05-10 15:00"), &home()));
+        assert!(!rule.matches(&quake(8.0, 10.0, 0.0, 0.0), &home()));
+    }
+
+    #[test]
+    fn strings_compare_case_insensitively() {
+        let rule = compile("flare.class >= \"m\"", &[]).unwrap();
+        let flare = |class: &str| {
+            RuleEvent::flare(&SolarFlare {
+                id: class.to_string(),
+                class_type: class.to_string(),
+                peak_time: String::new(),
+                source_location: None,
+            })
+        };
+        assert!(rule.matches(&flare("X1.1"), &home()));
+        assert!(rule.matches(&flare("M2.3"), &home()));
+        assert!(!rule.matches(&flare("C9.9"), &home()));
+    }
+
+    #[test]
+    fn flares_order_by_intensity() {
+        let flare = |class: &str| {
+            RuleEvent::flare(&SolarFlare {
+                id: class.to_string(),
+                class_type: class.to_string(),
+                peak_time: String::new(),
+                source_location: None,
+            })
+        };
+        let rule = compile("flare.intensity >= 5e-4", &[]).unwrap();
+        assert!(rule.matches(&flare("X10"), &home()));
+        assert!(!rule.matches(&flare("X2.0"), &home()));
+        assert!(!rule.matches(&flare("M9.9"), &home()));
+
+        let rule = compile("flare.intensity > 0.0001", &[]).unwrap();
+        assert!(rule.matches(&flare("X1.1"), &home()));
+        assert!(!rule.matches(&flare("C9.9"), &home()));
+        assert!(!rule.matches(&flare("Unknown"), &home()));
+    }
+
+    #[test]
+    fn missing_values_do_not_match() {
+        let rule = compile("cme.speed > 1000 || cme.earth_directed", &[]).unwrap();
+        let cme = CoronalMassEjection {
+            id: "cme".to_string(),
+            start_time: String::new(),
+            speed_kps: None,
+            is_earth_directed: true,
+            note: None,
+        };
+        assert!(rule.matches(&RuleEvent::cme(&cme), &home()));
+        let slow = CoronalMassEjection {
+            is_earth_directed: false,
+            ..cme
+        };
+        assert!(!rule.matches(&RuleEvent::cme(&slow), &home()));
+    }
+
+    #[test]
+    fn reports_validation_errors() {
+        let err = |src: &str| compile(src, &["Office".to_string()]).unwrap_err();
+        assert_eq!(err(""), "Rule is empty");
+        assert_eq!(err("kp >="), "Unexpected end of rule");
+        assert_eq!(err("kp >= 5 )"), "Unexpected ')' at position 8");
+        assert_eq!(err("kp # 5"), "Unexpected character '#' at position 3");
+        assert_eq!(err("earthquake.mag > 5"), "Unknown field 'earthquake.mag'");
+        assert_eq!(err("kp > \"high\""), "Cannot compare number with string");
+        assert_eq!(err("kp + 1"), "Rule must be a condition, not a number");
+        assert_eq!(err("1 < 2"), "Rule must reference at least one event field");
+        assert_eq!(err("max(kp) > 1"), "Unknown function 'max' at position 0");
+        assert_eq!(
+            err("kp > 5 && earthquake.magnitude > 6"),
+            "'earthquake.magnitude' belongs to earthquake events but the rule already uses kp fields"
+        );
+        assert_eq!(
+            err("distance(cabin) < 50 && earthquake.magnitude > 3"),
+            "Unknown location 'cabin' in distance()"
+        );
+        assert_eq!(
+            err("kp > 5 && distance(office) < 10"),
+            "distance() is not available for kp events"
+        );
+        assert!(compile(
+            "distance(\"office\") < 50 && earthquake.magnitude > 3",
+            &["Office".to_string()]
+        )
+        .is_ok());
+        assert_eq!(err("\"open"), "Unterminated string at position 0");
+    }
+}
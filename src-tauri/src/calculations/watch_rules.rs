@@ -0,0 +1,182 @@
+use crate::calculations::alert_policy;
+use crate::models::watchlist::{QuietHours, WatchRules};
+use chrono::{DateTime, Utc};
+
+pub fn earthquake_matches(rules: &WatchRules, magnitude: f64, depth_km: f64) -> bool {
+    rules.earthquakes
+        && rules.min_magnitude.is_none_or(|min| magnitude >= min)
+        && rules.max_depth_km.is_none_or(|max| depth_km <= max)
+}
+
+/// GDACS alert levels in increasing severity.
+pub fn gdacs_level_rank(level: &str) -> Option<u8> {
+    match level.trim().to_ascii_lowercase().as_str() {
+        "green" => Some(1),
+        "orange" => Some(2),
+        "red" => Some(3),
+        _ => None,
+    }
+}
+
+/// Volcano alert levels in increasing severity. GVP-only volcanoes use the
+/// same words; see `fetchers::volcano`.
+pub fn volcano_level_rank(level: &str) -> Option<u8> {
+    match level.trim().to_ascii_lowercase().as_str() {
+        "normal" => Some(0),
+        "advisory" => Some(1),
+        "watch" => Some(2),
+        "warning" => Some(3),
+        _ => None,
+    }
+}
+
+/// ICAO aviation colour codes in increasing severity.
+pub fn aviation_color_rank(color: &str) -> Option<u8> {
+    match color.trim().to_ascii_lowercase().as_str() {
+        "green" => Some(0),
+        "yellow" => Some(1),
+        "orange" => Some(2),
+        "red" => Some(3),
+        _ => None,
+    }
+}
+
+pub fn gdacs_matches(rules: &WatchRules, alert_level: &str) -> bool {
+    let Some(min) = rules.gdacs_min_level.as_deref().and_then(gdacs_level_rank) else {
+        return false;
+    };
+    gdacs_level_rank(alert_level).is_some_and(|rank| rank >= min)
+}
+
+pub fn eonet_matches(rules: &WatchRules, category_id: &str) -> bool {
+    rules
+        .eonet_categories
+        .iter()
+        .any(|c| c.eq_ignore_ascii_case(category_id))
+}
+
+/// Whether the watchlist's quiet hours cover `now`. The window is read in the
+/// watchlist's own IANA timezone, or `default_timezone` when it has none.
+pub fn in_quiet_hours(rules: &WatchRules, default_timezone: &str, now: DateTime<Utc>) -> bool {
+    let Some(quiet) = &rules.quiet_hours else {
+        return false;
+    };
+    let timezone = quiet.timezone.as_deref().unwrap_or(default_timezone);
+    alert_policy::quiet_hours_active(&quiet.start, &quiet.end, timezone, now)
+}
+
+/// Whether `minute` (after midnight) falls in the daily window `start..end`.
+pub fn window_contains(start: i32, end: i32, minute: i32) -> bool {
+    if start <= end {
+        minute >= start && minute < end
+    } else {
+        // Window wraps past midnight, e.g. 22:00-07:00
+        minute >= start || minute < end
+    }
+}
+
+pub fn validate_rules(rules: &WatchRules) -> Result<(), String> {
+    if let Some(min) = rules.min_magnitude {
+        if !min.is_finite() || !(0.0..=10.0).contains(&min) {
+            return Err("Minimum magnitude must be between 0 and 10".to_string());
+        }
+    }
+    if let Some(max) = rules.max_depth_km {
+        if !max.is_finite() || max <= 0.0 {
+            return Err("Maximum depth must be positive".to_string());
+        }
+    }
+    if let Some(level) = &rules.gdacs_min_level {
+        if gdacs_level_rank(level).is_none() {
+            return Err("GDACS level must be green, orange or red".to_string());
+        }
+    }
+    if let Some(QuietHours {
+        start,
+        end,
+        timezone,
+    }) = &rules.quiet_hours
+    {
+        if parse_hhmm(start).is_none() || parse_hhmm(end).is_none() {
+            return Err("Quiet hours must be given as HH:MM".to_string());
+        }
+        if let Some(tz) = timezone {
+            if tz.parse::<chrono_tz::Tz>().is_err() {
+                return Err(format!("Unknown timezone: {}", tz));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Minutes after midnight for "HH:MM".
+pub fn parse_hhmm(text: &str) -> Option<i32> {
+    let (h, m) = text.trim().split_once(':')?;
+    let (h, m): (i32, i32) = (h.parse().ok()?, m.parse().ok()?);
+    if !(0..24).contains(&h) || !(0..60).contains(&m) {
+        return None;
+    }
+    Some(h * 60 + m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn earthquake_thresholds() {
+        let rules = WatchRules {
+            min_magnitude: Some(4.5),
+            max_depth_km: Some(70.0),
+            ..WatchRules::default()
+        };
+        assert!(earthquake_matches(&rules, 5.0, 10.0));
+        assert!(!earthquake_matches(&rules, 4.0, 10.0));
+        assert!(!earthquake_matches(&rules, 6.0, 300.0));
+
+        let off = WatchRules {
+            earthquakes: false,
+            ..WatchRules::default()
+        };
+        assert!(!earthquake_matches(&off, 7.0, 10.0));
+    }
+
+    #[test]
+    fn gdacs_levels_are_ordered() {
+        let rules = WatchRules {
+            gdacs_min_level: Some("Orange".to_string()),
+            ..WatchRules::default()
+        };
+        assert!(gdacs_matches(&rules, "Red"));
+        assert!(gdacs_matches(&rules, "orange"));
+        assert!(!gdacs_matches(&rules, "Green"));
+        assert!(!gdacs_matches(&WatchRules::default(), "Red"));
+    }
+
+    #[test]
+    fn quiet_hours_wrap_midnight_in_watchlist_timezone() {
+        let mut rules = WatchRules {
+            quiet_hours: Some(QuietHours {
+                start: "22:00".to_string(),
+                end: "07:00".to_string(),
+                timezone: Some("America/Los_Angeles".to_string()),
+            }),
+            ..WatchRules::default()
+        };
+        // 06:00 UTC is 23:00 in Los Angeles in summer (PDT)...
+        let summer = Utc.with_ymd_and_hms(2024, 6, 1, 6, 0, 0).unwrap();
+        assert!(in_quiet_hours(&rules, "UTC", summer));
+        // ...and 22:00 in winter (PST)
+        let winter = Utc.with_ymd_and_hms(2024, 1, 15, 6, 0, 0).unwrap();
+        assert!(in_quiet_hours(&rules, "UTC", winter));
+        // 20:00 UTC is 13:00 in Los Angeles
+        let afternoon = Utc.with_ymd_and_hms(2024, 6, 1, 20, 0, 0).unwrap();
+        assert!(!in_quiet_hours(&rules, "UTC", afternoon));
+
+        // Without a zone of its own the window follows the observer's
+        rules.quiet_hours.as_mut().unwrap().timezone = None;
+        assert!(!in_quiet_hours(&rules, "Asia/Tokyo", summer));
+        assert!(in_quiet_hours(&rules, "America/Los_Angeles", summer));
+    }
+}
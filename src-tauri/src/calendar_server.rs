@@ -0,0 +1,101 @@
+use crate::commands::satellite::get_pass_predictions_inner;
+use crate::db::Database;
+use crate::ics::passes_to_ics;
+use tauri::Manager;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Serve the pass calendar at `/passes.ics` so calendar apps can subscribe to it.
+pub async fn serve(handle: tauri::AppHandle, addr: String) {
+    let listener = match TcpListener::bind(&addr).await {
+        Ok(l) => l,
+        Err(e) => {
+            log::error!("Failed to bind calendar server on {}: {}", addr, e);
+            return;
+        }
+    };
+    log::info!("Serving pass calendar on http://{}/passes.ics", addr);
+
+    loop {
+        let (stream, _) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                log::warn!("Calendar server accept error: {}", e);
+                continue;
+            }
+        };
+        let handle = handle.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = handle_connection(&handle, stream).await {
+                log::warn!("Calendar request failed: {}", e);
+            }
+        });
+    }
+}
+
+async fn handle_connection(handle: &tauri::AppHandle, mut stream: TcpStream) -> Result<(), String> {
+    let mut buf = [0u8; 2048];
+    let n = stream
+        .read(&mut buf)
+        .await
+        .map_err(|e| format!("Failed to read request: {}", e))?;
+    let request = String::from_utf8_lossy(&buf[..n]);
+    let mut parts = request
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+
+    let response = match (method, path.split('?').next().unwrap_or_default()) {
+        ("GET", "/passes.ics") | ("HEAD", "/passes.ics") => {
+            let db = handle.state::<Database>();
+            match get_pass_predictions_inner(&db).await {
+                Ok(passes) => {
+                    let body = passes_to_ics(&passes, chrono::Utc::now().timestamp());
+                    http_response(
+                        "200 OK",
+                        "text/calendar; charset=utf-8",
+                        &body,
+                        method == "HEAD",
+                    )
+                }
+                // Calendar clients retry on 503; a dropped socket looks like a broken server
+                Err(e) => {
+                    log::warn!("Calendar passes unavailable: {}", e);
+                    http_response(
+                        "503 Service Unavailable",
+                        "text/plain",
+                        "Pass predictions are unavailable",
+                        method == "HEAD",
+                    )
+                }
+            }
+        }
+        ("GET", _) | ("HEAD", _) => {
+            http_response("404 Not Found", "text/plain", "Not found", method == "HEAD")
+        }
+        _ => http_response(
+            "405 Method Not Allowed",
+            "text/plain",
+            "Method not allowed",
+            false,
+        ),
+    };
+
+    stream
+        .write_all(response.as_bytes())
+        .await
+        .map_err(|e| format!("Failed to write response: {}", e))
+}
+
+fn http_response(status: &str, content_type: &str, body: &str, head_only: bool) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        if head_only { "" } else { body }
+    )
+}
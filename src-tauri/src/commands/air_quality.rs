@@ -1,8 +1,17 @@
+use crate::commands::observer::resolve_location;
+use crate::db::Database;
 use crate::fetchers::air_quality::fetch_air_quality;
 use crate::models::air_quality::AirQuality;
+use tauri::State;
 
 #[tauri::command]
-pub async fn get_air_quality(lat: f64, lon: f64) -> Result<AirQuality, String> {
+pub async fn get_air_quality(
+    db: State<'_, Database>,
+    lat: Option<f64>,
+    lon: Option<f64>,
+    profile_id: Option<i64>,
+) -> Result<AirQuality, String> {
+    let (lat, lon) = resolve_location(&db, lat, lon, profile_id)?;
     if !lat.is_finite() || !lon.is_finite() {
         return Err("Invalid coordinates".into());
     }
@@ -0,0 +1,87 @@
+use crate::calculations::rule_engine::{self, CompiledRule, Locations};
+use crate::db::Database;
+use crate::models::alert_rule::AlertRule;
+use tauri::State;
+
+/// Locations `distance()` can refer to: every observer profile by name, plus
+/// `home` for the active one.
+pub fn rule_locations(db: &Database) -> Locations {
+    let mut locations: Locations = db
+        .get_observer_profiles()
+        .into_iter()
+        .map(|p| (p.name.to_lowercase(), (p.latitude, p.longitude)))
+        .collect();
+    let active = super::observer::active_observer(db);
+    locations.insert("home".to_string(), (active.latitude, active.longitude));
+    locations
+}
+
+fn compile(db: &Database, expression: &str) -> Result<CompiledRule, String> {
+    let names: Vec<String> = db
+        .get_observer_profiles()
+        .into_iter()
+        .map(|p| p.name)
+        .collect();
+    rule_engine::compile(expression, &names)
+}
+
+/// Enabled rules that still compile; a rule can stop compiling when the
+/// profile it measures distance to is removed.
+pub fn enabled_rules(db: &Database) -> Vec<(AlertRule, CompiledRule)> {
+    db.get_alert_rules()
+        .into_iter()
+        .filter(|rule| rule.enabled)
+        .filter_map(|rule| match compile(db, &rule.expression) {
+            Ok(compiled) => Some((rule, compiled)),
+            Err(e) => {
+                log::warn!("Skipping alert rule \"{}\": {}", rule.name, e);
+                None
+            }
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub fn get_alert_rules(db: State<'_, Database>) -> Vec<AlertRule> {
+    db.get_alert_rules()
+}
+
+/// Check an expression without saving it. Returns the event kind it applies to.
+#[tauri::command]
+pub fn validate_rule(db: State<'_, Database>, expression: String) -> Result<String, String> {
+    compile(&db, &expression).map(|rule| rule.kind.as_str().to_string())
+}
+
+/// Create a rule, or replace the rule with `id`. Invalid expressions are rejected
+/// with the parser or type error.
+#[tauri::command]
+pub fn save_rule(
+    db: State<'_, Database>,
+    id: Option<i64>,
+    name: String,
+    expression: String,
+    enabled: Option<bool>,
+) -> Result<AlertRule, String> {
+    if name.trim().is_empty() {
+        return Err("Name cannot be empty".to_string());
+    }
+    compile(&db, &expression)?;
+    let enabled = enabled.unwrap_or(true);
+
+    match id {
+        Some(id) => match db.update_alert_rule(id, name.trim(), expression.trim(), enabled) {
+            Ok(Some(rule)) => Ok(rule),
+            Ok(None) => Err(format!("Unknown alert rule: {}", id)),
+            Err(e) => Err(format!("Failed to update alert rule: {}", e)),
+        },
+        None => db
+            .add_alert_rule(name.trim(), expression.trim(), enabled)
+            .map_err(|e| format!("Failed to add alert rule: {}", e)),
+    }
+}
+
+#[tauri::command]
+pub fn remove_rule(db: State<'_, Database>, id: i64) -> Result<(), String> {
+    db.remove_alert_rule(id)
+        .map_err(|e| format!("Failed to remove alert rule: {}", e))
+}
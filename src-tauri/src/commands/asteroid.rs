@@ -1,22 +1,289 @@
-use crate::db::Database;
-use crate::fetchers::asteroid::fetch_asteroids;
-use crate::models::asteroid::Asteroid;
+use crate::db::{Database, UserSettings};
+use crate::fetchers::asteroid::{
+    fetch_asteroids, fetch_close_approaches, fetch_impact_risks, fetch_orbit_class,
+    join_impact_risks, merge_close_approaches,
+};
+use crate::models::asteroid::{Asteroid, AsteroidApproach, AsteroidQuery, ImpactRisk};
+use std::collections::HashMap;
+use std::sync::Arc;
 use tauri::State;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 
+/// JPL close-approach lookahead and distance limit when not set in settings.
+const DEFAULT_LOOKAHEAD_DAYS: f64 = 60.0;
+const DEFAULT_MAX_DISTANCE_LUNAR: f64 = 20.0;
+
+/// SBDB lookups per refresh for objects without a stored orbit class, to
+/// stay polite to the JPL API; the rest are filled in on later refreshes.
+const ORBIT_LOOKUPS_PER_REFRESH: usize = 25;
+const ORBIT_LOOKUPS_AT_ONCE: usize = 5;
+/// Objects SBDB couldn't resolve are not asked about again for a day.
+const ORBIT_RETRY_SECS: i64 = 86400;
+
+/// Upcoming approaches from NeoWs and the JPL close-approach API, filtered
+/// and sorted by `query`.
 #[tauri::command]
-pub async fn get_asteroids(db: State<'_, Database>) -> Result<Vec<Asteroid>, String> {
+pub async fn get_asteroids(
+    db: State<'_, Database>,
+    query: Option<AsteroidQuery>,
+) -> Result<Vec<Asteroid>, String> {
+    let query = query.unwrap_or_default();
+
     // Try cache first (6 hour window)
-    if let Some(cached) = db.get_cached_response("nasa:neo", 21600) {
+    let key = cache_key(&db.get_settings());
+    if let Some(cached) = db.get_cached_response(&key, 21600) {
         if let Ok(asteroids) = serde_json::from_str::<Vec<Asteroid>>(&cached) {
             if !asteroids.is_empty() {
-                return Ok(asteroids);
+                return Ok(apply_query(asteroids, &query));
             }
         }
     }
 
-    let asteroids = fetch_asteroids().await?;
+    let asteroids = refresh_asteroids(&db).await?;
     if let Ok(json) = serde_json::to_string(&asteroids) {
-        db.set_cached_response("nasa:neo", &json);
+        db.set_cached_response(&key, &json);
     }
+    Ok(apply_query(asteroids, &query))
+}
+
+/// Cache key for the approach list, which depends on the lookahead and
+/// distance limit in settings.
+pub fn cache_key(settings: &UserSettings) -> String {
+    let (days, max_distance) = lookahead(settings);
+    format!("nasa:neo:{}:{}", days, max_distance)
+}
+
+fn lookahead(settings: &UserSettings) -> (u32, f64) {
+    let days = settings
+        .neo_lookahead_days
+        .unwrap_or(DEFAULT_LOOKAHEAD_DAYS)
+        .round() as u32;
+    let max_distance = settings
+        .neo_max_distance_lunar
+        .unwrap_or(DEFAULT_MAX_DISTANCE_LUNAR);
+    (days, max_distance)
+}
+
+/// Every stored approach of one object, past ones included, oldest first.
+#[tauri::command]
+pub fn get_asteroid_approaches(
+    db: State<'_, Database>,
+    designation: String,
+) -> Vec<AsteroidApproach> {
+    db.get_asteroid_approaches(designation.trim())
+}
+
+/// NeoWs approaches for the coming week merged with the longer JPL
+/// lookahead, with orbit classes and Sentry impact risk attached. Every
+/// approach is also stored.
+pub async fn refresh_asteroids(db: &Database) -> Result<Vec<Asteroid>, String> {
+    let (days, max_distance) = lookahead(&db.get_settings());
+
+    let (neows, jpl) = tokio::join!(
+        fetch_asteroids(),
+        fetch_close_approaches(days, max_distance)
+    );
+    let mut asteroids = match (neows, jpl) {
+        (Err(a), Err(b)) => return Err(format!("{}; {}", a, b)),
+        (Ok(mut asteroids), Ok(jpl)) => {
+            merge_close_approaches(&mut asteroids, jpl);
+            asteroids
+        }
+        (Ok(asteroids), Err(e)) | (Err(e), Ok(asteroids)) => {
+            log::warn!("{}", e);
+            asteroids
+        }
+    };
+
+    join_impact_risks(&mut asteroids, &impact_risks(db).await);
+
+    attach_orbit_classes(db, &mut asteroids).await;
+    db.store_asteroid_approaches(&asteroids);
     Ok(asteroids)
 }
+
+/// Orbit classes from the database, looking up a limited number of missing
+/// ones in SBDB a few at a time. Failed lookups are remembered for a day.
+async fn attach_orbit_classes(db: &Database, asteroids: &mut [Asteroid]) {
+    let orbit_key = |a: &Asteroid| {
+        if a.spk_id.is_empty() {
+            a.designation.clone()
+        } else {
+            a.spk_id.clone()
+        }
+    };
+    let failure_key = |key: &str| format!("sbdb:failed:{}", key);
+
+    let mut pending: Vec<(String, String, String)> = Vec::new();
+    for asteroid in asteroids.iter_mut() {
+        let key = orbit_key(asteroid);
+        if key.is_empty() {
+            continue;
+        }
+        asteroid.orbit_class = db.get_orbit_class(&key);
+        if asteroid.orbit_class.is_none()
+            && pending.len() < ORBIT_LOOKUPS_PER_REFRESH
+            && !pending.iter().any(|(k, _, _)| *k == key)
+            && db
+                .get_cached_response(&failure_key(&key), ORBIT_RETRY_SECS)
+                .is_none()
+        {
+            pending.push((key, asteroid.spk_id.clone(), asteroid.designation.clone()));
+        }
+    }
+    if pending.is_empty() {
+        return;
+    }
+
+    let permits = Arc::new(Semaphore::new(ORBIT_LOOKUPS_AT_ONCE));
+    let mut lookups = JoinSet::new();
+    for (key, spk_id, designation) in pending {
+        let permits = permits.clone();
+        lookups.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let class = fetch_orbit_class(&spk_id, &designation).await;
+            (key, class)
+        });
+    }
+    let mut found = HashMap::new();
+    while let Some(joined) = lookups.join_next().await {
+        let Ok((key, class)) = joined else {
+            continue;
+        };
+        match class {
+            Ok(class) => {
+                db.set_orbit_class(&key, &class);
+                found.insert(key, class);
+            }
+            Err(e) => {
+                log::warn!("Orbit class for {}: {}", key, e);
+                db.set_cached_response(&failure_key(&key), &e);
+            }
+        }
+    }
+    for asteroid in asteroids.iter_mut().filter(|a| a.orbit_class.is_none()) {
+        asteroid.orbit_class = found.get(&orbit_key(asteroid)).cloned();
+    }
+}
+
+fn apply_query(mut asteroids: Vec<Asteroid>, query: &AsteroidQuery) -> Vec<Asteroid> {
+    asteroids.retain(|a| {
+        query
+            .max_distance_lunar
+            .is_none_or(|max| a.miss_distance_lunar <= max)
+            && query
+                .min_diameter_km
+                .is_none_or(|min| a.diameter_km_max >= min)
+            && query
+                .min_velocity_kps
+                .is_none_or(|min| a.velocity_kps >= min)
+            && (!query.hazardous_only || a.is_hazardous || a.impact_risk.is_some())
+    });
+    match query.sort.as_deref() {
+        Some("distance") => {
+            asteroids.sort_by(|a, b| a.miss_distance_lunar.total_cmp(&b.miss_distance_lunar))
+        }
+        Some("size") => asteroids.sort_by(|a, b| b.diameter_km_max.total_cmp(&a.diameter_km_max)),
+        Some("velocity") => asteroids.sort_by(|a, b| b.velocity_kps.total_cmp(&a.velocity_kps)),
+        _ => asteroids.sort_by_key(|a| a.approach_time),
+    }
+    asteroids
+}
+
+/// The Sentry table changes slowly; refetch it at most every 12 hours.
+async fn impact_risks(db: &Database) -> Vec<ImpactRisk> {
+    if let Some(cached) = db.get_cached_response("jpl:sentry", 43200) {
+        if let Ok(risks) = serde_json::from_str::<Vec<ImpactRisk>>(&cached) {
+            return risks;
+        }
+    }
+
+    match fetch_impact_risks().await {
+        Ok(risks) => {
+            if let Ok(json) = serde_json::to_string(&risks) {
+                db.set_cached_response("jpl:sentry", &json);
+            }
+            risks
+        }
+        Err(e) => {
+            log::warn!("{}", e);
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asteroid(id: &str, lunar: f64, diameter: f64, velocity: f64, time: i64) -> Asteroid {
+        Asteroid {
+            id: id.to_string(),
+            name: id.to_string(),
+            diameter_km_min: diameter / 2.0,
+            diameter_km_max: diameter,
+            is_hazardous: false,
+            approach_date: String::new(),
+            approach_time: time,
+            velocity_kps: velocity,
+            miss_distance_km: lunar * 384_400.0,
+            miss_distance_lunar: lunar,
+            orbiting_body: "Earth".to_string(),
+            absolute_magnitude_h: None,
+            spk_id: String::new(),
+            designation: String::new(),
+            orbit_class: None,
+            approaches: Vec::new(),
+            impact_risk: None,
+        }
+    }
+
+    #[test]
+    fn queries_filter_and_sort() {
+        let all = vec![
+            asteroid("near", 1.0, 0.02, 8.0, 3),
+            asteroid("big", 15.0, 1.2, 20.0, 1),
+            asteroid("fast", 8.0, 0.1, 30.0, 2),
+        ];
+        let ids = |query: AsteroidQuery| -> Vec<String> {
+            apply_query(all.clone(), &query)
+                .into_iter()
+                .map(|a| a.id)
+                .collect()
+        };
+
+        assert_eq!(ids(AsteroidQuery::default()), ["big", "fast", "near"]);
+        let by = |sort: &str| AsteroidQuery {
+            sort: Some(sort.to_string()),
+            ..AsteroidQuery::default()
+        };
+        assert_eq!(ids(by("distance")), ["near", "fast", "big"]);
+        assert_eq!(ids(by("size")), ["big", "fast", "near"]);
+        assert_eq!(ids(by("velocity")), ["fast", "big", "near"]);
+        assert_eq!(
+            ids(AsteroidQuery {
+                max_distance_lunar: Some(10.0),
+                min_diameter_km: Some(0.05),
+                ..AsteroidQuery::default()
+            }),
+            ["fast"]
+        );
+        assert!(ids(AsteroidQuery {
+            hazardous_only: true,
+            ..AsteroidQuery::default()
+        })
+        .is_empty());
+    }
+
+    #[test]
+    fn cache_key_follows_lookahead_settings() {
+        let defaults = UserSettings::default();
+        let wider = UserSettings {
+            neo_max_distance_lunar: Some(50.0),
+            ..UserSettings::default()
+        };
+        assert_eq!(cache_key(&defaults), "nasa:neo:60:20");
+        assert_ne!(cache_key(&defaults), cache_key(&wider));
+    }
+}
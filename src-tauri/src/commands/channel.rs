@@ -0,0 +1,151 @@
+use crate::db::Database;
+use crate::delivery;
+use crate::models::channel::{AlertChannel, ChannelConfig};
+use crate::models::notification::NotificationRecord;
+use tauri::State;
+
+#[tauri::command]
+pub fn get_alert_channels(db: State<'_, Database>) -> Vec<AlertChannel> {
+    db.get_alert_channels().into_iter().map(redacted).collect()
+}
+
+/// Channels go to the frontend without their secrets.
+fn redacted(channel: AlertChannel) -> AlertChannel {
+    AlertChannel {
+        config: channel.config.redacted(),
+        ..channel
+    }
+}
+
+/// Create a channel, or replace the channel with `id`.
+#[tauri::command]
+pub fn save_alert_channel(
+    db: State<'_, Database>,
+    id: Option<i64>,
+    name: String,
+    mut config: ChannelConfig,
+    template: Option<String>,
+    enabled: Option<bool>,
+) -> Result<AlertChannel, String> {
+    if name.trim().is_empty() {
+        return Err("Name cannot be empty".to_string());
+    }
+    if let Some(stored) = id.and_then(|id| db.get_alert_channel(id)) {
+        config.restore_secrets(&stored.config);
+    }
+    delivery::validate_channel(&config)?;
+    let template = template.filter(|t| !t.trim().is_empty());
+    let enabled = enabled.unwrap_or(true);
+
+    let id = match id {
+        Some(id) => {
+            match db.update_alert_channel(id, name.trim(), &config, template.as_deref(), enabled) {
+                Ok(true) => id,
+                Ok(false) => return Err(format!("Unknown alert channel: {}", id)),
+                Err(e) => return Err(format!("Failed to update alert channel: {}", e)),
+            }
+        }
+        None => db
+            .add_alert_channel(name.trim(), &config, template.as_deref(), enabled)
+            .map_err(|e| format!("Failed to add alert channel: {}", e))?,
+    };
+    db.get_alert_channel(id)
+        .map(redacted)
+        .ok_or_else(|| format!("Unknown alert channel: {}", id))
+}
+
+#[tauri::command]
+pub fn remove_alert_channel(db: State<'_, Database>, id: i64) -> Result<(), String> {
+    db.remove_alert_channel(id)
+        .map_err(|e| format!("Failed to remove alert channel: {}", e))
+}
+
+/// Deliver a sample alert through one channel, with the usual retries, and
+/// report the outcome.
+#[tauri::command]
+pub async fn send_test_alert(db: State<'_, Database>, id: i64) -> Result<(), String> {
+    let channel = db
+        .get_alert_channel(id)
+        .ok_or_else(|| format!("Unknown alert channel: {}", id))?;
+    let record = NotificationRecord {
+        id: 0,
+        source: "test".to_string(),
+        event_id: "test".to_string(),
+        rule: "send_test_alert".to_string(),
+        title: "EarthPulse test alert".to_string(),
+        body: format!("Delivery to \"{}\" works.", channel.name),
+        latitude: None,
+        longitude: None,
+        suppressed: false,
+        held: false,
+        severity: None,
+        acknowledged_at: None,
+        created_at: chrono::Utc::now().timestamp(),
+    };
+    delivery::deliver(&channel, &record).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::channel::REDACTED;
+
+    #[test]
+    fn secrets_round_trip_through_redaction() {
+        let stored = ChannelConfig::Email {
+            host: "smtp.example.org".to_string(),
+            port: 587,
+            from: "alerts@example.org".to_string(),
+            to: vec!["ops@example.org".to_string()],
+            username: Some("alerts".to_string()),
+            password: Some("hunter2".to_string()),
+            security: None,
+        };
+        let mut shown = stored.redacted();
+        let ChannelConfig::Email { password, .. } = &shown else {
+            unreachable!()
+        };
+        assert_eq!(password.as_deref(), Some(REDACTED));
+
+        shown.restore_secrets(&stored);
+        let ChannelConfig::Email { password, .. } = &shown else {
+            unreachable!()
+        };
+        assert_eq!(password.as_deref(), Some("hunter2"));
+
+        // A newly typed secret replaces the stored one
+        let mut edited = ChannelConfig::Gotify {
+            server: "https://gotify.example.org".to_string(),
+            app_token: "new".to_string(),
+        };
+        edited.restore_secrets(&ChannelConfig::Gotify {
+            server: "https://gotify.example.org".to_string(),
+            app_token: "old".to_string(),
+        });
+        assert!(matches!(edited, ChannelConfig::Gotify { app_token, .. } if app_token == "new"));
+
+        // Generic webhook URLs often carry a token, so they are hidden too
+        let hook = ChannelConfig::Webhook {
+            url: "https://hooks.example.org/T0KEN".to_string(),
+        };
+        let mut shown = hook.redacted();
+        assert!(matches!(&shown, ChannelConfig::Webhook { url } if url == REDACTED));
+        shown.restore_secrets(&hook);
+        assert!(matches!(shown, ChannelConfig::Webhook { url } if url.ends_with("T0KEN")));
+    }
+
+    #[test]
+    fn email_addresses_reject_line_breaks() {
+        let email = |from: &str| ChannelConfig::Email {
+            host: "smtp.example.org".to_string(),
+            port: 587,
+            from: from.to_string(),
+            to: vec!["ops@example.org".to_string()],
+            username: None,
+            password: None,
+            security: None,
+        };
+        assert!(delivery::validate_channel(&email("alerts@example.org")).is_ok());
+        assert!(delivery::validate_channel(&email("alerts@example.org\r\nBcc: x@y.z")).is_err());
+    }
+}
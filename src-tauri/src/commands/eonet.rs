@@ -1,15 +1,19 @@
-use crate::db::Database;
-use crate::fetchers::eonet::fetch_eonet_events;
-use crate::models::eonet::NaturalEvent;
+use crate::db::{Database, UserSettings};
+use crate::fetchers::eonet::{
+    fetch_closed_eonet_events, fetch_eonet_categories, fetch_eonet_events, DEFAULT_CATEGORIES,
+};
+use crate::models::eonet::{EonetCategoryInfo, NaturalEvent};
+use chrono::NaiveDate;
 use tauri::State;
 
+/// Open events in the categories selected in settings.
 #[tauri::command]
 pub async fn get_eonet_events(db: State<'_, Database>) -> Result<Vec<NaturalEvent>, String> {
     // Try cache first (30 min window)
     if let Some(cached) = db.get_cached_response("eonet:events", 1800) {
         if let Ok(events) = serde_json::from_str::<Vec<NaturalEvent>>(&cached) {
             if !events.is_empty() {
-                return Ok(events);
+                return Ok(filter_selected(&db, events));
             }
         }
     }
@@ -18,5 +22,84 @@ pub async fn get_eonet_events(db: State<'_, Database>) -> Result<Vec<NaturalEven
     if let Ok(json) = serde_json::to_string(&events) {
         db.set_cached_response("eonet:events", &json);
     }
+    Ok(filter_selected(&db, events))
+}
+
+#[tauri::command]
+pub async fn get_eonet_categories(
+    db: State<'_, Database>,
+) -> Result<Vec<EonetCategoryInfo>, String> {
+    if let Some(cached) = db.get_cached_response("eonet:categories", 86400) {
+        if let Ok(categories) = serde_json::from_str::<Vec<EonetCategoryInfo>>(&cached) {
+            return Ok(categories);
+        }
+    }
+
+    let categories = fetch_eonet_categories().await?;
+    if let Ok(json) = serde_json::to_string(&categories) {
+        db.set_cached_response("eonet:categories", &json);
+    }
+    Ok(categories)
+}
+
+/// Closed events active at some point between `start` and `end`
+/// (YYYY-MM-DD), in the given categories or else the ones selected in
+/// settings.
+#[tauri::command]
+pub async fn get_closed_eonet_events(
+    db: State<'_, Database>,
+    start: String,
+    end: String,
+    categories: Option<Vec<String>>,
+) -> Result<Vec<NaturalEvent>, String> {
+    let parse = |date: &str| {
+        NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+            .map_err(|_| format!("Invalid date '{}', expected YYYY-MM-DD", date))
+    };
+    let (from, to) = (parse(&start)?, parse(&end)?);
+    if to < from {
+        return Err("End date must not be before start date".to_string());
+    }
+    if (to - from).num_days() > 366 {
+        return Err("Date range is limited to one year".to_string());
+    }
+    let categories = categories.unwrap_or_else(|| selected_categories(&db.get_settings()));
+    // The API reads no categories as all of them
+    if categories.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Closed events don't change, so the range can be cached for a day
+    let key = format!("eonet:closed:{}:{}:{}", from, to, categories.join(","));
+    if let Some(cached) = db.get_cached_response(&key, 86400) {
+        if let Ok(events) = serde_json::from_str::<Vec<NaturalEvent>>(&cached) {
+            return Ok(events);
+        }
+    }
+
+    let events = fetch_closed_eonet_events(&from.to_string(), &to.to_string(), &categories).await?;
+    if let Ok(json) = serde_json::to_string(&events) {
+        db.set_cached_response(&key, &json);
+    }
     Ok(events)
 }
+
+pub fn selected_categories(settings: &UserSettings) -> Vec<String> {
+    match &settings.eonet_categories {
+        Some(list) => list
+            .split(',')
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(String::from)
+            .collect(),
+        None => DEFAULT_CATEGORIES.iter().map(|c| c.to_string()).collect(),
+    }
+}
+
+pub fn filter_selected(db: &Database, events: Vec<NaturalEvent>) -> Vec<NaturalEvent> {
+    let selected = selected_categories(&db.get_settings());
+    events
+        .into_iter()
+        .filter(|e| selected.iter().any(|c| c == &e.category_id))
+        .collect()
+}
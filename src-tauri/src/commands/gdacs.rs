@@ -1,6 +1,6 @@
 use crate::db::Database;
-use crate::fetchers::gdacs::fetch_gdacs_alerts;
-use crate::models::gdacs::GdacsAlert;
+use crate::fetchers::gdacs::{fetch_gdacs_alerts, fetch_gdacs_geometry};
+use crate::models::gdacs::{GdacsAlert, GdacsEpisode};
 use tauri::State;
 
 #[tauri::command]
@@ -20,3 +20,64 @@ pub async fn get_gdacs_alerts(db: State<'_, Database>) -> Result<Vec<GdacsAlert>
     }
     Ok(alerts)
 }
+
+/// Recorded episodes of an alert, oldest first, to show how it escalated.
+#[tauri::command]
+pub fn get_gdacs_history(db: State<'_, Database>, id: String) -> Vec<GdacsEpisode> {
+    db.get_gdacs_episodes(&id)
+}
+
+/// Event footprint (cyclone tracks and wind buffers, shaking intensity,
+/// flood extent) of an alert's current episode as GeoJSON.
+#[tauri::command]
+pub async fn get_gdacs_geometry(
+    db: State<'_, Database>,
+    id: String,
+) -> Result<serde_json::Value, String> {
+    let alerts = match db
+        .get_cached_response("gdacs:rss", 86400)
+        .and_then(|json| serde_json::from_str::<Vec<GdacsAlert>>(&json).ok())
+    {
+        Some(alerts) => alerts,
+        None => fetch_gdacs_alerts().await?,
+    };
+    let alert = alerts
+        .iter()
+        .find(|a| a.id == id)
+        .ok_or_else(|| format!("Unknown GDACS alert: {}", id))?;
+    alert_geometry(&db, alert).await
+}
+
+/// Geometry for an alert, cached per event and episode. A published episode
+/// doesn't change, so it is kept for a day; without an episode id GDACS
+/// returns the latest episode, which is only kept as long as the alert feed.
+pub async fn alert_geometry(
+    db: &Database,
+    alert: &GdacsAlert,
+) -> Result<serde_json::Value, String> {
+    if alert.event_id.is_empty() {
+        return Err("GDACS alert has no event id".to_string());
+    }
+    let (episode, max_age) = match alert.episode_id.as_deref() {
+        Some(episode) => (episode, 86400),
+        None => ("latest", 900),
+    };
+    let key = format!(
+        "gdacs:geometry:{}:{}:{}",
+        alert.alert_type, alert.event_id, episode
+    );
+    if let Some(cached) = db.get_cached_response(&key, max_age) {
+        if let Ok(value) = serde_json::from_str(&cached) {
+            return Ok(value);
+        }
+    }
+
+    let value = fetch_gdacs_geometry(
+        &alert.alert_type,
+        &alert.event_id,
+        alert.episode_id.as_deref(),
+    )
+    .await?;
+    db.set_cached_response(&key, &value.to_string());
+    Ok(value)
+}
@@ -0,0 +1,120 @@
+use crate::calculations::{gazetteer, orbit};
+use crate::db::Database;
+use crate::fetchers::geocode;
+use crate::models::geocode::{Place, PlaceLabel};
+use tauri::State;
+
+/// Online lookups are cached for 30 days; Nominatim's usage policy asks for it.
+const GEOCODE_CACHE_TTL: i64 = 30 * 86400;
+
+#[tauri::command]
+pub async fn geocode(
+    db: State<'_, Database>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<Place>, String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Err("Search text cannot be empty".to_string());
+    }
+    let limit = limit.unwrap_or(5).clamp(1, 20);
+
+    // Raw "lat, lon" input resolves without any lookup
+    if let Some((lat, lon)) = parse_coordinates(query) {
+        return Ok(vec![Place {
+            name: format!("{:.4}, {:.4}", lat, lon),
+            country: String::new(),
+            country_code: String::new(),
+            latitude: lat,
+            longitude: lon,
+            population: None,
+            source: "coordinates".to_string(),
+        }]);
+    }
+
+    if let Some(base_url) = db.get_settings().geocoder_url {
+        let cache_key = format!("geocode:{}:{}", query.to_lowercase(), limit);
+        if let Some(cached) = db.get_cached_response(&cache_key, GEOCODE_CACHE_TTL) {
+            if let Ok(places) = serde_json::from_str::<Vec<Place>>(&cached) {
+                return Ok(places);
+            }
+        }
+
+        match geocode::search_nominatim(&base_url, query, limit).await {
+            Ok(places) if !places.is_empty() => {
+                if let Ok(json) = serde_json::to_string(&places) {
+                    db.set_cached_response(&cache_key, &json);
+                }
+                return Ok(places);
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("Online geocoding failed, using gazetteer: {}", e),
+        }
+    }
+
+    Ok(gazetteer::search(query, limit))
+}
+
+#[tauri::command]
+pub async fn reverse_geocode(
+    db: State<'_, Database>,
+    lat: f64,
+    lon: f64,
+) -> Result<Option<PlaceLabel>, String> {
+    if !lat.is_finite() || !lon.is_finite() || lat.abs() > 90.0 || lon.abs() > 180.0 {
+        return Err("Invalid coordinates".to_string());
+    }
+
+    if let Some(base_url) = db.get_settings().geocoder_url {
+        let cache_key = format!("reverse_geocode:{:.3}:{:.3}", lat, lon);
+        if let Some(cached) = db.get_cached_response(&cache_key, GEOCODE_CACHE_TTL) {
+            if let Ok(label) = serde_json::from_str::<Option<PlaceLabel>>(&cached) {
+                return Ok(label);
+            }
+        }
+
+        match geocode::reverse_nominatim(&base_url, lat, lon).await {
+            Ok(Some(place)) => {
+                let label = Some(PlaceLabel {
+                    label: if place.country.is_empty() {
+                        place.name.clone()
+                    } else {
+                        format!("{}, {}", place.name, place.country)
+                    },
+                    distance_km: orbit::great_circle_km(place.latitude, place.longitude, lat, lon),
+                    place,
+                });
+                if let Ok(json) = serde_json::to_string(&label) {
+                    db.set_cached_response(&cache_key, &json);
+                }
+                return Ok(label);
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("Online reverse geocoding failed, using gazetteer: {}", e),
+        }
+    }
+
+    Ok(gazetteer::describe(lat, lon))
+}
+
+fn parse_coordinates(text: &str) -> Option<(f64, f64)> {
+    let (lat, lon) = text.split_once(',')?;
+    let lat: f64 = lat.trim().parse().ok()?;
+    let lon: f64 = lon.trim().parse().ok()?;
+    if !lat.is_finite() || !lon.is_finite() || lat.abs() > 90.0 || lon.abs() > 180.0 {
+        return None;
+    }
+    Some((lat, lon))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_coordinates;
+
+    #[test]
+    fn parse_coordinates_accepts_decimal_pairs_only() {
+        assert_eq!(parse_coordinates("37.3382, -121.8863"), Some((37.3382, -121.8863)));
+        assert_eq!(parse_coordinates("San Jose, US"), None);
+        assert_eq!(parse_coordinates("95, 10"), None);
+    }
+}
@@ -1,10 +1,23 @@
+use crate::commands::satellite::load_iss_propagator;
 use crate::db::Database;
-use crate::models::iss::IssData;
+use crate::models::iss::{IssData, IssPosition};
 use tauri::State;
 
 #[tauri::command]
 pub async fn get_iss_position(db: State<'_, Database>) -> Result<IssData, String> {
-    let current = db.get_latest_iss_position().ok_or("No ISS data yet")?;
+    let now = chrono::Utc::now().timestamp();
+    let propagated = load_iss_propagator(&db)
+        .await
+        .and_then(|p| p.geodetic_at(now))
+        .map(|(latitude, longitude, _)| IssPosition {
+            latitude,
+            longitude,
+            timestamp: now,
+        });
+
+    let current = propagated
+        .or_else(|| db.get_latest_iss_position())
+        .ok_or("No ISS data yet")?;
     let trail = db.get_iss_trail();
     Ok(IssData { current, trail })
 }
@@ -1,12 +1,18 @@
 pub mod air_quality;
+pub mod alert_rule;
 pub mod asteroid;
+pub mod channel;
 pub mod earthquake;
 pub mod eonet;
 pub mod gdacs;
+pub mod geocode;
 pub mod historical;
 pub mod iss;
 pub mod meteor;
+pub mod notification;
+pub mod observer;
 pub mod plate;
+pub mod reentry;
 pub mod replay;
 pub mod satellite;
 pub mod settings;
@@ -15,6 +21,9 @@ pub mod solar_event;
 pub mod summary;
 pub mod sst;
 pub mod terminator;
+pub mod tsunami;
+pub mod vaac;
 pub mod volcano;
 pub mod watchlist;
 pub mod weather;
+pub mod weather_alert;
@@ -0,0 +1,116 @@
+use crate::db::Database;
+use crate::models::notification::{NotificationRecord, NotificationRegion, NotificationSnooze};
+use crate::notifications::in_region;
+use tauri::State;
+
+const MAX_SNOOZE_MINUTES: u32 = 30 * 24 * 60;
+
+/// Notification history, newest first, filtered by event type, age,
+/// acknowledgement and area.
+#[tauri::command]
+pub fn get_notification_history(
+    db: State<'_, Database>,
+    source: Option<String>,
+    since: Option<i64>,
+    unacknowledged_only: Option<bool>,
+    region: Option<NotificationRegion>,
+    limit: Option<u32>,
+) -> Vec<NotificationRecord> {
+    let limit = limit.unwrap_or(200).min(5000);
+    let records = db.get_notification_history(
+        source.as_deref(),
+        since,
+        unacknowledged_only.unwrap_or(false),
+        if region.is_some() { u32::MAX } else { limit },
+    );
+    match region {
+        Some(region) => records
+            .into_iter()
+            .filter(|r| matches_region(r, &region))
+            .take(limit as usize)
+            .collect(),
+        None => records,
+    }
+}
+
+/// Acknowledge the given notifications, or every unacknowledged one matching
+/// `source` and `region`. Returns how many were acknowledged.
+#[tauri::command]
+pub fn acknowledge_notifications(
+    db: State<'_, Database>,
+    ids: Option<Vec<i64>>,
+    source: Option<String>,
+    region: Option<NotificationRegion>,
+) -> Result<usize, String> {
+    let ids = match ids {
+        Some(ids) => ids,
+        None => {
+            if source.is_none() && region.is_none() {
+                return Err("Give notification ids, a source or a region".to_string());
+            }
+            db.get_notification_history(source.as_deref(), None, true, u32::MAX)
+                .into_iter()
+                .filter(|r| {
+                    region
+                        .as_ref()
+                        .is_none_or(|region| matches_region(r, region))
+                })
+                .map(|r| r.id)
+                .collect()
+        }
+    };
+    db.acknowledge_notifications(&ids)
+        .map_err(|e| format!("Failed to acknowledge notifications: {}", e))
+}
+
+/// Silence toasts for an event type, an area, or both, for `minutes`.
+/// Notifications are still recorded in the history while snoozed.
+#[tauri::command]
+pub fn snooze_notifications(
+    db: State<'_, Database>,
+    source: Option<String>,
+    region: Option<NotificationRegion>,
+    minutes: u32,
+) -> Result<NotificationSnooze, String> {
+    if minutes == 0 || minutes > MAX_SNOOZE_MINUTES {
+        return Err(format!(
+            "Snooze must last between 1 and {} minutes",
+            MAX_SNOOZE_MINUTES
+        ));
+    }
+    if let Some(region) = &region {
+        validate_region(region)?;
+    }
+    let source = source.filter(|s| !s.trim().is_empty());
+    let until = chrono::Utc::now().timestamp() + i64::from(minutes) * 60;
+    db.add_notification_snooze(source.as_deref().map(str::trim), region.as_ref(), until)
+        .map_err(|e| format!("Failed to snooze notifications: {}", e))
+}
+
+#[tauri::command]
+pub fn get_notification_snoozes(db: State<'_, Database>) -> Vec<NotificationSnooze> {
+    db.get_active_snoozes(chrono::Utc::now().timestamp())
+}
+
+#[tauri::command]
+pub fn cancel_notification_snooze(db: State<'_, Database>, id: i64) -> Result<(), String> {
+    db.remove_notification_snooze(id)
+        .map_err(|e| format!("Failed to cancel snooze: {}", e))
+}
+
+fn matches_region(record: &NotificationRecord, region: &NotificationRegion) -> bool {
+    match (record.latitude, record.longitude) {
+        (Some(lat), Some(lon)) => in_region(region, (lat, lon)),
+        _ => false,
+    }
+}
+
+fn validate_region(region: &NotificationRegion) -> Result<(), String> {
+    if !(-90.0..=90.0).contains(&region.latitude) || !(-180.0..=180.0).contains(&region.longitude) {
+        return Err("Region centre out of range".to_string());
+    }
+    if !region.radius_km.is_finite() || region.radius_km <= 0.0 || region.radius_km > 20000.0 {
+        return Err("Region radius must be between 0 and 20000 km".to_string());
+    }
+    Ok(())
+}
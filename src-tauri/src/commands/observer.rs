@@ -0,0 +1,156 @@
+use crate::db::Database;
+use crate::models::observer::ObserverProfile;
+use tauri::State;
+
+/// The active observer, falling back to the legacy single location when no profile exists.
+pub fn active_observer(db: &Database) -> ObserverProfile {
+    db.get_active_observer_profile().unwrap_or_else(|| {
+        let settings = db.get_settings();
+        ObserverProfile {
+            id: 0,
+            name: "Home".to_string(),
+            latitude: settings.user_lat.unwrap_or(37.3382),
+            longitude: settings.user_lon.unwrap_or(-121.8863),
+            elevation_m: 0.0,
+            timezone: "UTC".to_string(),
+            is_active: true,
+            created_at: 0,
+        }
+    })
+}
+
+/// Resolve a location from explicit coordinates, then a profile id, then the active profile.
+pub fn resolve_location(
+    db: &Database,
+    lat: Option<f64>,
+    lon: Option<f64>,
+    profile_id: Option<i64>,
+) -> Result<(f64, f64), String> {
+    if let (Some(lat), Some(lon)) = (lat, lon) {
+        return Ok((lat, lon));
+    }
+    let observer = match profile_id {
+        Some(id) => db
+            .get_observer_profile(id)
+            .ok_or_else(|| format!("Unknown observer profile: {}", id))?,
+        None => active_observer(db),
+    };
+    Ok((observer.latitude, observer.longitude))
+}
+
+#[tauri::command]
+pub fn get_observer_profiles(db: State<'_, Database>) -> Vec<ObserverProfile> {
+    db.get_observer_profiles()
+}
+
+#[tauri::command]
+pub fn add_observer_profile(
+    db: State<'_, Database>,
+    name: String,
+    lat: f64,
+    lon: f64,
+    elevation_m: Option<f64>,
+    timezone: Option<String>,
+) -> Result<ObserverProfile, String> {
+    let elevation_m = elevation_m.unwrap_or(0.0);
+    let timezone = timezone.unwrap_or_else(|| "UTC".to_string());
+    validate_profile(&name, lat, lon, elevation_m, &timezone)?;
+    db.add_observer_profile(name.trim(), lat, lon, elevation_m, timezone.trim())
+        .map_err(|e| format!("Failed to add observer profile: {}", e))
+}
+
+#[tauri::command]
+pub fn update_observer_profile(
+    db: State<'_, Database>,
+    id: i64,
+    name: String,
+    lat: f64,
+    lon: f64,
+    elevation_m: f64,
+    timezone: String,
+) -> Result<(), String> {
+    validate_profile(&name, lat, lon, elevation_m, &timezone)?;
+    match db.update_observer_profile(id, name.trim(), lat, lon, elevation_m, timezone.trim()) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(format!("Unknown observer profile: {}", id)),
+        Err(e) => Err(format!("Failed to update observer profile: {}", e)),
+    }
+}
+
+#[tauri::command]
+pub fn set_active_observer_profile(db: State<'_, Database>, id: i64) -> Result<(), String> {
+    match db.set_active_observer_profile(id) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(format!("Unknown observer profile: {}", id)),
+        Err(e) => Err(format!("Failed to switch observer profile: {}", e)),
+    }
+}
+
+#[tauri::command]
+pub fn remove_observer_profile(db: State<'_, Database>, id: i64) -> Result<(), String> {
+    let profiles = db.get_observer_profiles();
+    let profile = profiles
+        .iter()
+        .find(|p| p.id == id)
+        .ok_or_else(|| format!("Unknown observer profile: {}", id))?;
+    if profile.is_active {
+        return Err("Switch to another profile before removing the active one".to_string());
+    }
+    db.remove_observer_profile(id)
+        .map_err(|e| format!("Failed to remove observer profile: {}", e))
+}
+
+fn validate_profile(
+    name: &str,
+    lat: f64,
+    lon: f64,
+    elevation_m: f64,
+    timezone: &str,
+) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Name cannot be empty".to_string());
+    }
+    if !lat.is_finite() || !(-90.0..=90.0).contains(&lat) {
+        return Err("Latitude must be between -90 and 90".to_string());
+    }
+    if !lon.is_finite() || !(-180.0..=180.0).contains(&lon) {
+        return Err("Longitude must be between -180 and 180".to_string());
+    }
+    if !elevation_m.is_finite() || !(-500.0..=9000.0).contains(&elevation_m) {
+        return Err("Elevation must be between -500 and 9000 m".to_string());
+    }
+    if !is_valid_timezone_name(timezone.trim()) {
+        return Err(format!("Invalid timezone: {}", timezone));
+    }
+    Ok(())
+}
+
+/// Known IANA zone names ("UTC", "Europe/Berlin", "America/Argentina/Salta"),
+/// so quiet hours can be read in the profile's local time.
+fn is_valid_timezone_name(tz: &str) -> bool {
+    tz.parse::<chrono_tz::Tz>().is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_valid_timezone_name, validate_profile};
+
+    #[test]
+    fn timezone_names_must_be_known_iana_zones() {
+        assert!(is_valid_timezone_name("UTC"));
+        assert!(is_valid_timezone_name("America/Los_Angeles"));
+        assert!(is_valid_timezone_name("America/Argentina/Salta"));
+        assert!(!is_valid_timezone_name("PST"));
+        assert!(!is_valid_timezone_name("europe/berlin"));
+        assert!(!is_valid_timezone_name("Europe//Berlin"));
+        assert!(!is_valid_timezone_name("Mars/Olympus_Mons"));
+    }
+
+    #[test]
+    fn validate_profile_rejects_bad_input() {
+        assert!(validate_profile("Home", 37.3, -121.9, 25.0, "America/Los_Angeles").is_ok());
+        assert!(validate_profile("  ", 37.3, -121.9, 25.0, "UTC").is_err());
+        assert!(validate_profile("Home", 97.0, -121.9, 25.0, "UTC").is_err());
+        assert!(validate_profile("Home", 37.3, -121.9, f64::NAN, "UTC").is_err());
+    }
+}
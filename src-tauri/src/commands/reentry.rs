@@ -0,0 +1,201 @@
+use crate::calculations::decay::{self, ElementSample};
+use crate::calculations::orbit::{self, Propagator};
+use crate::commands::satellite::load_tracked_objects;
+use crate::db::Database;
+use crate::models::satellite::{DecayPrediction, DecayReport, DecayStatus, ReentryWindow};
+use tauri::State;
+
+/// Reentries further out than this are not reported.
+const REENTRY_LOOKAHEAD_SECS: i64 = 30 * 86400;
+
+/// History window used to fit the decay rate.
+const DECAY_HISTORY_SECS: i64 = 30 * 86400;
+
+/// Relative uncertainty applied to reentry times without a published window.
+const ESTIMATE_UNCERTAINTY: f64 = 0.2;
+
+pub async fn get_reentry_predictions_inner(db: &Database) -> DecayReport {
+    let predictions: Vec<DecayPrediction> = db
+        .get_cached_response("decay:predictions", 86400)
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+
+    let objects = load_tracked_objects(db).await;
+    let now = chrono::Utc::now().timestamp();
+
+    let mut statuses = Vec::new();
+    let mut reentries = Vec::new();
+
+    for obj in &objects {
+        let norad_id = obj.id.trim_start_matches("sat-");
+        let mut samples: Vec<ElementSample> = db
+            .get_tle_history(norad_id, now - DECAY_HISTORY_SECS)
+            .iter()
+            .filter_map(|r| {
+                let p = Propagator::from_tle(&r.name, &r.line1, &r.line2)?;
+                let (perigee_km, apogee_km) = p.shell_km();
+                Some(ElementSample {
+                    epoch_unix: p.epoch_unix(),
+                    perigee_km,
+                    apogee_km,
+                })
+            })
+            .collect();
+
+        // A reboost resets the trend; only fit the drag since the last burn
+        if let Some(&(burn_epoch, _)) = decay::detect_maneuvers(&samples).last() {
+            samples.retain(|s| s.epoch_unix >= burn_epoch);
+        }
+
+        let elements = &obj.propagator.elements;
+        let (perigee_km, apogee_km) = obj.propagator.shell_km();
+        let rate = decay::decay_rate_km_per_day(
+            &samples,
+            orbit::semi_major_axis_km(elements.mean_motion),
+            elements.mean_motion,
+            elements.mean_motion_dot,
+        );
+        let estimated_reentry = decay::days_to_reentry(perigee_km, rate)
+            .map(|days| obj.propagator.epoch_unix() as i64 + (days * 86400.0) as i64);
+
+        statuses.push(DecayStatus {
+            satellite_id: obj.id.clone(),
+            name: obj.name.clone(),
+            perigee_km,
+            apogee_km,
+            decay_rate_km_per_day: rate,
+            history_points: samples.len(),
+            estimated_reentry,
+        });
+
+        // Published predictions take precedence over our own estimate
+        let window = match latest_prediction(&predictions, norad_id) {
+            Some(p) => Some(prediction_window(p, now)),
+            None => {
+                estimated_reentry.map(|t| ("estimate".to_string(), t, estimate_half_width(t, now)))
+            }
+        };
+        let Some((source, predicted_time, half_width)) = window else {
+            continue;
+        };
+        if predicted_time - now > REENTRY_LOOKAHEAD_SECS || predicted_time + half_width < now {
+            continue;
+        }
+
+        let window_start = predicted_time - half_width;
+        let window_end = predicted_time + half_width;
+        let predicted_point = latest_prediction(&predictions, norad_id)
+            .and_then(|p| Some([p.latitude?, p.longitude?]));
+
+        reentries.push(ReentryWindow {
+            satellite_id: obj.id.clone(),
+            name: obj.name.clone(),
+            source,
+            predicted_time,
+            window_start,
+            window_end,
+            latitude_band: latitude_band(elements.inclination),
+            ground_track: reentry_ground_track(&obj.propagator, window_start, window_end),
+            predicted_point,
+        });
+    }
+
+    // Predictions for objects we do not propagate still get a window, without a track
+    for p in &predictions {
+        let satellite_id = format!("sat-{}", p.norad_id);
+        if objects.iter().any(|o| o.id == satellite_id)
+            || reentries.iter().any(|r| r.satellite_id == satellite_id)
+            || latest_prediction(&predictions, &p.norad_id).map(|l| l.message_epoch)
+                != Some(p.message_epoch)
+        {
+            continue;
+        }
+        let (source, predicted_time, half_width) = prediction_window(p, now);
+        if predicted_time - now > REENTRY_LOOKAHEAD_SECS || predicted_time + half_width < now {
+            continue;
+        }
+
+        reentries.push(ReentryWindow {
+            satellite_id,
+            name: p
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("NORAD {}", p.norad_id)),
+            source,
+            predicted_time,
+            window_start: predicted_time - half_width,
+            window_end: predicted_time + half_width,
+            latitude_band: p.inclination.map(latitude_band).unwrap_or([-90.0, 90.0]),
+            ground_track: Vec::new(),
+            predicted_point: p.latitude.zip(p.longitude).map(|(lat, lon)| [lat, lon]),
+        });
+    }
+
+    reentries.sort_by_key(|r| r.predicted_time);
+    DecayReport {
+        objects: statuses,
+        reentries,
+    }
+}
+
+fn latest_prediction<'a>(
+    predictions: &'a [DecayPrediction],
+    norad_id: &str,
+) -> Option<&'a DecayPrediction> {
+    predictions
+        .iter()
+        .filter(|p| p.norad_id == norad_id)
+        .max_by_key(|p| p.message_epoch.unwrap_or(0))
+}
+
+/// Source label, predicted time and half-width (seconds) of a published prediction.
+fn prediction_window(p: &DecayPrediction, now: i64) -> (String, i64, i64) {
+    let half_width = match p.window_mins {
+        Some(mins) => (mins * 60.0) as i64,
+        None => estimate_half_width(p.decay_epoch, now),
+    };
+    (p.source.clone(), p.decay_epoch, half_width)
+}
+
+fn estimate_half_width(predicted_time: i64, now: i64) -> i64 {
+    let remaining = (predicted_time - now).max(0) as f64;
+    ((remaining * ESTIMATE_UNCERTAINTY) as i64).max(3600)
+}
+
+fn latitude_band(inclination_deg: f64) -> [f64; 2] {
+    let max_lat = if inclination_deg > 90.0 {
+        180.0 - inclination_deg
+    } else {
+        inclination_deg
+    };
+    [-max_lat, max_lat]
+}
+
+/// Sub-satellite track across the reentry window; the object may come down anywhere along it.
+fn reentry_ground_track(propagator: &Propagator, start: i64, end: i64) -> Vec<[f64; 2]> {
+    // Beyond a few days the track fills the whole latitude band anyway
+    if end - start > 3 * 86400 {
+        return Vec::new();
+    }
+    let step = ((end - start) / 1000).max(60);
+    (start..=end)
+        .step_by(step as usize)
+        .filter_map(|t| propagator.geodetic_at(t).map(|(lat, lon, _)| [lat, lon]))
+        .collect()
+}
+
+#[tauri::command]
+pub async fn get_reentry_predictions(db: State<'_, Database>) -> Result<DecayReport, String> {
+    // Try cache first (6 hour window)
+    if let Some(cached) = db.get_cached_response("reentry:report", 21600) {
+        if let Ok(report) = serde_json::from_str::<DecayReport>(&cached) {
+            return Ok(report);
+        }
+    }
+
+    let report = get_reentry_predictions_inner(&db).await;
+    if let Ok(json) = serde_json::to_string(&report) {
+        db.set_cached_response("reentry:report", &json);
+    }
+    Ok(report)
+}
@@ -1,9 +1,20 @@
-use crate::calculations::orbit;
+use crate::calculations::conjunction::{self, TrackedObject};
+use crate::calculations::decay::{self, ElementSample};
+use crate::calculations::orbit::{self, Propagator};
+use crate::commands::observer::{active_observer, resolve_location};
+use crate::commands::weather;
 use crate::db::Database;
 use crate::fetchers::tle;
-use crate::models::satellite::{PassPrediction, SatelliteData};
+use crate::fetchers::weather::conditions_at;
+use crate::ics;
+use crate::models::satellite::{
+    Conjunction, Maneuver, PassPrediction, SatelliteCoverage, SatelliteData, TleHistory,
+    TleHistoryPoint, TleRecord, TleWarning,
+};
 use tauri::State;
 
+const ISS_CAT_NR: &str = "25544";
+
 const SATELLITES: &[(&str, &str)] = &[
     ("25544", "https://celestrak.org/NORAD/elements/gp.php?CATNR=25544&FORMAT=TLE"),
     ("20580", "https://celestrak.org/NORAD/elements/gp.php?CATNR=20580&FORMAT=TLE"),
@@ -35,6 +46,19 @@ async fn get_tle_cached(
         .join("\n");
     db.set_cached_response(&cache_key, &cache_text);
 
+    // Keep every distinct element set for decay estimation
+    for pair in &pairs {
+        if let Some(propagator) = Propagator::from_tle(&pair.name, &pair.line1, &pair.line2) {
+            db.store_tle(&TleRecord {
+                norad_id: cat_nr.to_string(),
+                name: pair.name.clone(),
+                epoch: propagator.epoch_unix() as i64,
+                line1: pair.line1.clone(),
+                line2: pair.line2.clone(),
+            });
+        }
+    }
+
     Ok(pairs)
 }
 
@@ -46,6 +70,8 @@ pub async fn get_satellite_positions_inner(db: &Database) -> Result<SatelliteDat
     let now = chrono::Utc::now().timestamp();
     let mut positions = Vec::new();
     let mut orbits = Vec::new();
+    let mut warnings = Vec::new();
+    let max_age_hours = db.get_settings().tle_max_age_hours.unwrap_or(72.0);
 
     for (cat_nr, url) in SATELLITES {
         match get_tle_cached(db, cat_nr, url).await {
@@ -53,6 +79,25 @@ pub async fn get_satellite_positions_inner(db: &Database) -> Result<SatelliteDat
                 for pair in &pairs {
                     let id = format!("sat-{}", cat_nr);
 
+                    if let Some(propagator) =
+                        Propagator::from_tle(&pair.name, &pair.line1, &pair.line2)
+                    {
+                        let tle_epoch = propagator.epoch_unix() as i64;
+                        let age_hours = (now - tle_epoch) as f64 / 3600.0;
+                        if age_hours > max_age_hours {
+                            warnings.push(TleWarning {
+                                satellite_id: id.clone(),
+                                name: pair.name.clone(),
+                                tle_epoch,
+                                age_hours,
+                                message: format!(
+                                    "Position based on a {:.0} hour old element set; accuracy is degraded",
+                                    age_hours
+                                ),
+                            });
+                        }
+                    }
+
                     if let Some(pos) = orbit::propagate_position(
                         &id, &pair.name, &pair.line1, &pair.line2, now,
                     ) {
@@ -71,14 +116,23 @@ pub async fn get_satellite_positions_inner(db: &Database) -> Result<SatelliteDat
         }
     }
 
-    Ok(SatelliteData { positions, orbits })
+    Ok(SatelliteData {
+        positions,
+        orbits,
+        warnings,
+    })
 }
 
 pub async fn get_pass_predictions_inner(db: &Database) -> Result<Vec<PassPrediction>, String> {
-    let settings = db.get_settings();
-    let user_lat = settings.user_lat.unwrap_or(37.3382);
-    let user_lon = settings.user_lon.unwrap_or(-121.8863);
+    let observer = active_observer(db);
+    get_pass_predictions_at(db, observer.latitude, observer.longitude).await
+}
 
+pub async fn get_pass_predictions_at(
+    db: &Database,
+    user_lat: f64,
+    user_lon: f64,
+) -> Result<Vec<PassPrediction>, String> {
     let mut all_passes = Vec::new();
 
     for (cat_nr, url) in SATELLITES {
@@ -98,15 +152,265 @@ pub async fn get_pass_predictions_inner(db: &Database) -> Result<Vec<PassPredict
     }
 
     all_passes.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+
+    // A forecast failure shouldn't hide the passes themselves
+    match weather::get_forecast_at(db, user_lat, user_lon).await {
+        Ok(forecast) => {
+            for pass in &mut all_passes {
+                pass.cloud_cover_pct =
+                    conditions_at(&forecast, pass.start_time).and_then(|c| c.cloud_cover_pct);
+            }
+        }
+        Err(e) => log::warn!("No forecast for pass predictions: {}", e),
+    }
     Ok(all_passes)
 }
 
+pub async fn get_satellite_coverage_inner(
+    db: &Database,
+    satellite_id: &str,
+    start_time: i64,
+    duration_mins: i64,
+    min_elevation: f64,
+    lat: f64,
+    lon: f64,
+) -> Result<SatelliteCoverage, String> {
+    let (cat_nr, url) = SATELLITES
+        .iter()
+        .find(|(cat_nr, _)| satellite_id.strip_prefix("sat-") == Some(*cat_nr))
+        .ok_or_else(|| format!("Unknown satellite: {}", satellite_id))?;
+
+    let pairs = get_tle_cached(db, cat_nr, url).await?;
+    let pair = pairs.first().ok_or("No TLE available")?;
+
+    orbit::predict_coverage(
+        satellite_id,
+        &pair.name,
+        &pair.line1,
+        &pair.line2,
+        start_time,
+        duration_mins,
+        min_elevation,
+        (lat, lon),
+    )
+    .ok_or_else(|| "Failed to propagate satellite orbit".to_string())
+}
+
+/// ISS propagator from the cached TLE, falling back to the newest stored
+/// element set when CelesTrak is unreachable.
+pub async fn load_iss_propagator(db: &Database) -> Option<Propagator> {
+    let (cat_nr, url) = SATELLITES.iter().find(|(cat_nr, _)| *cat_nr == ISS_CAT_NR)?;
+
+    match get_tle_cached(db, cat_nr, url).await {
+        Ok(pairs) => {
+            if let Some(pair) = pairs.first() {
+                return Propagator::from_tle(&pair.name, &pair.line1, &pair.line2);
+            }
+        }
+        Err(e) => log::warn!("Failed to get ISS TLE, using stored history: {}", e),
+    }
+
+    let record = db.get_tle_history(cat_nr, 0).pop()?;
+    Propagator::from_tle(&record.name, &record.line1, &record.line2)
+}
+
+/// Load the current element set of every tracked satellite.
+pub async fn load_tracked_objects(db: &Database) -> Vec<TrackedObject> {
+    let mut objects = Vec::new();
+
+    for (cat_nr, url) in SATELLITES {
+        match get_tle_cached(db, cat_nr, url).await {
+            Ok(pairs) => {
+                for pair in &pairs {
+                    if let Some(propagator) =
+                        Propagator::from_tle(&pair.name, &pair.line1, &pair.line2)
+                    {
+                        objects.push(TrackedObject {
+                            id: format!("sat-{}", cat_nr),
+                            name: pair.name.clone(),
+                            propagator,
+                        });
+                    }
+                }
+            }
+            Err(e) => log::warn!("Failed to get TLE for {}: {}", cat_nr, e),
+        }
+    }
+
+    objects
+}
+
+pub async fn get_conjunctions_inner(db: &Database) -> Result<Vec<Conjunction>, String> {
+    let settings = db.get_settings();
+    let days = settings.conjunction_days.unwrap_or(3.0);
+    let threshold_km = settings.conjunction_threshold_km.unwrap_or(10.0);
+
+    let objects = load_tracked_objects(db).await;
+    if objects.len() < 2 {
+        return Err("Not enough tracked satellites to screen".to_string());
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    // Pairwise screening is CPU-bound; keep it off the async workers
+    tokio::task::spawn_blocking(move || {
+        conjunction::screen_conjunctions(&objects, now, days, threshold_km)
+    })
+    .await
+    .map_err(|e| format!("Conjunction screening failed: {}", e))
+}
+
+/// Stored element sets for one satellite, as altitude points plus detected maneuvers.
+pub fn get_tle_history_inner(
+    db: &Database,
+    satellite_id: &str,
+    days: f64,
+) -> Result<TleHistory, String> {
+    let norad_id = satellite_id.strip_prefix("sat-").unwrap_or(satellite_id);
+    let since = chrono::Utc::now().timestamp() - (days * 86400.0) as i64;
+
+    let records = db.get_tle_history(norad_id, since);
+    let propagators: Vec<Propagator> = records
+        .iter()
+        .filter_map(|r| Propagator::from_tle(&r.name, &r.line1, &r.line2))
+        .collect();
+    let Some(latest) = records.last() else {
+        return Err(format!("No element history for {}", satellite_id));
+    };
+
+    let points: Vec<TleHistoryPoint> = propagators
+        .iter()
+        .map(|p| {
+            let (perigee_km, apogee_km) = p.shell_km();
+            TleHistoryPoint {
+                epoch: p.epoch_unix() as i64,
+                semi_major_axis_km: orbit::semi_major_axis_km(p.elements.mean_motion),
+                perigee_km,
+                apogee_km,
+                mean_altitude_km: (perigee_km + apogee_km) / 2.0,
+            }
+        })
+        .collect();
+
+    let samples: Vec<ElementSample> = points
+        .iter()
+        .map(|p| ElementSample {
+            epoch_unix: p.epoch as f64,
+            perigee_km: p.perigee_km,
+            apogee_km: p.apogee_km,
+        })
+        .collect();
+    let maneuvers = decay::detect_maneuvers(&samples)
+        .into_iter()
+        .map(|(epoch, delta_sma_km)| Maneuver {
+            epoch: epoch as i64,
+            delta_sma_km,
+        })
+        .collect();
+
+    Ok(TleHistory {
+        satellite_id: format!("sat-{}", norad_id),
+        name: latest.name.clone(),
+        points,
+        maneuvers,
+    })
+}
+
 #[tauri::command]
 pub async fn get_satellite_positions(db: State<'_, Database>) -> Result<SatelliteData, String> {
     get_satellite_positions_inner(&db).await
 }
 
 #[tauri::command]
-pub async fn get_pass_predictions(db: State<'_, Database>) -> Result<Vec<PassPrediction>, String> {
-    get_pass_predictions_inner(&db).await
+pub async fn get_pass_predictions(
+    db: State<'_, Database>,
+    profile_id: Option<i64>,
+) -> Result<Vec<PassPrediction>, String> {
+    let (lat, lon) = resolve_location(&db, None, None, profile_id)?;
+    get_pass_predictions_at(&db, lat, lon).await
+}
+
+/// Render upcoming passes as an iCalendar file, optionally writing it to `path`.
+#[tauri::command]
+pub async fn export_pass_calendar(
+    db: State<'_, Database>,
+    path: Option<String>,
+) -> Result<String, String> {
+    let passes = get_pass_predictions_inner(&db).await?;
+    let calendar = ics::passes_to_ics(&passes, chrono::Utc::now().timestamp());
+
+    if let Some(path) = path.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        tokio::fs::write(path, &calendar)
+            .await
+            .map_err(|e| format!("Failed to write calendar: {}", e))?;
+    }
+    Ok(calendar)
+}
+
+#[tauri::command]
+pub async fn get_conjunctions(db: State<'_, Database>) -> Result<Vec<Conjunction>, String> {
+    // Try cache first (6 hour window)
+    if let Some(cached) = db.get_cached_response("conjunctions", 21600) {
+        if let Ok(conjunctions) = serde_json::from_str::<Vec<Conjunction>>(&cached) {
+            return Ok(conjunctions);
+        }
+    }
+
+    let conjunctions = get_conjunctions_inner(&db).await?;
+    if let Ok(json) = serde_json::to_string(&conjunctions) {
+        db.set_cached_response("conjunctions", &json);
+    }
+    Ok(conjunctions)
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn get_satellite_coverage(
+    db: State<'_, Database>,
+    satellite_id: String,
+    lat: Option<f64>,
+    lon: Option<f64>,
+    start_time: Option<i64>,
+    duration_mins: Option<i64>,
+    min_elevation: Option<f64>,
+) -> Result<SatelliteCoverage, String> {
+    let observer = active_observer(&db);
+    let lat = lat.unwrap_or(observer.latitude);
+    let lon = lon.unwrap_or(observer.longitude);
+    let start_time = start_time.unwrap_or_else(|| chrono::Utc::now().timestamp());
+    let duration_mins = duration_mins.unwrap_or(92);
+    let min_elevation = min_elevation.unwrap_or(orbit::MIN_ELEVATION_DEG);
+
+    if !lat.is_finite() || !lon.is_finite() || lat.abs() > 90.0 || lon.abs() > 180.0 {
+        return Err("Invalid coordinates".to_string());
+    }
+    if !(1..=1440).contains(&duration_mins) {
+        return Err("Duration must be between 1 and 1440 minutes".to_string());
+    }
+    if !min_elevation.is_finite() || !(0.0..90.0).contains(&min_elevation) {
+        return Err("Minimum elevation must be between 0 and 90 degrees".to_string());
+    }
+
+    get_satellite_coverage_inner(
+        &db,
+        &satellite_id,
+        start_time,
+        duration_mins,
+        min_elevation,
+        lat,
+        lon,
+    )
+    .await
+}
+
+#[tauri::command]
+pub fn get_tle_history(
+    db: State<'_, Database>,
+    satellite_id: String,
+    days: Option<f64>,
+) -> Result<TleHistory, String> {
+    let days = days.unwrap_or(30.0);
+    if !days.is_finite() || days <= 0.0 || days > 365.0 {
+        return Err("History window must be between 0 and 365 days".to_string());
+    }
+    get_tle_history_inner(&db, &satellite_id, days)
 }
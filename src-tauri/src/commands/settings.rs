@@ -1,3 +1,5 @@
+use crate::calculations::watch_rules;
+use crate::commands::{eonet, weather_alert};
 use crate::db::Database;
 use serde::{Deserialize, Serialize};
 use tauri::State;
@@ -13,6 +15,40 @@ pub struct SaveSettingsPayload {
     pub notify_volcanoes: bool,
     pub sonification_enabled: bool,
     pub ollama_model: String,
+    #[serde(default)]
+    pub notify_conjunctions: Option<bool>,
+    #[serde(default)]
+    pub conjunction_days: Option<f64>,
+    #[serde(default)]
+    pub conjunction_threshold_km: Option<f64>,
+    #[serde(default)]
+    pub decay_source: Option<String>,
+    #[serde(default)]
+    pub tle_max_age_hours: Option<f64>,
+    #[serde(default)]
+    pub iss_crosscheck: Option<bool>,
+    #[serde(default)]
+    pub calendar_server_addr: Option<String>,
+    #[serde(default)]
+    pub geocoder_url: Option<String>,
+    #[serde(default)]
+    pub quiet_hours_start: Option<String>,
+    #[serde(default)]
+    pub quiet_hours_end: Option<String>,
+    #[serde(default)]
+    pub rate_limit_per_hour: Option<f64>,
+    #[serde(default)]
+    pub digest_minutes: Option<f64>,
+    #[serde(default)]
+    pub eonet_categories: Option<Vec<String>>,
+    #[serde(default)]
+    pub vaac_url: Option<String>,
+    #[serde(default)]
+    pub neo_lookahead_days: Option<f64>,
+    #[serde(default)]
+    pub neo_max_distance_lunar: Option<f64>,
+    #[serde(default)]
+    pub weather_alert_feeds: Option<Vec<String>>,
 }
 
 #[derive(Serialize)]
@@ -26,14 +62,41 @@ pub struct SettingsResponse {
     pub notify_volcanoes: Option<bool>,
     pub sonification_enabled: Option<bool>,
     pub ollama_model: Option<String>,
+    pub notify_conjunctions: Option<bool>,
+    pub conjunction_days: Option<f64>,
+    pub conjunction_threshold_km: Option<f64>,
+    pub decay_source: Option<String>,
+    pub tle_max_age_hours: Option<f64>,
+    pub iss_crosscheck: Option<bool>,
+    pub calendar_server_addr: Option<String>,
+    pub geocoder_url: Option<String>,
+    pub quiet_hours_start: Option<String>,
+    pub quiet_hours_end: Option<String>,
+    pub rate_limit_per_hour: Option<f64>,
+    pub digest_minutes: Option<f64>,
+    pub eonet_categories: Option<Vec<String>>, // None: the default categories
+    pub vaac_url: Option<String>,
+    pub neo_lookahead_days: Option<f64>,
+    pub neo_max_distance_lunar: Option<f64>,
+    pub weather_alert_feeds: Option<Vec<String>>, // None: the NWS feed; []: disabled
 }
 
 #[tauri::command]
 pub fn get_settings(db: State<'_, Database>) -> Result<SettingsResponse, String> {
     let settings = db.get_settings();
+    // The location fields mirror the active observer profile
+    let observer = db.get_active_observer_profile();
+    let eonet_categories = settings
+        .eonet_categories
+        .as_ref()
+        .map(|_| eonet::selected_categories(&settings));
+    let weather_alert_feeds = settings
+        .weather_alert_feeds
+        .as_ref()
+        .map(|_| weather_alert::alert_feeds(&settings));
     Ok(SettingsResponse {
-        user_lat: settings.user_lat,
-        user_lon: settings.user_lon,
+        user_lat: observer.as_ref().map(|o| o.latitude).or(settings.user_lat),
+        user_lon: observer.as_ref().map(|o| o.longitude).or(settings.user_lon),
         mag_threshold: settings.mag_threshold,
         proximity_km: settings.proximity_km,
         notify_earthquakes: settings.notify_earthquakes,
@@ -41,6 +104,23 @@ pub fn get_settings(db: State<'_, Database>) -> Result<SettingsResponse, String>
         notify_volcanoes: settings.notify_volcanoes,
         sonification_enabled: settings.sonification_enabled,
         ollama_model: settings.ollama_model,
+        notify_conjunctions: settings.notify_conjunctions,
+        conjunction_days: settings.conjunction_days,
+        conjunction_threshold_km: settings.conjunction_threshold_km,
+        decay_source: settings.decay_source,
+        tle_max_age_hours: settings.tle_max_age_hours,
+        iss_crosscheck: settings.iss_crosscheck,
+        calendar_server_addr: settings.calendar_server_addr,
+        geocoder_url: settings.geocoder_url,
+        quiet_hours_start: settings.quiet_hours_start,
+        quiet_hours_end: settings.quiet_hours_end,
+        rate_limit_per_hour: settings.rate_limit_per_hour,
+        digest_minutes: settings.digest_minutes,
+        eonet_categories,
+        vaac_url: settings.vaac_url,
+        neo_lookahead_days: settings.neo_lookahead_days,
+        neo_max_distance_lunar: settings.neo_max_distance_lunar,
+        weather_alert_feeds,
     })
 }
 
@@ -59,6 +139,60 @@ pub fn save_settings(settings: SaveSettingsPayload, db: State<'_, Database>) ->
         settings.sonification_enabled,
         settings.ollama_model.trim(),
     );
+    db.set_active_observer_position(settings.user_lat, settings.user_lon);
+
+    if let Some(notify) = settings.notify_conjunctions {
+        db.set_setting("notify_conjunctions", if notify { "true" } else { "false" });
+    }
+    if let Some(days) = settings.conjunction_days {
+        db.set_setting("conjunction_days", &days.to_string());
+    }
+    if let Some(threshold) = settings.conjunction_threshold_km {
+        db.set_setting("conjunction_threshold_km", &threshold.to_string());
+    }
+    if let Some(source) = &settings.decay_source {
+        db.set_setting("decay_source", source.trim());
+    }
+    if let Some(hours) = settings.tle_max_age_hours {
+        db.set_setting("tle_max_age_hours", &hours.to_string());
+    }
+    if let Some(crosscheck) = settings.iss_crosscheck {
+        db.set_setting("iss_crosscheck", if crosscheck { "true" } else { "false" });
+    }
+    if let Some(addr) = &settings.calendar_server_addr {
+        db.set_setting("calendar_server_addr", addr.trim());
+    }
+    if let Some(url) = &settings.geocoder_url {
+        db.set_setting("geocoder_url", url.trim());
+    }
+    if let Some(start) = &settings.quiet_hours_start {
+        db.set_setting("quiet_hours_start", start.trim());
+    }
+    if let Some(end) = &settings.quiet_hours_end {
+        db.set_setting("quiet_hours_end", end.trim());
+    }
+    if let Some(limit) = settings.rate_limit_per_hour {
+        db.set_setting("rate_limit_per_hour", &limit.to_string());
+    }
+    if let Some(minutes) = settings.digest_minutes {
+        db.set_setting("digest_minutes", &minutes.to_string());
+    }
+    if let Some(categories) = &settings.eonet_categories {
+        db.set_setting("eonet_categories", &categories.join(","));
+    }
+    if let Some(url) = &settings.vaac_url {
+        db.set_setting("vaac_url", url.trim());
+    }
+    if let Some(days) = settings.neo_lookahead_days {
+        db.set_setting("neo_lookahead_days", &days.to_string());
+    }
+    if let Some(distance) = settings.neo_max_distance_lunar {
+        db.set_setting("neo_max_distance_lunar", &distance.to_string());
+    }
+    if let Some(feeds) = &settings.weather_alert_feeds {
+        let feeds: Vec<&str> = feeds.iter().map(|url| url.trim()).collect();
+        db.set_setting("weather_alert_feeds", &feeds.join("\n"));
+    }
     Ok(())
 }
 
@@ -78,6 +212,90 @@ fn validate_settings(settings: &SaveSettingsPayload) -> Result<(), String> {
     if settings.ollama_model.trim().is_empty() {
         return Err("Ollama model is required".to_string());
     }
+    if let Some(days) = settings.conjunction_days {
+        if !days.is_finite() || days <= 0.0 || days > 14.0 {
+            return Err("Conjunction screening window must be between 0 and 14 days".to_string());
+        }
+    }
+    if let Some(threshold) = settings.conjunction_threshold_km {
+        if !threshold.is_finite() || threshold <= 0.0 {
+            return Err("Conjunction threshold must be positive".to_string());
+        }
+    }
+    if let Some(source) = settings.decay_source.as_deref().map(str::trim) {
+        let is_url = source.starts_with("http://") || source.starts_with("https://");
+        if !source.is_empty() && !is_url && !std::path::Path::new(source).is_file() {
+            return Err("Decay source must be an http(s) URL or an existing file".to_string());
+        }
+    }
+    if let Some(hours) = settings.tle_max_age_hours {
+        if !hours.is_finite() || hours <= 0.0 {
+            return Err("Maximum TLE age must be positive".to_string());
+        }
+    }
+    if let Some(addr) = settings.calendar_server_addr.as_deref().map(str::trim) {
+        if !addr.is_empty() && addr.parse::<std::net::SocketAddr>().is_err() {
+            return Err("Calendar server address must look like 127.0.0.1:8765".to_string());
+        }
+    }
+    if let Some(url) = settings.geocoder_url.as_deref().map(str::trim) {
+        if !url.is_empty() && !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err("Geocoder URL must start with http:// or https://".to_string());
+        }
+    }
+    let quiet_start = settings.quiet_hours_start.as_deref().map(str::trim);
+    let quiet_end = settings.quiet_hours_end.as_deref().map(str::trim);
+    for time in [quiet_start, quiet_end].into_iter().flatten() {
+        if !time.is_empty() && watch_rules::parse_hhmm(time).is_none() {
+            return Err("Quiet hours must be given as HH:MM".to_string());
+        }
+    }
+    if quiet_start.is_some_and(str::is_empty) != quiet_end.is_some_and(str::is_empty) {
+        return Err("Quiet hours need both a start and an end".to_string());
+    }
+    if let Some(limit) = settings.rate_limit_per_hour {
+        if !limit.is_finite() || limit < 0.0 {
+            return Err("Rate limit must be zero (off) or positive".to_string());
+        }
+    }
+    if let Some(minutes) = settings.digest_minutes {
+        if !minutes.is_finite() || !(0.0..=1440.0).contains(&minutes) {
+            return Err("Digest window must be between 0 (off) and 1440 minutes".to_string());
+        }
+    }
+    if let Some(categories) = &settings.eonet_categories {
+        if categories
+            .iter()
+            .any(|c| c.is_empty() || !c.chars().all(|ch| ch.is_ascii_alphanumeric()))
+        {
+            return Err("EONET categories must be category ids such as seaLakeIce".to_string());
+        }
+    }
+    if let Some(url) = settings.vaac_url.as_deref().map(str::trim) {
+        if !url.is_empty() && !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err("VAAC URL must start with http:// or https://".to_string());
+        }
+    }
+    if let Some(days) = settings.neo_lookahead_days {
+        if !days.is_finite() || !(1.0..=365.0).contains(&days) {
+            return Err("Asteroid lookahead must be between 1 and 365 days".to_string());
+        }
+    }
+    if let Some(distance) = settings.neo_max_distance_lunar {
+        if !distance.is_finite() || distance <= 0.0 || distance > 400.0 {
+            return Err(
+                "Asteroid distance limit must be between 0 and 400 lunar distances".to_string(),
+            );
+        }
+    }
+    if let Some(feeds) = &settings.weather_alert_feeds {
+        if feeds.iter().map(|url| url.trim()).any(|url| {
+            let is_http = url.starts_with("http://") || url.starts_with("https://");
+            !is_http || url.contains(char::is_whitespace)
+        }) {
+            return Err("Weather alert feeds must be http:// or https:// URLs".to_string());
+        }
+    }
 
     Ok(())
 }
@@ -97,6 +315,23 @@ mod tests {
             notify_volcanoes: true,
             sonification_enabled: false,
             ollama_model: "llama3.2".to_string(),
+            notify_conjunctions: None,
+            conjunction_days: None,
+            conjunction_threshold_km: None,
+            decay_source: None,
+            tle_max_age_hours: None,
+            iss_crosscheck: None,
+            calendar_server_addr: None,
+            geocoder_url: None,
+            quiet_hours_start: None,
+            quiet_hours_end: None,
+            rate_limit_per_hour: None,
+            digest_minutes: None,
+            eonet_categories: None,
+            vaac_url: None,
+            neo_lookahead_days: None,
+            neo_max_distance_lunar: None,
+            weather_alert_feeds: None,
         }
     }
 
@@ -123,4 +358,35 @@ mod tests {
         payload.user_lon = -220.0;
         assert!(validate_settings(&payload).is_err());
     }
+
+    #[test]
+    fn validate_settings_rejects_invalid_conjunction_window() {
+        let mut payload = valid_payload();
+        payload.conjunction_days = Some(30.0);
+        assert!(validate_settings(&payload).is_err());
+
+        payload.conjunction_days = Some(3.0);
+        payload.conjunction_threshold_km = Some(-1.0);
+        assert!(validate_settings(&payload).is_err());
+    }
+
+    #[test]
+    fn validate_settings_checks_notification_pacing() {
+        let mut payload = valid_payload();
+        payload.quiet_hours_start = Some("22:00".to_string());
+        payload.quiet_hours_end = Some("07:00".to_string());
+        payload.rate_limit_per_hour = Some(10.0);
+        payload.digest_minutes = Some(60.0);
+        assert!(validate_settings(&payload).is_ok());
+
+        payload.quiet_hours_end = Some("7pm".to_string());
+        assert!(validate_settings(&payload).is_err());
+
+        payload.quiet_hours_end = Some(String::new());
+        assert!(validate_settings(&payload).is_err());
+
+        payload.quiet_hours_start = Some(String::new());
+        payload.digest_minutes = Some(-5.0);
+        assert!(validate_settings(&payload).is_err());
+    }
 }
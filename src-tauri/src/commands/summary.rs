@@ -1,3 +1,4 @@
+use crate::commands::asteroid;
 use crate::db::Database;
 use crate::fetchers::http::HTTP_CLIENT;
 use tauri::State;
@@ -34,7 +35,7 @@ pub async fn generate_summary(
         .unwrap_or(0);
 
     let asteroid_count = db
-        .get_cached_response("nasa:neo", 86400)
+        .get_cached_response(&asteroid::cache_key(&db.get_settings()), 86400)
         .and_then(|json| {
             serde_json::from_str::<Vec<serde_json::Value>>(&json)
                 .ok()
@@ -0,0 +1,31 @@
+use crate::db::Database;
+use crate::fetchers::tsunami::{fetch_tsunami_bulletins, link_earthquake};
+use crate::models::tsunami::TsunamiBulletin;
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_tsunami_bulletins(
+    db: State<'_, Database>,
+) -> Result<Vec<TsunamiBulletin>, String> {
+    // Try cache first (5 min window)
+    if let Some(cached) = db.get_cached_response("tsunami:bulletins", 300) {
+        if let Ok(bulletins) = serde_json::from_str::<Vec<TsunamiBulletin>>(&cached) {
+            return Ok(bulletins);
+        }
+    }
+
+    let mut bulletins = fetch_tsunami_bulletins().await?;
+    link_earthquakes(&db, &mut bulletins);
+    if let Ok(json) = serde_json::to_string(&bulletins) {
+        db.set_cached_response("tsunami:bulletins", &json);
+    }
+    Ok(bulletins)
+}
+
+/// Attach the originating USGS quake from the cache to each bulletin.
+pub fn link_earthquakes(db: &Database, bulletins: &mut [TsunamiBulletin]) {
+    let quakes = db.get_cached_earthquakes().unwrap_or_default();
+    for bulletin in bulletins {
+        bulletin.earthquake_id = link_earthquake(bulletin, &quakes);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::db::Database;
+use crate::fetchers::vaac::{self, fetch_ash_advisories};
+use crate::models::vaac::AshAdvisory;
+use crate::models::volcano::Volcano;
+use tauri::State;
+
+/// Current ash advisories from the VAAC URL in settings, linked to known
+/// volcanoes. Empty when no URL is configured.
+#[tauri::command]
+pub async fn get_ash_advisories(db: State<'_, Database>) -> Result<Vec<AshAdvisory>, String> {
+    load_ash_advisories(&db).await
+}
+
+/// Observed and forecast ash clouds as GeoJSON layers.
+#[tauri::command]
+pub async fn get_ash_layers(db: State<'_, Database>) -> Result<serde_json::Value, String> {
+    let advisories = load_ash_advisories(&db).await?;
+    Ok(vaac::to_geojson(&advisories))
+}
+
+async fn load_ash_advisories(db: &Database) -> Result<Vec<AshAdvisory>, String> {
+    let Some(url) = db.get_settings().vaac_url else {
+        return Ok(Vec::new());
+    };
+
+    // Try cache first (15 min window)
+    if let Some(cached) = db.get_cached_response(&cache_key(&url), 900) {
+        if let Ok(advisories) = serde_json::from_str::<Vec<AshAdvisory>>(&cached) {
+            return Ok(advisories);
+        }
+    }
+
+    let mut advisories = fetch_ash_advisories(&url).await?;
+    link_volcanoes(db, &mut advisories);
+    if let Ok(json) = serde_json::to_string(&advisories) {
+        db.set_cached_response(&cache_key(&url), &json);
+    }
+    Ok(advisories)
+}
+
+/// Advisories are cached per feed so changing the URL in settings takes
+/// effect at once.
+pub fn cache_key(url: &str) -> String {
+    format!("vaac:advisories:{}", url)
+}
+
+pub fn link_volcanoes(db: &Database, advisories: &mut [AshAdvisory]) {
+    let volcanoes: Vec<Volcano> = db
+        .get_cached_response("volcano:active", 86400)
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    for advisory in advisories.iter_mut() {
+        advisory.volcano_id = vaac::link_volcano(advisory, &volcanoes);
+    }
+}
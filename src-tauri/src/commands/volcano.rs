@@ -1,6 +1,28 @@
-use crate::fetchers::volcano::get_active_volcanoes;
+use crate::db::Database;
+use crate::fetchers::volcano::fetch_active_volcanoes;
+use crate::models::volcano::{Volcano, VolcanoStatusChange};
+use tauri::State;
 
 #[tauri::command]
-pub fn get_volcanoes() -> Vec<crate::models::volcano::Volcano> {
-    get_active_volcanoes()
+pub async fn get_volcanoes(db: State<'_, Database>) -> Result<Vec<Volcano>, String> {
+    // Try cache first (1 hour window)
+    if let Some(cached) = db.get_cached_response("volcano:active", 3600) {
+        if let Ok(volcanoes) = serde_json::from_str::<Vec<Volcano>>(&cached) {
+            if !volcanoes.is_empty() {
+                return Ok(volcanoes);
+            }
+        }
+    }
+
+    let volcanoes = fetch_active_volcanoes().await?;
+    if let Ok(json) = serde_json::to_string(&volcanoes) {
+        db.set_cached_response("volcano:active", &json);
+    }
+    Ok(volcanoes)
+}
+
+/// Recorded alert level and colour code changes of a volcano, oldest first.
+#[tauri::command]
+pub fn get_volcano_history(db: State<'_, Database>, id: String) -> Vec<VolcanoStatusChange> {
+    db.get_volcano_status_history(&id)
 }
@@ -1,5 +1,6 @@
+use crate::calculations::{geometry, watch_rules};
 use crate::db::Database;
-use crate::models::watchlist::Watchlist;
+use crate::models::watchlist::{WatchRules, WatchShape, Watchlist};
 use tauri::State;
 
 #[tauri::command]
@@ -27,10 +28,129 @@ pub fn add_watchlist(
     if name.trim().is_empty() {
         return Err("Name cannot be empty".to_string());
     }
-    db.add_watchlist(&name, lat, lon, radius_km)
+    let shape = WatchShape::Circle {
+        latitude: lat,
+        longitude: lon,
+        radius_km,
+    };
+    db.add_watchlist(&name, lat, lon, radius_km, &shape, &WatchRules::default())
         .map_err(|e| format!("Failed to add watchlist: {}", e))
 }
 
+#[tauri::command]
+pub fn add_watchlist_shape(
+    db: State<'_, Database>,
+    name: String,
+    shape: WatchShape,
+) -> Result<Watchlist, String> {
+    if name.trim().is_empty() {
+        return Err("Name cannot be empty".to_string());
+    }
+    geometry::validate_shape(&shape)?;
+    insert_shape(&db, name.trim(), &shape)
+}
+
+/// Create one watchlist per feature of a GeoJSON file. Line features become
+/// corridors of `buffer_km`, point features circles of `radius_km`.
+#[tauri::command]
+pub async fn import_watchlists_geojson(
+    db: State<'_, Database>,
+    path: String,
+    buffer_km: Option<f64>,
+    radius_km: Option<f64>,
+) -> Result<Vec<Watchlist>, String> {
+    let text = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    let value: serde_json::Value =
+        serde_json::from_str(&text).map_err(|e| format!("Invalid GeoJSON: {}", e))?;
+    let shapes = geometry::shapes_from_geojson(
+        &value,
+        buffer_km.unwrap_or(50.0),
+        radius_km.unwrap_or(100.0),
+    )?;
+
+    let stem = std::path::Path::new(&path)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("Imported")
+        .to_string();
+    let count = shapes.len();
+
+    // Every shape was validated while reading the file; the inserts share one
+    // transaction so a failure leaves no partial import behind
+    let drafts = shapes
+        .into_iter()
+        .enumerate()
+        .map(|(i, (name, shape))| {
+            let name = name.unwrap_or_else(|| {
+                if count == 1 {
+                    stem.clone()
+                } else {
+                    format!("{} {}", stem, i + 1)
+                }
+            });
+            let (latitude, longitude, radius_km) = geometry::center_and_radius(&shape);
+            Watchlist {
+                id: 0,
+                name,
+                latitude,
+                longitude,
+                radius_km,
+                shape,
+                rules: WatchRules::default(),
+                created_at: 0,
+            }
+        })
+        .collect();
+    db.add_watchlists(drafts)
+        .map_err(|e| format!("Failed to import watchlists: {}", e))
+}
+
+fn insert_shape(db: &Database, name: &str, shape: &WatchShape) -> Result<Watchlist, String> {
+    let (lat, lon, radius_km) = geometry::center_and_radius(shape);
+    db.add_watchlist(name, lat, lon, radius_km, shape, &WatchRules::default())
+        .map_err(|e| format!("Failed to add watchlist: {}", e))
+}
+
+/// Rename a watchlist, replace its shape or change its alert rules. Fields
+/// left as `None` keep their current value.
+#[tauri::command]
+pub fn update_watchlist(
+    db: State<'_, Database>,
+    id: i64,
+    name: Option<String>,
+    shape: Option<WatchShape>,
+    rules: Option<WatchRules>,
+) -> Result<Watchlist, String> {
+    let mut watchlist = db
+        .get_watchlist(id)
+        .ok_or_else(|| format!("Watchlist {} not found", id))?;
+
+    if let Some(name) = name {
+        if name.trim().is_empty() {
+            return Err("Name cannot be empty".to_string());
+        }
+        watchlist.name = name.trim().to_string();
+    }
+    if let Some(shape) = shape {
+        geometry::validate_shape(&shape)?;
+        let (lat, lon, radius_km) = geometry::center_and_radius(&shape);
+        watchlist.latitude = lat;
+        watchlist.longitude = lon;
+        watchlist.radius_km = radius_km;
+        watchlist.shape = shape;
+    }
+    if let Some(rules) = rules {
+        watch_rules::validate_rules(&rules)?;
+        watchlist.rules = rules;
+    }
+
+    db.update_watchlist(&watchlist)
+        .map_err(|e| format!("Failed to update watchlist: {}", e))?;
+    Ok(watchlist)
+}
+
 #[tauri::command]
 pub fn remove_watchlist(db: State<'_, Database>, id: i64) -> Result<(), String> {
     db.remove_watchlist(id)
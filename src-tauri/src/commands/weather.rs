@@ -1,10 +1,90 @@
-use crate::fetchers::weather::fetch_weather;
-use crate::models::weather::Weather;
+use crate::commands::observer::{active_observer, resolve_location};
+use crate::db::Database;
+use crate::fetchers::weather::{conditions_at, fetch_forecast, fetch_weather};
+use crate::models::weather::{Forecast, LocalConditions, Weather};
+use tauri::State;
 
 #[tauri::command]
-pub async fn get_weather(lat: f64, lon: f64) -> Result<Weather, String> {
+pub async fn get_weather(
+    db: State<'_, Database>,
+    lat: Option<f64>,
+    lon: Option<f64>,
+    profile_id: Option<i64>,
+) -> Result<Weather, String> {
+    let (lat, lon) = resolve_location(&db, lat, lon, profile_id)?;
     if !lat.is_finite() || !lon.is_finite() {
         return Err("Invalid coordinates".into());
     }
     fetch_weather(lat, lon).await
 }
+
+/// Hourly and daily forecast for a point, an observer profile or the active
+/// observer.
+#[tauri::command]
+pub async fn get_forecast(
+    db: State<'_, Database>,
+    lat: Option<f64>,
+    lon: Option<f64>,
+    profile_id: Option<i64>,
+) -> Result<Forecast, String> {
+    let (lat, lon) = resolve_location(&db, lat, lon, profile_id)?;
+    get_forecast_at(&db, lat, lon).await
+}
+
+/// Sky conditions at the active observer at `time` (unix seconds, default
+/// now).
+#[tauri::command]
+pub async fn get_local_conditions(
+    db: State<'_, Database>,
+    time: Option<i64>,
+) -> Result<Option<LocalConditions>, String> {
+    let time = time.unwrap_or_else(|| chrono::Utc::now().timestamp());
+    local_conditions(&db, time).await
+}
+
+async fn local_conditions(db: &Database, time: i64) -> Result<Option<LocalConditions>, String> {
+    let observer = active_observer(db);
+    let forecast = get_forecast_at(db, observer.latitude, observer.longitude).await?;
+    Ok(conditions_at(&forecast, time))
+}
+
+/// Current conditions at the active observer for notifications, where a
+/// missing forecast only means a shorter message.
+pub async fn get_local_conditions_now(db: &Database) -> Option<LocalConditions> {
+    local_conditions(db, chrono::Utc::now().timestamp())
+        .await
+        .unwrap_or_else(|e| {
+            log::warn!("No local conditions: {}", e);
+            None
+        })
+}
+
+/// Forecasts are cached per 0.1° cell (about 11 km), well within the model
+/// resolution, so nearby locations share one request.
+pub async fn get_forecast_at(db: &Database, lat: f64, lon: f64) -> Result<Forecast, String> {
+    if !lat.is_finite() || !lon.is_finite() {
+        return Err("Invalid coordinates".into());
+    }
+    let (lat, lon) = (round_coordinate(lat), round_coordinate(lon));
+    let key = format!("weather:forecast:{:.1}:{:.1}", lat, lon);
+
+    // Try cache first (1 hour window, matching the model update cycle)
+    if let Some(cached) = db.get_cached_response(&key, 3600) {
+        if let Ok(forecast) = serde_json::from_str::<Forecast>(&cached) {
+            if !forecast.hourly.is_empty() {
+                return Ok(forecast);
+            }
+        }
+    }
+
+    let forecast = fetch_forecast(lat, lon).await?;
+    if let Ok(json) = serde_json::to_string(&forecast) {
+        db.set_cached_response(&key, &json);
+    }
+    Ok(forecast)
+}
+
+fn round_coordinate(value: f64) -> f64 {
+    // Adding zero turns -0.0 into 0.0 so both share a cache key
+    (value * 10.0).round() / 10.0 + 0.0
+}
@@ -0,0 +1,91 @@
+use crate::calculations::geometry;
+use crate::commands::observer::resolve_location;
+use crate::db::{Database, UserSettings};
+use crate::fetchers::weather_alert::{fetch_weather_alerts, DEFAULT_FEED_URL};
+use crate::models::weather_alert::WeatherAlert;
+use tauri::State;
+
+/// Set after each refresh; stored alerts are served while it is fresh.
+const REFRESHED_KEY: &str = "weather_alerts:refreshed";
+
+/// Active CAP alerts from the configured feeds, newest first.
+#[tauri::command]
+pub async fn get_weather_alerts(db: State<'_, Database>) -> Result<Vec<WeatherAlert>, String> {
+    // Stored alerts are kept current by the background refresh (5 min)
+    if db.get_cached_response(REFRESHED_KEY, 300).is_some() {
+        return Ok(stored_alerts(&db));
+    }
+    refresh_weather_alerts(&db).await
+}
+
+/// Active alerts whose area covers a point, an observer profile or the
+/// active observer.
+#[tauri::command]
+pub async fn get_weather_alerts_at(
+    db: State<'_, Database>,
+    lat: Option<f64>,
+    lon: Option<f64>,
+    profile_id: Option<i64>,
+) -> Result<Vec<WeatherAlert>, String> {
+    let (lat, lon) = resolve_location(&db, lat, lon, profile_id)?;
+    let alerts = get_weather_alerts(db).await?;
+    Ok(alerts
+        .into_iter()
+        .filter(|a| {
+            a.areas
+                .iter()
+                .flat_map(|area| &area.shapes)
+                .any(|s| geometry::contains(s, lat, lon))
+        })
+        .collect())
+}
+
+/// Fetch every configured feed and store its alerts. A feed that fails
+/// keeps its stored alerts until they expire.
+pub async fn refresh_weather_alerts(db: &Database) -> Result<Vec<WeatherAlert>, String> {
+    let feeds = alert_feeds(&db.get_settings());
+    let mut errors = Vec::new();
+
+    for url in &feeds {
+        match fetch_weather_alerts(url).await {
+            Ok(alerts) => {
+                if let Err(e) = db.replace_weather_alerts(url, &alerts) {
+                    log::error!("Failed to store weather alerts from {}: {}", url, e);
+                }
+            }
+            Err(e) => {
+                log::warn!("Weather alert feed {}: {}", url, e);
+                errors.push(e);
+            }
+        }
+    }
+    if !feeds.is_empty() && errors.len() == feeds.len() {
+        return Err(errors.join("; "));
+    }
+
+    db.set_cached_response(REFRESHED_KEY, &chrono::Utc::now().timestamp().to_string());
+    Ok(stored_alerts(db))
+}
+
+/// Stored alerts from the feeds still configured.
+fn stored_alerts(db: &Database) -> Vec<WeatherAlert> {
+    let feeds = alert_feeds(&db.get_settings());
+    db.get_weather_alerts()
+        .into_iter()
+        .filter(|a| feeds.contains(&a.feed_url))
+        .collect()
+}
+
+/// Feeds from settings: the NWS feed when none were ever saved, and none at
+/// all when an empty list was saved.
+pub fn alert_feeds(settings: &UserSettings) -> Vec<String> {
+    match &settings.weather_alert_feeds {
+        Some(list) => list
+            .lines()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .map(String::from)
+            .collect(),
+        None => vec![DEFAULT_FEED_URL.to_string()],
+    }
+}
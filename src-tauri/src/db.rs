@@ -1,9 +1,19 @@
+use crate::models::alert_rule::AlertRule;
+use crate::models::asteroid::{Asteroid, AsteroidApproach, OrbitClass};
+use crate::models::channel::{AlertChannel, ChannelConfig};
 use crate::models::earthquake::Earthquake;
+use crate::models::gdacs::{GdacsAlert, GdacsEpisode};
 use crate::models::iss::IssPosition;
-use crate::models::watchlist::Watchlist;
+use crate::models::notification::{NotificationRecord, NotificationRegion, NotificationSnooze};
+use crate::models::observer::ObserverProfile;
+use crate::models::satellite::TleRecord;
+use crate::models::volcano::{Volcano, VolcanoStatusChange};
+use crate::models::watchlist::{WatchRules, WatchShape, Watchlist};
+use crate::models::weather_alert::WeatherAlert;
 use rusqlite::Connection;
 use std::sync::Mutex;
 
+#[derive(Default)]
 pub struct UserSettings {
     pub user_lat: Option<f64>,
     pub user_lon: Option<f64>,
@@ -14,6 +24,23 @@ pub struct UserSettings {
     pub notify_volcanoes: Option<bool>,
     pub sonification_enabled: Option<bool>,
     pub ollama_model: Option<String>,
+    pub notify_conjunctions: Option<bool>,
+    pub conjunction_days: Option<f64>,
+    pub conjunction_threshold_km: Option<f64>,
+    pub decay_source: Option<String>,
+    pub tle_max_age_hours: Option<f64>,
+    pub iss_crosscheck: Option<bool>,
+    pub calendar_server_addr: Option<String>,
+    pub geocoder_url: Option<String>,
+    pub quiet_hours_start: Option<String>,
+    pub quiet_hours_end: Option<String>,
+    pub rate_limit_per_hour: Option<f64>,
+    pub digest_minutes: Option<f64>,
+    pub eonet_categories: Option<String>, // comma-separated category ids; empty selects none
+    pub vaac_url: Option<String>,
+    pub neo_lookahead_days: Option<f64>,
+    pub neo_max_distance_lunar: Option<f64>,
+    pub weather_alert_feeds: Option<String>, // newline-separated CAP feed URLs; empty disables
 }
 
 pub struct Database {
@@ -68,10 +95,161 @@ impl Database {
                 radius_km REAL NOT NULL,
                 created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
             );
+
+            CREATE TABLE IF NOT EXISTS tle_history (
+                norad_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                epoch INTEGER NOT NULL,
+                line1 TEXT NOT NULL,
+                line2 TEXT NOT NULL,
+                fetched_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+                PRIMARY KEY (norad_id, epoch)
+            );
+
+            CREATE TABLE IF NOT EXISTS observer_profiles (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                latitude REAL NOT NULL,
+                longitude REAL NOT NULL,
+                elevation_m REAL NOT NULL DEFAULT 0,
+                timezone TEXT NOT NULL DEFAULT 'UTC',
+                is_active INTEGER NOT NULL DEFAULT 0,
+                created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+            );
+
+            CREATE TABLE IF NOT EXISTS alert_rules (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                expression TEXT NOT NULL,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+            );
+
+            CREATE TABLE IF NOT EXISTS alert_channels (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                config TEXT NOT NULL,
+                template TEXT,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+            );
+
+            CREATE TABLE IF NOT EXISTS notification_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source TEXT NOT NULL,
+                event_id TEXT NOT NULL,
+                rule TEXT NOT NULL,
+                title TEXT NOT NULL,
+                body TEXT NOT NULL,
+                latitude REAL,
+                longitude REAL,
+                suppressed INTEGER NOT NULL DEFAULT 0,
+                acknowledged_at INTEGER,
+                created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_notification_history_created
+                ON notification_history(created_at);
+
+            -- Events already notified about, so restarts don't repeat them
+            CREATE TABLE IF NOT EXISTS notification_keys (
+                key TEXT PRIMARY KEY,
+                created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+            );
+
+            CREATE TABLE IF NOT EXISTS notification_snoozes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                source TEXT,
+                latitude REAL,
+                longitude REAL,
+                radius_km REAL,
+                until INTEGER NOT NULL,
+                created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+            );
+
+            -- One row per GDACS episode, to follow an event's alert level over time
+            CREATE TABLE IF NOT EXISTS gdacs_episodes (
+                alert_id TEXT NOT NULL,
+                episode_id TEXT NOT NULL,
+                alert_level TEXT NOT NULL,
+                severity_value REAL,
+                severity_unit TEXT,
+                population REAL,
+                from_date TEXT NOT NULL,
+                to_date TEXT NOT NULL,
+                recorded_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+                PRIMARY KEY (alert_id, episode_id)
+            );
+
+            -- JPL orbit class per small body (SPK-ID, or designation without one)
+            CREATE TABLE IF NOT EXISTS asteroid_orbits (
+                spk_id TEXT PRIMARY KEY,
+                code TEXT NOT NULL,
+                name TEXT NOT NULL,
+                fetched_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+            );
+
+            -- Every known asteroid approach, from NeoWs or the JPL close-approach API
+            CREATE TABLE IF NOT EXISTS asteroid_approaches (
+                designation TEXT NOT NULL,
+                name TEXT NOT NULL,
+                approach_time INTEGER NOT NULL,
+                approach_date TEXT NOT NULL,
+                velocity_kps REAL NOT NULL,
+                miss_distance_km REAL NOT NULL,
+                miss_distance_lunar REAL NOT NULL,
+                orbiting_body TEXT NOT NULL,
+                source TEXT NOT NULL,
+                fetched_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+                PRIMARY KEY (designation, approach_time)
+            );
+
+            -- One row per volcano alert level or colour code change
+            CREATE TABLE IF NOT EXISTS volcano_status_history (
+                volcano_id TEXT NOT NULL,
+                status TEXT NOT NULL,
+                aviation_color TEXT,
+                source TEXT NOT NULL,
+                recorded_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+            );
+            CREATE INDEX IF NOT EXISTS idx_volcano_status_history
+                ON volcano_status_history (volcano_id, recorded_at);
+
+            -- Active CAP weather alerts per feed, the full alert kept as JSON
+            CREATE TABLE IF NOT EXISTS weather_alerts (
+                id TEXT PRIMARY KEY,
+                feed_url TEXT NOT NULL,
+                expires_at INTEGER,
+                data TEXT NOT NULL,
+                fetched_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now'))
+            );
+
+            -- Seed a first profile from the legacy single-location settings
+            INSERT INTO observer_profiles (name, latitude, longitude, is_active)
+            SELECT 'Home',
+                   COALESCE((SELECT CAST(value AS REAL) FROM settings WHERE key = 'user_lat'), 37.3382),
+                   COALESCE((SELECT CAST(value AS REAL) FROM settings WHERE key = 'user_lon'), -121.8863),
+                   1
+            WHERE NOT EXISTS (SELECT 1 FROM observer_profiles);
             ",
         )
         .expect("Failed to create tables");
 
+        // Columns added after the first release; ALTER fails harmlessly once applied
+        conn.execute("ALTER TABLE watchlists ADD COLUMN geometry TEXT", []).ok();
+        conn.execute("ALTER TABLE watchlists ADD COLUMN rules TEXT", []).ok();
+        conn.execute("ALTER TABLE earthquakes ADD COLUMN alert TEXT", []).ok();
+        conn.execute(
+            "ALTER TABLE notification_history ADD COLUMN held INTEGER NOT NULL DEFAULT 0",
+            [],
+        )
+        .ok();
+        conn.execute(
+            "ALTER TABLE notification_history ADD COLUMN severity REAL",
+            [],
+        )
+        .ok();
+
         Database {
             conn: Mutex::new(conn),
         }
@@ -82,8 +260,8 @@ impl Database {
     pub fn store_earthquakes(&self, quakes: &[Earthquake]) {
         let conn = self.conn.lock().unwrap();
         let mut stmt = match conn.prepare(
-            "INSERT OR REPLACE INTO earthquakes (id, magnitude, latitude, longitude, depth, place, time, tsunami, title, fetched_at)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, strftime('%s', 'now'))",
+            "INSERT OR REPLACE INTO earthquakes (id, magnitude, latitude, longitude, depth, place, time, tsunami, title, alert, fetched_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, strftime('%s', 'now'))",
         ) {
             Ok(s) => s,
             Err(e) => {
@@ -103,6 +281,7 @@ impl Database {
                 q.time,
                 q.tsunami as i32,
                 q.title,
+                q.alert,
             ])
             .ok();
         }
@@ -113,7 +292,7 @@ impl Database {
 
         let mut stmt = conn
             .prepare(
-                "SELECT id, magnitude, latitude, longitude, depth, place, time, tsunami, title
+                "SELECT id, magnitude, latitude, longitude, depth, place, time, tsunami, title, alert
                  FROM earthquakes
                  WHERE fetched_at > strftime('%s', 'now') - 600
                  ORDER BY time DESC",
@@ -132,6 +311,7 @@ impl Database {
                     time: row.get(6)?,
                     tsunami: row.get::<_, i32>(7)? == 1,
                     title: row.get(8)?,
+                    alert: row.get(9)?,
                 })
             })
             .ok()?
@@ -209,7 +389,7 @@ impl Database {
         // Get earthquakes that were active at the given timestamp
         // (time <= timestamp AND time + 24h > timestamp)
         let mut stmt = match conn.prepare(
-            "SELECT id, magnitude, latitude, longitude, depth, place, time, tsunami, title
+            "SELECT id, magnitude, latitude, longitude, depth, place, time, tsunami, title, alert
              FROM earthquakes
              WHERE time <= ?1 AND time > ?1 - 86400000
              ORDER BY time DESC",
@@ -232,6 +412,7 @@ impl Database {
                 time: row.get(6)?,
                 tsunami: row.get::<_, i32>(7)? == 1,
                 title: row.get(8)?,
+                alert: row.get(9)?,
             })
         }) {
             Ok(rows) => rows.filter_map(|r| r.ok()).collect(),
@@ -276,38 +457,35 @@ impl Database {
                 'notify_aurora',
                 'notify_volcanoes',
                 'sonification_enabled',
-                'ollama_model'
+                'ollama_model',
+                'notify_conjunctions',
+                'conjunction_days',
+                'conjunction_threshold_km',
+                'decay_source',
+                'tle_max_age_hours',
+                'iss_crosscheck',
+                'calendar_server_addr',
+                'geocoder_url',
+                'quiet_hours_start',
+                'quiet_hours_end',
+                'rate_limit_per_hour',
+                'digest_minutes',
+                'eonet_categories',
+                'vaac_url',
+                'neo_lookahead_days',
+                'neo_max_distance_lunar',
+                'weather_alert_feeds'
             )",
         ) {
             Ok(s) => s,
             Err(e) => {
                 log::error!("Failed to prepare settings query: {}", e);
-                return UserSettings {
-                    user_lat: None,
-                    user_lon: None,
-                    mag_threshold: None,
-                    proximity_km: None,
-                    notify_earthquakes: None,
-                    notify_aurora: None,
-                    notify_volcanoes: None,
-                    sonification_enabled: None,
-                    ollama_model: None,
-                };
-            }
-        };
-
-        let mut settings = UserSettings {
-            user_lat: None,
-            user_lon: None,
-            mag_threshold: None,
-            proximity_km: None,
-            notify_earthquakes: None,
-            notify_aurora: None,
-            notify_volcanoes: None,
-            sonification_enabled: None,
-            ollama_model: None,
+                return UserSettings::default();
+            }
         };
 
+        let mut settings = UserSettings::default();
+
         let rows = match stmt.query_map([], |row| {
             Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
         }) {
@@ -332,6 +510,37 @@ impl Database {
                     settings.sonification_enabled = parse_bool_setting(&row.1)
                 }
                 "ollama_model" => settings.ollama_model = Some(row.1),
+                "notify_conjunctions" => {
+                    settings.notify_conjunctions = parse_bool_setting(&row.1)
+                }
+                "conjunction_days" => settings.conjunction_days = val,
+                "conjunction_threshold_km" => settings.conjunction_threshold_km = val,
+                "decay_source" => {
+                    settings.decay_source = Some(row.1).filter(|s| !s.trim().is_empty())
+                }
+                "tle_max_age_hours" => settings.tle_max_age_hours = val,
+                "iss_crosscheck" => settings.iss_crosscheck = parse_bool_setting(&row.1),
+                "calendar_server_addr" => {
+                    settings.calendar_server_addr = Some(row.1).filter(|s| !s.trim().is_empty())
+                }
+                "geocoder_url" => {
+                    settings.geocoder_url = Some(row.1).filter(|s| !s.trim().is_empty())
+                }
+                "quiet_hours_start" => {
+                    settings.quiet_hours_start = Some(row.1).filter(|s| !s.trim().is_empty())
+                }
+                "quiet_hours_end" => {
+                    settings.quiet_hours_end = Some(row.1).filter(|s| !s.trim().is_empty())
+                }
+                "rate_limit_per_hour" => settings.rate_limit_per_hour = val,
+                "digest_minutes" => settings.digest_minutes = val,
+                "neo_lookahead_days" => settings.neo_lookahead_days = val,
+                "neo_max_distance_lunar" => settings.neo_max_distance_lunar = val,
+                "vaac_url" => settings.vaac_url = Some(row.1).filter(|s| !s.trim().is_empty()),
+                // An empty list is a deliberate "no categories"
+                "eonet_categories" => settings.eonet_categories = Some(row.1),
+                // An empty list turns weather alerts off
+                "weather_alert_feeds" => settings.weather_alert_feeds = Some(row.1),
                 _ => {}
             }
         }
@@ -390,6 +599,15 @@ impl Database {
         }
     }
 
+    pub fn set_setting(&self, key: &str, value: &str) {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+            rusqlite::params![key, value],
+        )
+        .ok();
+    }
+
     // -- API cache methods --
 
     pub fn get_cached_response(&self, endpoint: &str, max_age_secs: i64) -> Option<String> {
@@ -411,30 +629,70 @@ impl Database {
         .ok();
     }
 
-    // -- Watchlist methods --
+    // -- TLE history methods --
 
-    pub fn get_watchlists(&self) -> Vec<Watchlist> {
+    pub fn store_tle(&self, record: &TleRecord) {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR IGNORE INTO tle_history (norad_id, name, epoch, line1, line2) VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![
+                record.norad_id,
+                record.name,
+                record.epoch,
+                record.line1,
+                record.line2
+            ],
+        )
+        .ok();
+    }
+
+    pub fn get_tle_history(&self, norad_id: &str, since_epoch: i64) -> Vec<TleRecord> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = match conn.prepare(
-            "SELECT id, name, latitude, longitude, radius_km, created_at FROM watchlists ORDER BY created_at DESC",
+            "SELECT norad_id, name, epoch, line1, line2 FROM tle_history
+             WHERE norad_id = ?1 AND epoch >= ?2
+             ORDER BY epoch ASC",
         ) {
             Ok(s) => s,
             Err(e) => {
-                log::error!("Failed to prepare watchlists query: {}", e);
+                log::error!("Failed to prepare TLE history query: {}", e);
                 return Vec::new();
             }
         };
 
-        let result = match stmt.query_map([], |row| {
-            Ok(Watchlist {
-                id: row.get(0)?,
+        let result = match stmt.query_map(rusqlite::params![norad_id, since_epoch], |row| {
+            Ok(TleRecord {
+                norad_id: row.get(0)?,
                 name: row.get(1)?,
-                latitude: row.get(2)?,
-                longitude: row.get(3)?,
-                radius_km: row.get(4)?,
-                created_at: row.get(5)?,
+                epoch: row.get(2)?,
+                line1: row.get(3)?,
+                line2: row.get(4)?,
             })
         }) {
+            Ok(rows) => rows.filter_map(|r| r.ok()).collect(),
+            Err(e) => {
+                log::error!("Failed to query TLE history: {}", e);
+                Vec::new()
+            }
+        };
+        result
+    }
+
+    // -- Watchlist methods --
+
+    pub fn get_watchlists(&self) -> Vec<Watchlist> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = match conn.prepare(
+            "SELECT id, name, latitude, longitude, radius_km, created_at, geometry, rules FROM watchlists ORDER BY created_at DESC",
+        ) {
+            Ok(s) => s,
+            Err(e) => {
+                log::error!("Failed to prepare watchlists query: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let result = match stmt.query_map([], row_to_watchlist) {
             Ok(rows) => rows.filter_map(|r| r.ok()).collect(),
             Err(e) => {
                 log::error!("Failed to query watchlists: {}", e);
@@ -444,32 +702,75 @@ impl Database {
         result
     }
 
+    pub fn get_watchlist(&self, id: i64) -> Option<Watchlist> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id, name, latitude, longitude, radius_km, created_at, geometry, rules FROM watchlists WHERE id = ?1",
+            rusqlite::params![id],
+            row_to_watchlist,
+        )
+        .ok()
+    }
+
+    /// `latitude`/`longitude`/`radius_km` summarise the shape for map display.
     pub fn add_watchlist(
         &self,
         name: &str,
         latitude: f64,
         longitude: f64,
         radius_km: f64,
+        shape: &WatchShape,
+        rules: &WatchRules,
     ) -> Result<Watchlist, rusqlite::Error> {
         let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "INSERT INTO watchlists (name, latitude, longitude, radius_km) VALUES (?1, ?2, ?3, ?4)",
-            rusqlite::params![name, latitude, longitude, radius_km],
-        )?;
-        let id = conn.last_insert_rowid();
-        let created_at: i64 = conn.query_row(
-            "SELECT created_at FROM watchlists WHERE id = ?1",
-            rusqlite::params![id],
-            |row| row.get(0),
+        insert_watchlist(
+            &conn,
+            Watchlist {
+                id: 0,
+                name: name.to_string(),
+                latitude,
+                longitude,
+                radius_km,
+                shape: shape.clone(),
+                rules: rules.clone(),
+                created_at: 0,
+            },
+        )
+    }
+
+    /// Insert several watchlists in one transaction, so either all of them are
+    /// added or none. `id` and `created_at` of the drafts are ignored.
+    pub fn add_watchlists(
+        &self,
+        drafts: Vec<Watchlist>,
+    ) -> Result<Vec<Watchlist>, rusqlite::Error> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let watchlists = drafts
+            .into_iter()
+            .map(|draft| insert_watchlist(&tx, draft))
+            .collect::<Result<Vec<_>, _>>()?;
+        tx.commit()?;
+        Ok(watchlists)
+    }
+
+    pub fn update_watchlist(&self, watchlist: &Watchlist) -> Result<bool, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        let changed = conn.execute(
+            "UPDATE watchlists
+             SET name = ?2, latitude = ?3, longitude = ?4, radius_km = ?5, geometry = ?6, rules = ?7
+             WHERE id = ?1",
+            rusqlite::params![
+                watchlist.id,
+                watchlist.name,
+                watchlist.latitude,
+                watchlist.longitude,
+                watchlist.radius_km,
+                shape_geometry_json(&watchlist.shape),
+                serde_json::to_string(&watchlist.rules).ok()
+            ],
         )?;
-        Ok(Watchlist {
-            id,
-            name: name.to_string(),
-            latitude,
-            longitude,
-            radius_km,
-            created_at,
-        })
+        Ok(changed > 0)
     }
 
     pub fn remove_watchlist(&self, id: i64) -> Result<(), rusqlite::Error> {
@@ -481,34 +782,1068 @@ impl Database {
         Ok(())
     }
 
-    // -- Cleanup --
+    // -- Observer profile methods --
 
-    pub fn cleanup_old_data(&self) {
+    pub fn get_observer_profiles(&self) -> Vec<ObserverProfile> {
         let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "DELETE FROM earthquakes WHERE fetched_at < strftime('%s', 'now') - 604800",
+        let mut stmt = match conn.prepare(
+            "SELECT id, name, latitude, longitude, elevation_m, timezone, is_active, created_at
+             FROM observer_profiles ORDER BY created_at ASC, id ASC",
+        ) {
+            Ok(s) => s,
+            Err(e) => {
+                log::error!("Failed to prepare observer profiles query: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let rows = match stmt.query_map([], row_to_observer_profile) {
+            Ok(r) => r,
+            Err(e) => {
+                log::error!("Failed to query observer profiles: {}", e);
+                return Vec::new();
+            }
+        };
+
+        rows.filter_map(|r| r.ok()).collect()
+    }
+
+    pub fn get_observer_profile(&self, id: i64) -> Option<ObserverProfile> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id, name, latitude, longitude, elevation_m, timezone, is_active, created_at
+             FROM observer_profiles WHERE id = ?1",
+            rusqlite::params![id],
+            row_to_observer_profile,
+        )
+        .ok()
+    }
+
+    pub fn get_active_observer_profile(&self) -> Option<ObserverProfile> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id, name, latitude, longitude, elevation_m, timezone, is_active, created_at
+             FROM observer_profiles ORDER BY is_active DESC, id ASC LIMIT 1",
             [],
+            row_to_observer_profile,
         )
-        .ok();
+        .ok()
+    }
+
+    pub fn add_observer_profile(
+        &self,
+        name: &str,
+        latitude: f64,
+        longitude: f64,
+        elevation_m: f64,
+        timezone: &str,
+    ) -> Result<ObserverProfile, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
         conn.execute(
-            "DELETE FROM iss_positions WHERE fetched_at < strftime('%s', 'now') - 604800",
-            [],
+            "INSERT INTO observer_profiles (name, latitude, longitude, elevation_m, timezone)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![name, latitude, longitude, elevation_m, timezone],
+        )?;
+        let id = conn.last_insert_rowid();
+        conn.query_row(
+            "SELECT id, name, latitude, longitude, elevation_m, timezone, is_active, created_at
+             FROM observer_profiles WHERE id = ?1",
+            rusqlite::params![id],
+            row_to_observer_profile,
         )
-        .ok();
+    }
+
+    pub fn update_observer_profile(
+        &self,
+        id: i64,
+        name: &str,
+        latitude: f64,
+        longitude: f64,
+        elevation_m: f64,
+        timezone: &str,
+    ) -> Result<bool, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        let changed = conn.execute(
+            "UPDATE observer_profiles
+             SET name = ?2, latitude = ?3, longitude = ?4, elevation_m = ?5, timezone = ?6
+             WHERE id = ?1",
+            rusqlite::params![id, name, latitude, longitude, elevation_m, timezone],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// Move the active profile's position; used when the legacy settings form saves coordinates.
+    pub fn set_active_observer_position(&self, latitude: f64, longitude: f64) {
+        let conn = self.conn.lock().unwrap();
         conn.execute(
-            "DELETE FROM api_cache WHERE fetched_at < strftime('%s', 'now') - 86400",
-            [],
+            "UPDATE observer_profiles SET latitude = ?1, longitude = ?2 WHERE is_active = 1",
+            rusqlite::params![latitude, longitude],
         )
         .ok();
     }
-}
 
-fn parse_bool_setting(value: &str) -> Option<bool> {
-    match value.to_ascii_lowercase().as_str() {
-        "true" | "1" | "yes" | "on" => Some(true),
-        "false" | "0" | "no" | "off" => Some(false),
-        _ => None,
+    pub fn set_active_observer_profile(&self, id: i64) -> Result<bool, rusqlite::Error> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let exists: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM observer_profiles WHERE id = ?1)",
+            rusqlite::params![id],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Ok(false);
+        }
+        tx.execute(
+            "UPDATE observer_profiles SET is_active = (id = ?1)",
+            rusqlite::params![id],
+        )?;
+        tx.commit()?;
+        Ok(true)
+    }
+
+    pub fn remove_observer_profile(&self, id: i64) -> Result<(), rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM observer_profiles WHERE id = ?1",
+            rusqlite::params![id],
+        )?;
+        Ok(())
+    }
+
+    // -- Alert rule methods --
+
+    pub fn get_alert_rules(&self) -> Vec<AlertRule> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = match conn.prepare(
+            "SELECT id, name, expression, enabled, created_at FROM alert_rules ORDER BY id ASC",
+        ) {
+            Ok(s) => s,
+            Err(e) => {
+                log::error!("Failed to prepare alert rules query: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let rows = match stmt.query_map([], row_to_alert_rule) {
+            Ok(r) => r,
+            Err(e) => {
+                log::error!("Failed to query alert rules: {}", e);
+                return Vec::new();
+            }
+        };
+
+        rows.filter_map(|r| r.ok()).collect()
+    }
+
+    pub fn add_alert_rule(
+        &self,
+        name: &str,
+        expression: &str,
+        enabled: bool,
+    ) -> Result<AlertRule, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO alert_rules (name, expression, enabled) VALUES (?1, ?2, ?3)",
+            rusqlite::params![name, expression, enabled],
+        )?;
+        let id = conn.last_insert_rowid();
+        conn.query_row(
+            "SELECT id, name, expression, enabled, created_at FROM alert_rules WHERE id = ?1",
+            rusqlite::params![id],
+            row_to_alert_rule,
+        )
+    }
+
+    /// Returns `None` when no rule has this id.
+    pub fn update_alert_rule(
+        &self,
+        id: i64,
+        name: &str,
+        expression: &str,
+        enabled: bool,
+    ) -> Result<Option<AlertRule>, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        let changed = conn.execute(
+            "UPDATE alert_rules SET name = ?2, expression = ?3, enabled = ?4 WHERE id = ?1",
+            rusqlite::params![id, name, expression, enabled],
+        )?;
+        if changed == 0 {
+            return Ok(None);
+        }
+        conn.query_row(
+            "SELECT id, name, expression, enabled, created_at FROM alert_rules WHERE id = ?1",
+            rusqlite::params![id],
+            row_to_alert_rule,
+        )
+        .map(Some)
+    }
+
+    pub fn remove_alert_rule(&self, id: i64) -> Result<(), rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM alert_rules WHERE id = ?1", rusqlite::params![id])?;
+        Ok(())
     }
+
+    // -- Alert channel methods --
+
+    pub fn get_alert_channels(&self) -> Vec<AlertChannel> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = match conn.prepare(
+            "SELECT id, name, config, template, enabled, created_at FROM alert_channels ORDER BY id ASC",
+        ) {
+            Ok(s) => s,
+            Err(e) => {
+                log::error!("Failed to prepare alert channels query: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let rows = match stmt.query_map([], row_to_alert_channel) {
+            Ok(r) => r,
+            Err(e) => {
+                log::error!("Failed to query alert channels: {}", e);
+                return Vec::new();
+            }
+        };
+
+        // Rows whose config no longer parses are skipped
+        rows.filter_map(|r| r.ok()).flatten().collect()
+    }
+
+    pub fn get_alert_channel(&self, id: i64) -> Option<AlertChannel> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id, name, config, template, enabled, created_at FROM alert_channels WHERE id = ?1",
+            rusqlite::params![id],
+            row_to_alert_channel,
+        )
+        .ok()
+        .flatten()
+    }
+
+    pub fn add_alert_channel(
+        &self,
+        name: &str,
+        config: &ChannelConfig,
+        template: Option<&str>,
+        enabled: bool,
+    ) -> Result<i64, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO alert_channels (name, config, template, enabled) VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![
+                name,
+                serde_json::to_string(config).unwrap_or_default(),
+                template,
+                enabled
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    pub fn update_alert_channel(
+        &self,
+        id: i64,
+        name: &str,
+        config: &ChannelConfig,
+        template: Option<&str>,
+        enabled: bool,
+    ) -> Result<bool, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        let changed = conn.execute(
+            "UPDATE alert_channels SET name = ?2, config = ?3, template = ?4, enabled = ?5 WHERE id = ?1",
+            rusqlite::params![
+                id,
+                name,
+                serde_json::to_string(config).unwrap_or_default(),
+                template,
+                enabled
+            ],
+        )?;
+        Ok(changed > 0)
+    }
+
+    pub fn remove_alert_channel(&self, id: i64) -> Result<(), rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM alert_channels WHERE id = ?1",
+            rusqlite::params![id],
+        )?;
+        Ok(())
+    }
+
+    // -- Notification history methods --
+
+    /// Record `key` as notified. Returns false if it already was.
+    pub fn claim_notification_key(&self, key: &str) -> bool {
+        let conn = self.conn.lock().unwrap();
+        match conn.execute(
+            "INSERT OR IGNORE INTO notification_keys (key) VALUES (?1)",
+            rusqlite::params![key],
+        ) {
+            Ok(inserted) => inserted > 0,
+            Err(e) => {
+                // Better a duplicate toast than a missed one
+                log::error!("Failed to record notification key: {}", e);
+                true
+            }
+        }
+    }
+
+    /// Record `{key}:{at}` as notified unless a time within `tolerance_secs`
+    /// of `at` already was for `key`, so an event whose predicted time drifts
+    /// between runs counts once. Returns false if it was already notified.
+    pub fn claim_notification_key_near(&self, key: &str, at: i64, tolerance_secs: i64) -> bool {
+        let conn = self.conn.lock().unwrap();
+        let prefix = format!("{}:", key);
+        let claimed: Vec<i64> = match conn.prepare(
+            "SELECT substr(key, length(?1) + 1) FROM notification_keys
+             WHERE substr(key, 1, length(?1)) = ?1",
+        ) {
+            Ok(mut stmt) => stmt
+                .query_map(rusqlite::params![prefix], |row| row.get::<_, String>(0))
+                .map(|rows| rows.filter_map(|r| r.ok()?.parse().ok()).collect())
+                .unwrap_or_default(),
+            Err(e) => {
+                log::error!("Failed to prepare notification key query: {}", e);
+                Vec::new()
+            }
+        };
+        if claimed.iter().any(|t| (t - at).abs() <= tolerance_secs) {
+            return false;
+        }
+
+        match conn.execute(
+            "INSERT OR IGNORE INTO notification_keys (key) VALUES (?1)",
+            rusqlite::params![format!("{}{}", prefix, at)],
+        ) {
+            Ok(inserted) => inserted > 0,
+            Err(e) => {
+                log::error!("Failed to record notification key: {}", e);
+                true
+            }
+        }
+    }
+
+    pub fn add_notification(&self, record: &NotificationRecord) -> Result<i64, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO notification_history
+             (source, event_id, rule, title, body, latitude, longitude, suppressed, held,
+              severity, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            rusqlite::params![
+                record.source,
+                record.event_id,
+                record.rule,
+                record.title,
+                record.body,
+                record.latitude,
+                record.longitude,
+                record.suppressed,
+                record.held,
+                record.severity,
+                record.created_at
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Notifications shown for `source` since `since`, for rate limiting.
+    pub fn count_shown_notifications(&self, source: &str, since: i64) -> i64 {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT COUNT(*) FROM notification_history
+             WHERE source = ?1 AND created_at >= ?2 AND suppressed = 0",
+            rusqlite::params![source, since],
+            |row| row.get(0),
+        )
+        .unwrap_or(0)
+    }
+
+    /// Notifications waiting for the next digest, oldest first.
+    pub fn get_held_notifications(&self) -> Vec<NotificationRecord> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = match conn.prepare(
+            "SELECT id, source, event_id, rule, title, body, latitude, longitude, suppressed,
+                    held, severity, acknowledged_at, created_at
+             FROM notification_history
+             WHERE held = 1
+             ORDER BY created_at ASC, id ASC",
+        ) {
+            Ok(s) => s,
+            Err(e) => {
+                log::error!("Failed to prepare held notifications query: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let rows = match stmt.query_map([], row_to_notification) {
+            Ok(r) => r,
+            Err(e) => {
+                log::error!("Failed to query held notifications: {}", e);
+                return Vec::new();
+            }
+        };
+
+        rows.filter_map(|r| r.ok()).collect()
+    }
+
+    pub fn release_held_notifications(&self, ids: &[i64]) -> Result<(), rusqlite::Error> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        for id in ids {
+            tx.execute(
+                "UPDATE notification_history SET held = 0 WHERE id = ?1",
+                rusqlite::params![id],
+            )?;
+        }
+        tx.commit()
+    }
+
+    /// Newest first. `None` filters match everything.
+    pub fn get_notification_history(
+        &self,
+        source: Option<&str>,
+        since: Option<i64>,
+        unacknowledged_only: bool,
+        limit: u32,
+    ) -> Vec<NotificationRecord> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = match conn.prepare(
+            "SELECT id, source, event_id, rule, title, body, latitude, longitude, suppressed,
+                    held, severity, acknowledged_at, created_at
+             FROM notification_history
+             WHERE (?1 IS NULL OR source = ?1)
+               AND (?2 IS NULL OR created_at >= ?2)
+               AND (?3 = 0 OR acknowledged_at IS NULL)
+             ORDER BY created_at DESC, id DESC
+             LIMIT ?4",
+        ) {
+            Ok(s) => s,
+            Err(e) => {
+                log::error!("Failed to prepare notification history query: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let rows = match stmt.query_map(
+            rusqlite::params![source, since, unacknowledged_only, limit],
+            row_to_notification,
+        ) {
+            Ok(r) => r,
+            Err(e) => {
+                log::error!("Failed to query notification history: {}", e);
+                return Vec::new();
+            }
+        };
+
+        rows.filter_map(|r| r.ok()).collect()
+    }
+
+    /// Returns how many notifications were newly acknowledged.
+    pub fn acknowledge_notifications(&self, ids: &[i64]) -> Result<usize, rusqlite::Error> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let mut count = 0;
+        for id in ids {
+            count += tx.execute(
+                "UPDATE notification_history SET acknowledged_at = strftime('%s', 'now')
+                 WHERE id = ?1 AND acknowledged_at IS NULL",
+                rusqlite::params![id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(count)
+    }
+
+    pub fn add_notification_snooze(
+        &self,
+        source: Option<&str>,
+        region: Option<&NotificationRegion>,
+        until: i64,
+    ) -> Result<NotificationSnooze, rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO notification_snoozes (source, latitude, longitude, radius_km, until)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            rusqlite::params![
+                source,
+                region.map(|r| r.latitude),
+                region.map(|r| r.longitude),
+                region.map(|r| r.radius_km),
+                until
+            ],
+        )?;
+        let id = conn.last_insert_rowid();
+        conn.query_row(
+            "SELECT id, source, latitude, longitude, radius_km, until, created_at
+             FROM notification_snoozes WHERE id = ?1",
+            rusqlite::params![id],
+            row_to_snooze,
+        )
+    }
+
+    pub fn get_active_snoozes(&self, now: i64) -> Vec<NotificationSnooze> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = match conn.prepare(
+            "SELECT id, source, latitude, longitude, radius_km, until, created_at
+             FROM notification_snoozes WHERE until > ?1 ORDER BY until ASC",
+        ) {
+            Ok(s) => s,
+            Err(e) => {
+                log::error!("Failed to prepare snooze query: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let rows = match stmt.query_map(rusqlite::params![now], row_to_snooze) {
+            Ok(r) => r,
+            Err(e) => {
+                log::error!("Failed to query snoozes: {}", e);
+                return Vec::new();
+            }
+        };
+
+        rows.filter_map(|r| r.ok()).collect()
+    }
+
+    pub fn remove_notification_snooze(&self, id: i64) -> Result<(), rusqlite::Error> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM notification_snoozes WHERE id = ?1",
+            rusqlite::params![id],
+        )?;
+        Ok(())
+    }
+
+    // -- GDACS episode methods --
+
+    /// Record the alert's current episode. Returns false if it was already known.
+    pub fn add_gdacs_episode(&self, alert: &GdacsAlert) -> bool {
+        let conn = self.conn.lock().unwrap();
+        // Feeds without episode ids still get one row per alert level
+        let episode_id = alert
+            .episode_id
+            .clone()
+            .unwrap_or_else(|| alert.severity.to_ascii_lowercase());
+        match conn.execute(
+            "INSERT OR IGNORE INTO gdacs_episodes
+             (alert_id, episode_id, alert_level, severity_value, severity_unit, population,
+              from_date, to_date)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                alert.id,
+                episode_id,
+                alert.severity,
+                alert.severity_value,
+                alert.severity_unit,
+                alert.population,
+                alert.from_date,
+                alert.to_date
+            ],
+        ) {
+            Ok(inserted) => inserted > 0,
+            Err(e) => {
+                log::error!("Failed to record GDACS episode: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Oldest first.
+    pub fn get_gdacs_episodes(&self, alert_id: &str) -> Vec<GdacsEpisode> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = match conn.prepare(
+            "SELECT episode_id, alert_level, severity_value, severity_unit, population,
+                    from_date, to_date, recorded_at
+             FROM gdacs_episodes
+             WHERE alert_id = ?1
+             ORDER BY recorded_at ASC, rowid ASC",
+        ) {
+            Ok(s) => s,
+            Err(e) => {
+                log::error!("Failed to prepare GDACS episode query: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let rows = match stmt.query_map(rusqlite::params![alert_id], |row| {
+            Ok(GdacsEpisode {
+                episode_id: row.get(0)?,
+                alert_level: row.get(1)?,
+                severity_value: row.get(2)?,
+                severity_unit: row.get(3)?,
+                population: row.get(4)?,
+                from_date: row.get(5)?,
+                to_date: row.get(6)?,
+                recorded_at: row.get(7)?,
+            })
+        }) {
+            Ok(r) => r,
+            Err(e) => {
+                log::error!("Failed to query GDACS episodes: {}", e);
+                return Vec::new();
+            }
+        };
+
+        rows.filter_map(|r| r.ok()).collect()
+    }
+
+    // -- Asteroid orbit methods --
+
+    pub fn get_orbit_class(&self, spk_id: &str) -> Option<OrbitClass> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT code, name FROM asteroid_orbits WHERE spk_id = ?1",
+            rusqlite::params![spk_id],
+            |row| {
+                Ok(OrbitClass {
+                    code: row.get(0)?,
+                    name: row.get(1)?,
+                })
+            },
+        )
+        .ok()
+    }
+
+    pub fn set_orbit_class(&self, spk_id: &str, class: &OrbitClass) {
+        let conn = self.conn.lock().unwrap();
+        if let Err(e) = conn.execute(
+            "INSERT OR REPLACE INTO asteroid_orbits (spk_id, code, name) VALUES (?1, ?2, ?3)",
+            rusqlite::params![spk_id, class.code, class.name],
+        ) {
+            log::error!("Failed to store orbit class: {}", e);
+        }
+    }
+
+    /// Upsert every approach of the given objects.
+    pub fn store_asteroid_approaches(&self, asteroids: &[Asteroid]) {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = match conn.prepare(
+            "INSERT OR REPLACE INTO asteroid_approaches
+             (designation, name, approach_time, approach_date, velocity_kps,
+              miss_distance_km, miss_distance_lunar, orbiting_body, source)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        ) {
+            Ok(s) => s,
+            Err(e) => {
+                log::error!("Failed to prepare asteroid approach insert: {}", e);
+                return;
+            }
+        };
+        for asteroid in asteroids.iter().filter(|a| !a.designation.is_empty()) {
+            for a in &asteroid.approaches {
+                if let Err(e) = stmt.execute(rusqlite::params![
+                    asteroid.designation,
+                    asteroid.name,
+                    a.time,
+                    a.date,
+                    a.velocity_kps,
+                    a.miss_distance_km,
+                    a.miss_distance_lunar,
+                    a.orbiting_body,
+                    a.source
+                ]) {
+                    log::error!("Failed to store asteroid approach: {}", e);
+                }
+            }
+        }
+    }
+
+    /// Stored approaches of one object, oldest first.
+    pub fn get_asteroid_approaches(&self, designation: &str) -> Vec<AsteroidApproach> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = match conn.prepare(
+            "SELECT approach_date, approach_time, velocity_kps, miss_distance_km,
+                    miss_distance_lunar, orbiting_body, source
+             FROM asteroid_approaches
+             WHERE designation = ?1
+             ORDER BY approach_time ASC",
+        ) {
+            Ok(s) => s,
+            Err(e) => {
+                log::error!("Failed to prepare asteroid approach query: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let rows = match stmt.query_map(rusqlite::params![designation], |row| {
+            Ok(AsteroidApproach {
+                date: row.get(0)?,
+                time: row.get(1)?,
+                velocity_kps: row.get(2)?,
+                miss_distance_km: row.get(3)?,
+                miss_distance_lunar: row.get(4)?,
+                orbiting_body: row.get(5)?,
+                source: row.get(6)?,
+            })
+        }) {
+            Ok(r) => r,
+            Err(e) => {
+                log::error!("Failed to query asteroid approaches: {}", e);
+                return Vec::new();
+            }
+        };
+
+        rows.filter_map(|r| r.ok()).collect()
+    }
+
+    // -- Volcano status methods --
+
+    /// Record the volcano's status if it differs from the last one recorded
+    /// from the same source, so a GVP fallback while HANS is down does not
+    /// stand in for the official level. Returns false when nothing changed.
+    pub fn add_volcano_status(&self, volcano: &Volcano) -> bool {
+        let source = volcano.status_source();
+        let last = self.get_last_volcano_status(&volcano.id, source);
+        if last.is_some_and(|last| {
+            last.status == volcano.status && last.aviation_color == volcano.aviation_color
+        }) {
+            return false;
+        }
+
+        let conn = self.conn.lock().unwrap();
+        match conn.execute(
+            "INSERT INTO volcano_status_history (volcano_id, status, aviation_color, source)
+             VALUES (?1, ?2, ?3, ?4)",
+            rusqlite::params![volcano.id, volcano.status, volcano.aviation_color, source],
+        ) {
+            Ok(_) => true,
+            Err(e) => {
+                log::error!("Failed to record volcano status: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Most recent status recorded for the volcano from `source`.
+    pub fn get_last_volcano_status(
+        &self,
+        volcano_id: &str,
+        source: &str,
+    ) -> Option<VolcanoStatusChange> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT status, aviation_color, source, recorded_at
+             FROM volcano_status_history
+             WHERE volcano_id = ?1 AND source = ?2
+             ORDER BY recorded_at DESC, rowid DESC LIMIT 1",
+            rusqlite::params![volcano_id, source],
+            |row| {
+                Ok(VolcanoStatusChange {
+                    status: row.get(0)?,
+                    aviation_color: row.get(1)?,
+                    source: row.get(2)?,
+                    recorded_at: row.get(3)?,
+                })
+            },
+        )
+        .ok()
+    }
+
+    /// Oldest first.
+    pub fn get_volcano_status_history(&self, volcano_id: &str) -> Vec<VolcanoStatusChange> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = match conn.prepare(
+            "SELECT status, aviation_color, source, recorded_at
+             FROM volcano_status_history
+             WHERE volcano_id = ?1
+             ORDER BY recorded_at ASC, rowid ASC",
+        ) {
+            Ok(s) => s,
+            Err(e) => {
+                log::error!("Failed to prepare volcano status query: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let rows = match stmt.query_map(rusqlite::params![volcano_id], |row| {
+            Ok(VolcanoStatusChange {
+                status: row.get(0)?,
+                aviation_color: row.get(1)?,
+                source: row.get(2)?,
+                recorded_at: row.get(3)?,
+            })
+        }) {
+            Ok(r) => r,
+            Err(e) => {
+                log::error!("Failed to query volcano status history: {}", e);
+                return Vec::new();
+            }
+        };
+
+        rows.filter_map(|r| r.ok()).collect()
+    }
+
+    // -- Weather alert methods --
+
+    /// Replace the stored alerts of one feed with its current ones, so
+    /// alerts the feed no longer lists are dropped.
+    pub fn replace_weather_alerts(
+        &self,
+        feed_url: &str,
+        alerts: &[WeatherAlert],
+    ) -> Result<(), rusqlite::Error> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "DELETE FROM weather_alerts WHERE feed_url = ?1",
+            rusqlite::params![feed_url],
+        )?;
+        for alert in alerts {
+            let Ok(data) = serde_json::to_string(alert) else {
+                continue;
+            };
+            tx.execute(
+                "INSERT OR REPLACE INTO weather_alerts (id, feed_url, expires_at, data)
+                 VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params![alert.id, feed_url, alert.expires_at(), data],
+            )?;
+        }
+        tx.commit()
+    }
+
+    /// Stored alerts that have not expired, newest first.
+    pub fn get_weather_alerts(&self) -> Vec<WeatherAlert> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = match conn.prepare(
+            "SELECT data FROM weather_alerts
+             WHERE expires_at IS NULL OR expires_at > strftime('%s', 'now')",
+        ) {
+            Ok(s) => s,
+            Err(e) => {
+                log::error!("Failed to prepare weather alert query: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let rows = match stmt.query_map([], |row| row.get::<_, String>(0)) {
+            Ok(r) => r,
+            Err(e) => {
+                log::error!("Failed to query weather alerts: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let mut alerts: Vec<WeatherAlert> = rows
+            .filter_map(|r| r.ok())
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect();
+        alerts.sort_by(|a, b| b.sent.cmp(&a.sent));
+        alerts
+    }
+
+    // -- Cleanup --
+
+    pub fn cleanup_old_data(&self) {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM earthquakes WHERE fetched_at < strftime('%s', 'now') - 604800",
+            [],
+        )
+        .ok();
+        conn.execute(
+            "DELETE FROM iss_positions WHERE fetched_at < strftime('%s', 'now') - 604800",
+            [],
+        )
+        .ok();
+        conn.execute(
+            "DELETE FROM api_cache WHERE fetched_at < strftime('%s', 'now') - 86400",
+            [],
+        )
+        .ok();
+        conn.execute(
+            "DELETE FROM tle_history WHERE epoch < strftime('%s', 'now') - 31536000",
+            [],
+        )
+        .ok();
+        conn.execute(
+            "DELETE FROM notification_history WHERE created_at < strftime('%s', 'now') - 7776000",
+            [],
+        )
+        .ok();
+        conn.execute(
+            "DELETE FROM notification_keys WHERE created_at < strftime('%s', 'now') - 2592000",
+            [],
+        )
+        .ok();
+        conn.execute(
+            "DELETE FROM notification_snoozes WHERE until < strftime('%s', 'now')",
+            [],
+        )
+        .ok();
+        conn.execute(
+            "DELETE FROM gdacs_episodes WHERE recorded_at < strftime('%s', 'now') - 15552000",
+            [],
+        )
+        .ok();
+        conn.execute(
+            "DELETE FROM asteroid_approaches WHERE approach_time < (strftime('%s', 'now') - 31536000) * 1000",
+            [],
+        )
+        .ok();
+        conn.execute(
+            "DELETE FROM volcano_status_history WHERE recorded_at < strftime('%s', 'now') - 31536000",
+            [],
+        )
+        .ok();
+        // Alerts without an expiry go once their feed hasn't been read for a week
+        conn.execute(
+            "DELETE FROM weather_alerts
+             WHERE expires_at < strftime('%s', 'now') OR fetched_at < strftime('%s', 'now') - 604800",
+            [],
+        )
+        .ok();
+    }
+}
+
+fn insert_watchlist(conn: &Connection, draft: Watchlist) -> Result<Watchlist, rusqlite::Error> {
+    conn.execute(
+        "INSERT INTO watchlists (name, latitude, longitude, radius_km, geometry, rules) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![
+            draft.name,
+            draft.latitude,
+            draft.longitude,
+            draft.radius_km,
+            shape_geometry_json(&draft.shape),
+            serde_json::to_string(&draft.rules).ok()
+        ],
+    )?;
+    let id = conn.last_insert_rowid();
+    let created_at: i64 = conn.query_row(
+        "SELECT created_at FROM watchlists WHERE id = ?1",
+        rusqlite::params![id],
+        |row| row.get(0),
+    )?;
+    Ok(Watchlist {
+        id,
+        created_at,
+        ..draft
+    })
+}
+
+fn parse_bool_setting(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Some(true),
+        "false" | "0" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+fn row_to_watchlist(row: &rusqlite::Row) -> rusqlite::Result<Watchlist> {
+    let latitude: f64 = row.get(2)?;
+    let longitude: f64 = row.get(3)?;
+    let radius_km: f64 = row.get(4)?;
+    // Rows without stored geometry are plain point + radius watchlists
+    let shape = row
+        .get::<_, Option<String>>(6)?
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or(WatchShape::Circle {
+            latitude,
+            longitude,
+            radius_km,
+        });
+    let rules = row
+        .get::<_, Option<String>>(7)?
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    Ok(Watchlist {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        latitude,
+        longitude,
+        radius_km,
+        shape,
+        rules,
+        created_at: row.get(5)?,
+    })
+}
+
+/// Circles live entirely in the latitude/longitude/radius columns.
+fn shape_geometry_json(shape: &WatchShape) -> Option<String> {
+    match shape {
+        WatchShape::Circle { .. } => None,
+        _ => serde_json::to_string(shape).ok(),
+    }
+}
+
+fn row_to_alert_rule(row: &rusqlite::Row) -> rusqlite::Result<AlertRule> {
+    Ok(AlertRule {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        expression: row.get(2)?,
+        enabled: row.get(3)?,
+        created_at: row.get(4)?,
+    })
+}
+
+fn row_to_alert_channel(row: &rusqlite::Row) -> rusqlite::Result<Option<AlertChannel>> {
+    let config: String = row.get(2)?;
+    let Ok(config) = serde_json::from_str(&config) else {
+        log::warn!("Ignoring alert channel with unreadable config");
+        return Ok(None);
+    };
+    Ok(Some(AlertChannel {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        config,
+        template: row.get(3)?,
+        enabled: row.get(4)?,
+        created_at: row.get(5)?,
+    }))
+}
+
+fn row_to_notification(row: &rusqlite::Row) -> rusqlite::Result<NotificationRecord> {
+    Ok(NotificationRecord {
+        id: row.get(0)?,
+        source: row.get(1)?,
+        event_id: row.get(2)?,
+        rule: row.get(3)?,
+        title: row.get(4)?,
+        body: row.get(5)?,
+        latitude: row.get(6)?,
+        longitude: row.get(7)?,
+        suppressed: row.get(8)?,
+        held: row.get(9)?,
+        severity: row.get(10)?,
+        acknowledged_at: row.get(11)?,
+        created_at: row.get(12)?,
+    })
+}
+
+fn row_to_snooze(row: &rusqlite::Row) -> rusqlite::Result<NotificationSnooze> {
+    let latitude: Option<f64> = row.get(2)?;
+    let longitude: Option<f64> = row.get(3)?;
+    let radius_km: Option<f64> = row.get(4)?;
+    let region = match (latitude, longitude, radius_km) {
+        (Some(latitude), Some(longitude), Some(radius_km)) => Some(NotificationRegion {
+            latitude,
+            longitude,
+            radius_km,
+        }),
+        _ => None,
+    };
+    Ok(NotificationSnooze {
+        id: row.get(0)?,
+        source: row.get(1)?,
+        region,
+        until: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
+
+fn row_to_observer_profile(row: &rusqlite::Row) -> rusqlite::Result<ObserverProfile> {
+    Ok(ObserverProfile {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        latitude: row.get(2)?,
+        longitude: row.get(3)?,
+        elevation_m: row.get(4)?,
+        timezone: row.get(5)?,
+        is_active: row.get::<_, i64>(6)? != 0,
+        created_at: row.get(7)?,
+    })
 }
 
 #[cfg(test)]
@@ -0,0 +1,431 @@
+//! Forwarding notifications to webhooks, push services and email.
+
+mod smtp;
+
+use crate::fetchers::http::HTTP_CLIENT;
+use crate::models::channel::{AlertChannel, ChannelConfig, SmtpSecurity};
+use crate::models::notification::NotificationRecord;
+use std::time::Duration;
+
+pub const DEFAULT_TEMPLATE: &str = "{title}\n{body}";
+
+/// Waits between attempts; one more attempt than there are delays.
+const RETRY_DELAYS: [Duration; 2] = [Duration::from_secs(2), Duration::from_secs(10)];
+
+enum SendError {
+    /// Network failures, 429 and 5xx responses
+    Retryable(String),
+    Fatal(String),
+}
+
+/// Fill `{title}`, `{body}`, `{source}`, `{rule}`, `{event_id}`, `{time}` and
+/// `{location}` in a channel template.
+pub fn render_template(template: &str, record: &NotificationRecord) -> String {
+    let time = chrono::DateTime::from_timestamp(record.created_at, 0)
+        .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_default();
+    let location = match (record.latitude, record.longitude) {
+        (Some(lat), Some(lon)) => format!("{:.3}, {:.3}", lat, lon),
+        _ => String::new(),
+    };
+    template
+        .replace("{title}", &record.title)
+        .replace("{body}", &record.body)
+        .replace("{source}", &record.source)
+        .replace("{rule}", &record.rule)
+        .replace("{event_id}", &record.event_id)
+        .replace("{time}", &time)
+        .replace("{location}", &location)
+}
+
+pub fn validate_channel(config: &ChannelConfig) -> Result<(), String> {
+    let check_url = |url: &str| {
+        if url.starts_with("http://") || url.starts_with("https://") {
+            Ok(())
+        } else {
+            Err(format!("'{}' is not an http(s) URL", url))
+        }
+    };
+    match config {
+        ChannelConfig::Webhook { url } => check_url(url),
+        ChannelConfig::Slack { webhook_url } | ChannelConfig::Discord { webhook_url } => {
+            check_url(webhook_url)
+        }
+        ChannelConfig::Ntfy { server, topic, .. } => {
+            check_url(server)?;
+            if topic.trim().is_empty() || topic.contains('/') {
+                return Err("ntfy topic must be a single path segment".to_string());
+            }
+            Ok(())
+        }
+        ChannelConfig::Gotify { server, app_token } => {
+            check_url(server)?;
+            if app_token.trim().is_empty() {
+                return Err("Gotify needs an application token".to_string());
+            }
+            Ok(())
+        }
+        ChannelConfig::Email {
+            host,
+            port,
+            from,
+            to,
+            username,
+            security,
+            ..
+        } => {
+            if host.trim().is_empty() || *port == 0 {
+                return Err("Email needs an SMTP host and port".to_string());
+            }
+            // Addresses go into the message headers verbatim, so a line break
+            // would let them add headers of their own
+            let valid = |address: &str| address.contains('@') && !address.contains(['\r', '\n']);
+            if !valid(from) || to.is_empty() || !to.iter().all(|t| valid(t)) {
+                return Err("Email needs valid sender and recipient addresses".to_string());
+            }
+            let security = security.unwrap_or_else(|| smtp::default_security(host, *port));
+            if username.is_some() && security == SmtpSecurity::None && !smtp::is_loopback(host) {
+                return Err(
+                    "SMTP login needs TLS or STARTTLS unless the relay is on this machine"
+                        .to_string(),
+                );
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Deliver to every channel, logging failures.
+pub async fn dispatch(channels: Vec<AlertChannel>, record: NotificationRecord) {
+    for channel in channels {
+        if let Err(e) = deliver(&channel, &record).await {
+            log::warn!("Alert delivery to \"{}\" failed: {}", channel.name, e);
+        }
+    }
+}
+
+pub async fn deliver(channel: &AlertChannel, record: &NotificationRecord) -> Result<(), String> {
+    deliver_with_retry(channel, record, &RETRY_DELAYS).await
+}
+
+async fn deliver_with_retry(
+    channel: &AlertChannel,
+    record: &NotificationRecord,
+    delays: &[Duration],
+) -> Result<(), String> {
+    let text = render_template(
+        channel.template.as_deref().unwrap_or(DEFAULT_TEMPLATE),
+        record,
+    );
+    let mut attempt = 0;
+    loop {
+        match send_once(&channel.config, record, &text).await {
+            Ok(()) => return Ok(()),
+            Err(SendError::Fatal(e)) => return Err(e),
+            Err(SendError::Retryable(e)) => match delays.get(attempt) {
+                Some(delay) => {
+                    log::debug!("Retrying \"{}\" after error: {}", channel.name, e);
+                    tokio::time::sleep(*delay).await;
+                    attempt += 1;
+                }
+                None => return Err(format!("{} (after {} attempts)", e, attempt + 1)),
+            },
+        }
+    }
+}
+
+struct HttpRequest {
+    url: String,
+    headers: Vec<(&'static str, String)>,
+    body: String,
+}
+
+/// The HTTP request for a channel; `None` for email.
+fn http_request(
+    config: &ChannelConfig,
+    record: &NotificationRecord,
+    text: &str,
+) -> Option<HttpRequest> {
+    let json = |value: serde_json::Value| value.to_string();
+    let request = match config {
+        ChannelConfig::Webhook { url } => HttpRequest {
+            url: url.clone(),
+            headers: vec![("Content-Type", "application/json".to_string())],
+            body: json(serde_json::json!({
+                "title": record.title,
+                "body": record.body,
+                "text": text,
+                "source": record.source,
+                "event_id": record.event_id,
+                "rule": record.rule,
+                "latitude": record.latitude,
+                "longitude": record.longitude,
+                "created_at": record.created_at,
+            })),
+        },
+        ChannelConfig::Slack { webhook_url } => HttpRequest {
+            url: webhook_url.clone(),
+            headers: vec![("Content-Type", "application/json".to_string())],
+            body: json(serde_json::json!({ "text": text })),
+        },
+        ChannelConfig::Discord { webhook_url } => HttpRequest {
+            url: webhook_url.clone(),
+            headers: vec![("Content-Type", "application/json".to_string())],
+            // Discord rejects messages over 2000 characters
+            body: json(
+                serde_json::json!({ "content": text.chars().take(2000).collect::<String>() }),
+            ),
+        },
+        ChannelConfig::Ntfy {
+            server,
+            topic,
+            token,
+        } => {
+            let mut headers = vec![
+                ("Content-Type", "text/plain; charset=utf-8".to_string()),
+                // Header values must be ASCII; non-ASCII titles stay in the body only
+                (
+                    "Title",
+                    record.title.chars().filter(char::is_ascii).collect(),
+                ),
+                ("Tags", record.source.clone()),
+            ];
+            if let Some(token) = token {
+                headers.push(("Authorization", format!("Bearer {}", token)));
+            }
+            HttpRequest {
+                url: format!("{}/{}", server.trim_end_matches('/'), topic),
+                headers,
+                body: text.to_string(),
+            }
+        }
+        ChannelConfig::Gotify { server, app_token } => HttpRequest {
+            url: format!("{}/message", server.trim_end_matches('/')),
+            headers: vec![
+                ("Content-Type", "application/json".to_string()),
+                ("X-Gotify-Key", app_token.clone()),
+            ],
+            body: json(serde_json::json!({
+                "title": record.title,
+                "message": text,
+                "priority": 5,
+            })),
+        },
+        ChannelConfig::Email { .. } => return None,
+    };
+    Some(request)
+}
+
+async fn send_once(
+    config: &ChannelConfig,
+    record: &NotificationRecord,
+    text: &str,
+) -> Result<(), SendError> {
+    if let ChannelConfig::Email {
+        host,
+        port,
+        from,
+        to,
+        username,
+        password,
+        security,
+    } = config
+    {
+        let security = security.unwrap_or_else(|| smtp::default_security(host, *port));
+        let credentials = username
+            .as_deref()
+            .map(|user| (user, password.as_deref().unwrap_or_default()));
+        let message = smtp::Message {
+            from,
+            to,
+            subject: &record.title,
+            body: text,
+        };
+        return smtp::send(host, *port, security, credentials, &message)
+            .await
+            .map_err(|e| match e {
+                smtp::SmtpError::Transient(e) => SendError::Retryable(e),
+                smtp::SmtpError::Permanent(e) => SendError::Fatal(e),
+            });
+    }
+
+    let Some(request) = http_request(config, record, text) else {
+        return Ok(());
+    };
+    let mut builder = HTTP_CLIENT.post(&request.url).body(request.body);
+    for (name, value) in request.headers {
+        builder = builder.header(name, value);
+    }
+    let response = builder
+        .send()
+        .await
+        .map_err(|e| SendError::Retryable(format!("Failed to reach {}: {}", request.url, e)))?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(())
+    } else if status.is_server_error() || status.as_u16() == 429 {
+        Err(SendError::Retryable(format!(
+            "{} returned {}",
+            request.url, status
+        )))
+    } else {
+        Err(SendError::Fatal(format!(
+            "{} returned {}",
+            request.url, status
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn record() -> NotificationRecord {
+        NotificationRecord {
+            id: 1,
+            source: "earthquake".to_string(),
+            event_id: "us7000abcd".to_string(),
+            rule: "magnitude_threshold".to_string(),
+            title: "Earthquake M6.1".to_string(),
+            body: "20 km SW of Somewhere".to_string(),
+            latitude: Some(35.5),
+            longitude: Some(139.25),
+            suppressed: false,
+            held: false,
+            severity: None,
+            acknowledged_at: None,
+            created_at: 1_700_000_000,
+        }
+    }
+
+    fn channel(config: ChannelConfig) -> AlertChannel {
+        AlertChannel {
+            id: 1,
+            name: "test".to_string(),
+            config,
+            template: None,
+            enabled: true,
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn renders_placeholders() {
+        let text = render_template("[{source}] {title} at {location}, {time}", &record());
+        assert_eq!(
+            text,
+            "[earthquake] Earthquake M6.1 at 35.500, 139.250, 2023-11-14 22:13 UTC"
+        );
+    }
+
+    #[test]
+    fn builds_service_payloads() {
+        let rec = record();
+        let slack = http_request(
+            &ChannelConfig::Slack {
+                webhook_url: "https://hooks.slack.test/x".to_string(),
+            },
+            &rec,
+            "hello",
+        )
+        .unwrap();
+        assert_eq!(slack.body, r#"{"text":"hello"}"#);
+
+        let ntfy = http_request(
+            &ChannelConfig::Ntfy {
+                server: "https://ntfy.test/".to_string(),
+                topic: "quakes".to_string(),
+                token: Some("tk".to_string()),
+            },
+            &rec,
+            "hello",
+        )
+        .unwrap();
+        assert_eq!(ntfy.url, "https://ntfy.test/quakes");
+        assert!(ntfy
+            .headers
+            .contains(&("Authorization", "Bearer tk".to_string())));
+        assert_eq!(ntfy.body, "hello");
+
+        let webhook = http_request(
+            &ChannelConfig::Webhook {
+                url: "http://localhost/hook".to_string(),
+            },
+            &rec,
+            "hello",
+        )
+        .unwrap();
+        let value: serde_json::Value = serde_json::from_str(&webhook.body).unwrap();
+        assert_eq!(value["event_id"], "us7000abcd");
+        assert_eq!(value["text"], "hello");
+    }
+
+    /// Answers each connection with the next status, recording request bodies.
+    async fn http_stub(statuses: Vec<u16>) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut bodies = Vec::new();
+            for status in statuses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0u8; 8192];
+                let mut request = String::new();
+                // Read until the whole body has arrived
+                loop {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    request.push_str(&String::from_utf8_lossy(&buf[..n]));
+                    if let Some((head, body)) = request.split_once("\r\n\r\n") {
+                        let length = head
+                            .lines()
+                            .find_map(|l| {
+                                l.to_ascii_lowercase()
+                                    .strip_prefix("content-length:")
+                                    .map(|v| v.trim().parse::<usize>().unwrap())
+                            })
+                            .unwrap_or(0);
+                        if body.len() >= length {
+                            bodies.push(body.to_string());
+                            break;
+                        }
+                    }
+                }
+                let response = format!(
+                    "HTTP/1.1 {} Stub\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            }
+            bodies
+        });
+        (url, server)
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors() {
+        let (url, server) = http_stub(vec![503, 200]).await;
+        let ch = channel(ChannelConfig::Discord { webhook_url: url });
+        deliver_with_retry(&ch, &record(), &[Duration::ZERO, Duration::ZERO])
+            .await
+            .unwrap();
+        let bodies = server.await.unwrap();
+        assert_eq!(bodies.len(), 2);
+        assert_eq!(
+            bodies[1],
+            r#"{"content":"Earthquake M6.1\n20 km SW of Somewhere"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn client_errors_are_not_retried() {
+        let (url, server) = http_stub(vec![404]).await;
+        let ch = channel(ChannelConfig::Webhook { url });
+        let err = deliver_with_retry(&ch, &record(), &[Duration::ZERO])
+            .await
+            .unwrap_err();
+        assert!(err.ends_with("returned 404 Not Found"), "{}", err);
+        server.await.unwrap();
+    }
+}
@@ -0,0 +1,385 @@
+//! Minimal SMTP client for plain-text alert emails, over implicit TLS,
+//! STARTTLS, or plain SMTP for a relay such as a local MTA.
+
+use crate::models::channel::SmtpSecurity;
+use std::sync::{Arc, LazyLock};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::time::{timeout, Duration};
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{self, ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+static TLS_CONFIG: LazyLock<Arc<ClientConfig>> = LazyLock::new(|| {
+    let roots = RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    let config =
+        ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .expect("Failed to configure TLS")
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+    Arc::new(config)
+});
+
+trait Stream: AsyncRead + AsyncWrite + Unpin + Send {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Stream for T {}
+
+type Connection = BufReader<Box<dyn Stream>>;
+
+pub struct Message<'a> {
+    pub from: &'a str,
+    pub to: &'a [String],
+    pub subject: &'a str,
+    pub body: &'a str,
+}
+
+#[derive(Debug)]
+pub enum SmtpError {
+    /// Connection problems and 4xx replies
+    Transient(String),
+    /// 5xx replies
+    Permanent(String),
+}
+
+pub fn is_loopback(host: &str) -> bool {
+    host == "localhost"
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// The security used when a channel doesn't name one.
+pub fn default_security(host: &str, port: u16) -> SmtpSecurity {
+    if port == 465 {
+        SmtpSecurity::Tls
+    } else if is_loopback(host) {
+        SmtpSecurity::None
+    } else {
+        SmtpSecurity::StartTls
+    }
+}
+
+pub async fn send(
+    host: &str,
+    port: u16,
+    security: SmtpSecurity,
+    credentials: Option<(&str, &str)>,
+    message: &Message<'_>,
+) -> Result<(), SmtpError> {
+    match timeout(TIMEOUT, session(host, port, security, credentials, message)).await {
+        Ok(result) => result,
+        Err(_) => Err(SmtpError::Transient(format!(
+            "SMTP session with {} timed out",
+            host
+        ))),
+    }
+}
+
+async fn session(
+    host: &str,
+    port: u16,
+    security: SmtpSecurity,
+    credentials: Option<(&str, &str)>,
+    message: &Message<'_>,
+) -> Result<(), SmtpError> {
+    let stream = TcpStream::connect((host, port))
+        .await
+        .map_err(|e| SmtpError::Transient(format!("Failed to connect to {}: {}", host, e)))?;
+    let mut conn: Connection = match security {
+        SmtpSecurity::Tls => BufReader::new(tls(host, stream).await?),
+        SmtpSecurity::StartTls | SmtpSecurity::None => BufReader::new(Box::new(stream)),
+    };
+
+    expect(&mut conn, 220).await?;
+    let extensions = command(&mut conn, "EHLO earthpulse", 250).await?;
+
+    if security == SmtpSecurity::StartTls {
+        if !extensions
+            .iter()
+            .any(|e| e.eq_ignore_ascii_case("STARTTLS"))
+        {
+            return Err(SmtpError::Permanent(format!(
+                "{} does not offer STARTTLS",
+                host
+            )));
+        }
+        command(&mut conn, "STARTTLS", 220).await?;
+        conn = BufReader::new(tls(host, conn.into_inner()).await?);
+        command(&mut conn, "EHLO earthpulse", 250).await?;
+    }
+
+    if let Some((user, password)) = credentials {
+        if security == SmtpSecurity::None && !is_loopback(host) {
+            return Err(SmtpError::Permanent(
+                "Refusing to send SMTP credentials without TLS".to_string(),
+            ));
+        }
+        let token = base64(format!("\0{}\0{}", user, password).as_bytes());
+        command(&mut conn, &format!("AUTH PLAIN {}", token), 235).await?;
+    }
+
+    command(&mut conn, &format!("MAIL FROM:<{}>", message.from), 250).await?;
+    for to in message.to {
+        command(&mut conn, &format!("RCPT TO:<{}>", to), 250).await?;
+    }
+    command(&mut conn, "DATA", 354).await?;
+    write(&mut conn, &format_message(message)).await?;
+    expect(&mut conn, 250).await?;
+    // The message is accepted; a failed QUIT doesn't matter
+    command(&mut conn, "QUIT", 221).await.ok();
+    Ok(())
+}
+
+/// TLS handshake over `stream`, verifying the certificate against `host`.
+async fn tls<S>(host: &str, stream: S) -> Result<Box<dyn Stream>, SmtpError>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let name = ServerName::try_from(host.to_string())
+        .map_err(|_| SmtpError::Permanent(format!("'{}' is not a valid TLS server name", host)))?;
+    let stream = TlsConnector::from(TLS_CONFIG.clone())
+        .connect(name, stream)
+        .await
+        .map_err(|e| SmtpError::Transient(format!("TLS handshake with {} failed: {}", host, e)))?;
+    Ok(Box::new(stream))
+}
+
+async fn write(conn: &mut Connection, data: &str) -> Result<(), SmtpError> {
+    conn.write_all(data.as_bytes())
+        .await
+        .and(conn.flush().await)
+        .map_err(|e| SmtpError::Transient(format!("SMTP write failed: {}", e)))
+}
+
+async fn command(conn: &mut Connection, line: &str, code: u16) -> Result<Vec<String>, SmtpError> {
+    write(conn, &format!("{}\r\n", line)).await?;
+    expect(conn, code).await
+}
+
+/// Read a possibly multi-line reply and check its code. Returns the reply
+/// text after the greeting line, which for EHLO lists the extensions.
+async fn expect(conn: &mut Connection, code: u16) -> Result<Vec<String>, SmtpError> {
+    let mut lines = Vec::new();
+    loop {
+        let mut line = String::new();
+        let n = conn
+            .read_line(&mut line)
+            .await
+            .map_err(|e| SmtpError::Transient(format!("SMTP read failed: {}", e)))?;
+        if n == 0 {
+            return Err(SmtpError::Transient(
+                "SMTP server closed the connection".to_string(),
+            ));
+        }
+        let reply: u16 = line.get(..3).and_then(|c| c.parse().ok()).unwrap_or(0);
+        lines.push(line.get(4..).unwrap_or_default().trim().to_string());
+        // "250-" continues a multi-line reply, "250 " ends it
+        if line.as_bytes().get(3) == Some(&b'-') {
+            continue;
+        }
+        return match reply {
+            r if r == code => Ok(lines.split_off(1)),
+            400..=499 => Err(SmtpError::Transient(format!("SMTP error: {}", line.trim()))),
+            _ => Err(SmtpError::Permanent(format!("SMTP error: {}", line.trim()))),
+        };
+    }
+}
+
+/// Headers and dot-stuffed body, terminated by the end-of-data marker.
+fn format_message(message: &Message) -> String {
+    // Alert titles come from feeds; a line break would start a new header
+    let subject = message.subject.replace(['\r', '\n'], " ");
+    let subject = if subject.is_ascii() {
+        subject
+    } else {
+        format!("=?UTF-8?B?{}?=", base64(subject.as_bytes()))
+    };
+    let mut out = format!(
+        "From: <{}>\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nMIME-Version: 1.0\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: 8bit\r\n\r\n",
+        message.from,
+        message
+            .to
+            .iter()
+            .map(|t| format!("<{}>", t))
+            .collect::<Vec<_>>()
+            .join(", "),
+        subject,
+        chrono::Utc::now().to_rfc2822(),
+    );
+    for line in message.body.lines() {
+        if line.starts_with('.') {
+            out.push('.');
+        }
+        out.push_str(line);
+        out.push_str("\r\n");
+    }
+    out.push_str(".\r\n");
+    out
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn base64_matches_rfc4648_vectors() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn subject_line_breaks_cannot_add_headers() {
+        let to = vec!["ops@example.org".to_string()];
+        let message = Message {
+            from: "alerts@example.org",
+            to: &to,
+            subject: "Flood warning\r\nBcc: someone@example.com",
+            body: "Body",
+        };
+        let formatted = format_message(&message);
+        assert!(formatted.contains("Subject: Flood warning  Bcc: someone@example.com\r\n"));
+        assert!(!formatted.contains("\r\nBcc:"));
+    }
+
+    /// Scripted SMTP server returning everything the client sent.
+    async fn smtp_stub(rcpt_reply: &'static str) -> (u16, tokio::task::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut reader = BufReader::new(reader);
+            let mut transcript = String::new();
+            writer.write_all(b"220 stub ready\r\n").await.unwrap();
+            let mut in_data = false;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).await.unwrap() == 0 {
+                    break;
+                }
+                transcript.push_str(&line);
+                let reply: &[u8] = if in_data {
+                    if line != ".\r\n" {
+                        continue;
+                    }
+                    in_data = false;
+                    b"250 queued\r\n"
+                } else if line.starts_with("EHLO") {
+                    b"250-stub\r\n250 AUTH PLAIN\r\n"
+                } else if line.starts_with("AUTH") {
+                    b"235 ok\r\n"
+                } else if line.starts_with("RCPT") {
+                    rcpt_reply.as_bytes()
+                } else if line.starts_with("DATA") {
+                    in_data = true;
+                    b"354 go ahead\r\n"
+                } else if line.starts_with("QUIT") {
+                    writer.write_all(b"221 bye\r\n").await.unwrap();
+                    break;
+                } else {
+                    b"250 ok\r\n"
+                };
+                writer.write_all(reply).await.unwrap();
+            }
+            transcript
+        });
+        (port, server)
+    }
+
+    #[tokio::test]
+    async fn sends_message_to_stub() {
+        let (port, server) = smtp_stub("250 ok\r\n").await;
+        let to = vec!["ops@example.org".to_string()];
+        let message = Message {
+            from: "alerts@example.org",
+            to: &to,
+            subject: "Séisme M6.1",
+            body: "Line one\n.hidden dot",
+        };
+        send(
+            "127.0.0.1",
+            port,
+            SmtpSecurity::None,
+            Some(("user", "pw")),
+            &message,
+        )
+        .await
+        .unwrap();
+
+        let transcript = server.await.unwrap();
+        assert!(transcript.contains("AUTH PLAIN AHVzZXIAcHc=\r\n"));
+        assert!(transcript.contains("RCPT TO:<ops@example.org>\r\n"));
+        assert!(transcript.contains("Subject: =?UTF-8?B?U8OpaXNtZSBNNi4x?=\r\n"));
+        assert!(transcript.contains("\r\n..hidden dot\r\n.\r\n"));
+        assert!(transcript.ends_with("QUIT\r\n"));
+    }
+
+    #[tokio::test]
+    async fn rejected_recipient_is_permanent() {
+        let (port, _server) = smtp_stub("550 no such user\r\n").await;
+        let to = vec!["nobody@example.org".to_string()];
+        let message = Message {
+            from: "alerts@example.org",
+            to: &to,
+            subject: "Test",
+            body: "Test",
+        };
+        let err = send("127.0.0.1", port, SmtpSecurity::None, None, &message)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, SmtpError::Permanent(e) if e.contains("550")));
+    }
+
+    #[tokio::test]
+    async fn starttls_must_be_offered() {
+        let (port, server) = smtp_stub("250 ok\r\n").await;
+        let to = vec!["ops@example.org".to_string()];
+        let message = Message {
+            from: "alerts@example.org",
+            to: &to,
+            subject: "Test",
+            body: "Test",
+        };
+        let err = send(
+            "127.0.0.1",
+            port,
+            SmtpSecurity::StartTls,
+            Some(("user", "pw")),
+            &message,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, SmtpError::Permanent(e) if e.contains("STARTTLS")));
+        // Nothing, least of all the credentials, was sent after EHLO
+        assert_eq!(server.await.unwrap(), "EHLO earthpulse\r\n");
+    }
+}
@@ -1,6 +1,21 @@
 use super::http::HTTP_CLIENT;
-use crate::models::asteroid::{Asteroid, NeoResponse};
-use chrono::Utc;
+use crate::models::asteroid::{
+    Asteroid, AsteroidApproach, CadResponse, ImpactRisk, NeoObject, NeoResponse, OrbitClass,
+    SbdbResponse, SentryResponse,
+};
+use chrono::{NaiveDateTime, Utc};
+use std::collections::BTreeMap;
+
+const SENTRY_URL: &str = "https://ssd-api.jpl.nasa.gov/sentry.api";
+const SBDB_URL: &str = "https://ssd-api.jpl.nasa.gov/sbdb.api";
+const CAD_URL: &str = "https://ssd-api.jpl.nasa.gov/cad.api";
+
+const KM_PER_AU: f64 = 149_597_870.7;
+const KM_PER_LUNAR_DISTANCE: f64 = 384_400.0;
+
+/// Approaches of the same object this close in time are the same approach
+/// reported by both sources.
+const SAME_APPROACH_MS: i64 = 6 * 3600 * 1000;
 
 pub async fn fetch_asteroids() -> Result<Vec<Asteroid>, String> {
     let today = Utc::now().format("%Y-%m-%d").to_string();
@@ -24,40 +39,476 @@ pub async fn fetch_asteroids() -> Result<Vec<Asteroid>, String> {
         .await
         .map_err(|e| format!("Failed to parse asteroid data: {}", e))?;
 
-    let mut asteroids: Vec<Asteroid> = neo
-        .near_earth_objects
-        .values()
-        .flat_map(|objects| {
-            objects.iter().filter_map(|obj| {
-                let approach = obj.close_approach_data.first()?;
-                let velocity: f64 = approach
-                    .relative_velocity
-                    .kilometers_per_second
-                    .parse()
-                    .ok()?;
-                let miss_km: f64 = approach.miss_distance.kilometers.parse().ok()?;
-                let miss_lunar: f64 = approach.miss_distance.lunar.parse().ok()?;
-
-                if !velocity.is_finite() || !miss_km.is_finite() || !miss_lunar.is_finite() {
-                    return None;
-                }
-
-                Some(Asteroid {
-                    id: obj.id.clone(),
-                    name: obj.name.clone(),
-                    diameter_km_min: obj.estimated_diameter.kilometers.estimated_diameter_min,
-                    diameter_km_max: obj.estimated_diameter.kilometers.estimated_diameter_max,
-                    is_hazardous: obj.is_potentially_hazardous_asteroid,
-                    approach_date: approach.close_approach_date.clone(),
-                    approach_time: approach.epoch_date_close_approach,
-                    velocity_kps: velocity,
-                    miss_distance_km: miss_km,
-                    miss_distance_lunar: miss_lunar,
-                })
+    Ok(collect_asteroids(neo))
+}
+
+/// The feed lists an object under every day it approaches, so merge them by
+/// id and keep each approach once.
+fn collect_asteroids(neo: NeoResponse) -> Vec<Asteroid> {
+    let mut objects: BTreeMap<String, (NeoObject, Vec<AsteroidApproach>)> = BTreeMap::new();
+    for obj in neo.near_earth_objects.into_values().flatten() {
+        let approaches: Vec<AsteroidApproach> = obj
+            .close_approach_data
+            .iter()
+            .filter_map(to_approach)
+            .collect();
+        match objects.get_mut(&obj.id) {
+            Some((_, known)) => known.extend(approaches),
+            None => {
+                objects.insert(obj.id.clone(), (obj, approaches));
+            }
+        }
+    }
+
+    let mut asteroids: Vec<Asteroid> = objects
+        .into_values()
+        .filter_map(|(obj, mut approaches)| {
+            approaches.sort_by_key(|a| a.time);
+            approaches.dedup_by_key(|a| a.time);
+            let next = approaches.first()?.clone();
+            let (number, designation) = designations(&obj.name);
+            let designation = number.or(designation).unwrap_or_default().to_string();
+            Some(Asteroid {
+                id: obj.id,
+                name: obj.name,
+                diameter_km_min: obj.estimated_diameter.kilometers.estimated_diameter_min,
+                diameter_km_max: obj.estimated_diameter.kilometers.estimated_diameter_max,
+                is_hazardous: obj.is_potentially_hazardous_asteroid,
+                approach_date: next.date,
+                approach_time: next.time,
+                velocity_kps: next.velocity_kps,
+                miss_distance_km: next.miss_distance_km,
+                miss_distance_lunar: next.miss_distance_lunar,
+                orbiting_body: next.orbiting_body,
+                absolute_magnitude_h: obj.absolute_magnitude_h,
+                spk_id: obj.neo_reference_id,
+                designation,
+                orbit_class: None,
+                approaches,
+                impact_risk: None,
             })
         })
         .collect();
 
     asteroids.sort_by_key(|a| a.approach_time);
-    Ok(asteroids)
+    asteroids
+}
+
+fn to_approach(approach: &crate::models::asteroid::CloseApproach) -> Option<AsteroidApproach> {
+    let velocity: f64 = approach
+        .relative_velocity
+        .kilometers_per_second
+        .parse()
+        .ok()?;
+    let miss_km: f64 = approach.miss_distance.kilometers.parse().ok()?;
+    let miss_lunar: f64 = approach.miss_distance.lunar.parse().ok()?;
+
+    if !velocity.is_finite() || !miss_km.is_finite() || !miss_lunar.is_finite() {
+        return None;
+    }
+
+    Some(AsteroidApproach {
+        date: approach.close_approach_date.clone(),
+        time: approach.epoch_date_close_approach,
+        velocity_kps: velocity,
+        miss_distance_km: miss_km,
+        miss_distance_lunar: miss_lunar,
+        orbiting_body: approach.orbiting_body.clone(),
+        source: "neows".to_string(),
+    })
+}
+
+/// Earth approaches from the JPL close-approach API over the next `days`,
+/// closer than `max_distance_lunar`, grouped by object.
+pub async fn fetch_close_approaches(
+    days: u32,
+    max_distance_lunar: f64,
+) -> Result<Vec<Asteroid>, String> {
+    let date_max = format!("+{}", days);
+    let dist_max = format!("{}LD", max_distance_lunar);
+    let response = HTTP_CLIENT
+        .get(CAD_URL)
+        .query(&[
+            ("date-min", "now"),
+            ("date-max", date_max.as_str()),
+            ("dist-max", dist_max.as_str()),
+            ("diameter", "true"),
+            ("fullname", "true"),
+        ])
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch JPL close approaches: {}", e))?;
+
+    let cad: CadResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse JPL close approaches: {}", e))?;
+
+    Ok(collect_close_approaches(cad))
+}
+
+fn collect_close_approaches(cad: CadResponse) -> Vec<Asteroid> {
+    let column = |name: &str| cad.fields.iter().position(|f| f == name);
+    let (Some(des), Some(cd), Some(dist), Some(v_rel)) =
+        (column("des"), column("cd"), column("dist"), column("v_rel"))
+    else {
+        return Vec::new();
+    };
+    let (h, diameter, fullname) = (column("h"), column("diameter"), column("fullname"));
+
+    let mut objects: BTreeMap<String, Asteroid> = BTreeMap::new();
+    for row in &cad.data {
+        let value = |i: Option<usize>| {
+            i.and_then(|i| row.get(i))
+                .and_then(|v| v.as_deref())
+                .map(str::trim)
+        };
+        let number = |i: Option<usize>| value(i).and_then(|v| v.parse::<f64>().ok());
+        let (Some(designation), Some(time), Some(dist_au), Some(velocity)) = (
+            value(Some(des)),
+            value(Some(cd)).and_then(|t| NaiveDateTime::parse_from_str(t, "%Y-%b-%d %H:%M").ok()),
+            number(Some(dist)),
+            number(Some(v_rel)),
+        ) else {
+            continue;
+        };
+        let miss_km = dist_au * KM_PER_AU;
+        let approach = AsteroidApproach {
+            date: time.format("%Y-%m-%d").to_string(),
+            time: time.and_utc().timestamp_millis(),
+            velocity_kps: velocity,
+            miss_distance_km: miss_km,
+            miss_distance_lunar: miss_km / KM_PER_LUNAR_DISTANCE,
+            orbiting_body: "Earth".to_string(),
+            source: "jpl".to_string(),
+        };
+
+        let entry = objects.entry(designation.to_string()).or_insert_with(|| {
+            let h = number(h);
+            // Without a measured diameter, bound it by albedos 0.25 and 0.05 like NeoWs
+            let (diameter_km_min, diameter_km_max) = match (number(diameter), h) {
+                (Some(d), _) => (d, d),
+                (None, Some(h)) => (diameter_from_h(h, 0.25), diameter_from_h(h, 0.05)),
+                (None, None) => (0.0, 0.0),
+            };
+            let name = value(fullname)
+                .filter(|n| !n.is_empty())
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| format!("({})", designation));
+            Asteroid {
+                id: format!("jpl-{}", designation.replace(' ', "_")),
+                name,
+                diameter_km_min,
+                diameter_km_max,
+                // CAD has no orbit intersection distance to judge this by
+                is_hazardous: false,
+                approach_date: String::new(),
+                approach_time: 0,
+                velocity_kps: 0.0,
+                miss_distance_km: 0.0,
+                miss_distance_lunar: 0.0,
+                orbiting_body: String::new(),
+                absolute_magnitude_h: h,
+                spk_id: String::new(),
+                designation: designation.to_string(),
+                orbit_class: None,
+                approaches: Vec::new(),
+                impact_risk: None,
+            }
+        });
+        entry.approaches.push(approach);
+    }
+
+    objects
+        .into_values()
+        .map(|mut a| {
+            a.approaches.sort_by_key(|p| p.time);
+            set_next_approach(&mut a);
+            a
+        })
+        .collect()
+}
+
+/// Add JPL approaches to the NeoWs objects with the same designation, and
+/// JPL-only objects as new entries. NeoWs keeps precedence for approaches
+/// both sources report.
+pub fn merge_close_approaches(asteroids: &mut Vec<Asteroid>, jpl: Vec<Asteroid>) {
+    for object in jpl {
+        let Some(known) = asteroids
+            .iter_mut()
+            .find(|a| !a.designation.is_empty() && a.designation == object.designation)
+        else {
+            asteroids.push(object);
+            continue;
+        };
+        for approach in object.approaches {
+            if !known
+                .approaches
+                .iter()
+                .any(|p| (p.time - approach.time).abs() < SAME_APPROACH_MS)
+            {
+                known.approaches.push(approach);
+            }
+        }
+        known.approaches.sort_by_key(|p| p.time);
+        if known.absolute_magnitude_h.is_none() {
+            known.absolute_magnitude_h = object.absolute_magnitude_h;
+        }
+        set_next_approach(known);
+    }
+    asteroids.sort_by_key(|a| a.approach_time);
+}
+
+/// Copy the soonest approach into the top-level fields.
+fn set_next_approach(asteroid: &mut Asteroid) {
+    if let Some(next) = asteroid.approaches.first() {
+        asteroid.approach_date = next.date.clone();
+        asteroid.approach_time = next.time;
+        asteroid.velocity_kps = next.velocity_kps;
+        asteroid.miss_distance_km = next.miss_distance_km;
+        asteroid.miss_distance_lunar = next.miss_distance_lunar;
+        asteroid.orbiting_body = next.orbiting_body.clone();
+    }
+}
+
+/// Diameter in km for absolute magnitude `h` and geometric albedo `albedo`.
+fn diameter_from_h(h: f64, albedo: f64) -> f64 {
+    1329.0 / albedo.sqrt() * 10f64.powf(-h / 5.0)
+}
+
+/// Every object on the JPL Sentry impact-risk list.
+pub async fn fetch_impact_risks() -> Result<Vec<ImpactRisk>, String> {
+    let response = HTTP_CLIENT
+        .get(SENTRY_URL)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch Sentry risk table: {}", e))?;
+
+    let sentry: SentryResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse Sentry risk table: {}", e))?;
+
+    Ok(sentry
+        .data
+        .into_iter()
+        .filter_map(|o| {
+            Some(ImpactRisk {
+                impact_probability: o.ip.trim().parse().ok()?,
+                palermo_cumulative: o.ps_cum.trim().parse().ok()?,
+                palermo_max: o.ps_max.trim().parse().ok()?,
+                torino_max: o
+                    .ts_max
+                    .as_deref()
+                    .and_then(|t| t.trim().parse().ok())
+                    .unwrap_or(0),
+                potential_impacts: match &o.n_imp {
+                    serde_json::Value::Number(n) => n.as_u64().unwrap_or(0) as u32,
+                    serde_json::Value::String(s) => s.trim().parse().unwrap_or(0),
+                    _ => 0,
+                },
+                years: o.range,
+                designation: o.des.trim().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Orbit class of one object from the JPL Small-Body Database, by SPK-ID
+/// when known, which is unambiguous, else by designation.
+pub async fn fetch_orbit_class(spk_id: &str, designation: &str) -> Result<OrbitClass, String> {
+    let query = if spk_id.is_empty() {
+        ("sstr", designation)
+    } else {
+        ("spk", spk_id)
+    };
+    let response = HTTP_CLIENT
+        .get(SBDB_URL)
+        .query(&[query])
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch SBDB record: {}", e))?;
+
+    let sbdb: SbdbResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse SBDB record: {}", e))?;
+
+    Ok(sbdb.object.orbit_class)
+}
+
+/// Attach Sentry entries. NeoWs names look like "465633 (2009 JR5)" or
+/// "(2024 AB1)"; Sentry uses the number for numbered objects and the
+/// provisional designation otherwise.
+pub fn join_impact_risks(asteroids: &mut [Asteroid], risks: &[ImpactRisk]) {
+    for asteroid in asteroids.iter_mut() {
+        let (number, designation) = designations(&asteroid.name);
+        asteroid.impact_risk = risks
+            .iter()
+            .find(|r| {
+                Some(r.designation.as_str()) == number
+                    || Some(r.designation.as_str()) == designation
+            })
+            .cloned();
+    }
+}
+
+fn designations(name: &str) -> (Option<&str>, Option<&str>) {
+    let name = name.trim();
+    let designation = name
+        .split_once('(')
+        .and_then(|(_, rest)| rest.split_once(')'))
+        .map(|(d, _)| d.trim())
+        .filter(|d| !d.is_empty());
+    let number = name
+        .split_whitespace()
+        .next()
+        .filter(|n| n.chars().all(|c| c.is_ascii_digit()));
+    (number, designation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approach(date: &str, epoch: i64, lunar: &str) -> serde_json::Value {
+        serde_json::json!({
+            "close_approach_date": date,
+            "epoch_date_close_approach": epoch,
+            "relative_velocity": {"kilometers_per_second": "12.5"},
+            "miss_distance": {"kilometers": "1000000", "lunar": lunar},
+            "orbiting_body": "Earth"
+        })
+    }
+
+    #[test]
+    fn merges_days_and_keeps_every_approach() {
+        let object = |approaches: Vec<serde_json::Value>| {
+            serde_json::json!({
+                "id": "3542519", "neo_reference_id": "3542519", "name": "(2010 PK9)",
+                "absolute_magnitude_h": 21.5,
+                "estimated_diameter": {"kilometers": {
+                    "estimated_diameter_min": 0.1, "estimated_diameter_max": 0.3}},
+                "is_potentially_hazardous_asteroid": true,
+                "close_approach_data": approaches
+            })
+        };
+        let neo: NeoResponse = serde_json::from_value(serde_json::json!({
+            "near_earth_objects": {
+                "2024-06-03": [object(vec![approach("2024-06-03", 2000, "2.6")])],
+                "2024-06-01": [object(vec![
+                    approach("2024-06-01", 1000, "5.1"),
+                    approach("2024-06-03", 2000, "2.6")
+                ])]
+            }
+        }))
+        .unwrap();
+
+        let asteroids = collect_asteroids(neo);
+        assert_eq!(asteroids.len(), 1);
+        let a = &asteroids[0];
+        assert_eq!(a.approaches.len(), 2);
+        assert_eq!(a.approach_time, 1000);
+        assert_eq!(a.miss_distance_lunar, 5.1);
+        assert_eq!(a.orbiting_body, "Earth");
+        assert_eq!(a.absolute_magnitude_h, Some(21.5));
+        assert_eq!(a.spk_id, "3542519");
+    }
+
+    #[test]
+    fn joins_sentry_by_number_or_designation() {
+        let risk = |des: &str| ImpactRisk {
+            designation: des.to_string(),
+            impact_probability: 1e-4,
+            palermo_cumulative: -3.0,
+            palermo_max: -3.2,
+            torino_max: 0,
+            potential_impacts: 12,
+            years: "2060-2100".to_string(),
+        };
+        let risks = vec![risk("101955"), risk("2010 PK9")];
+        assert_eq!(
+            designations("101955 Bennu (1999 RQ36)"),
+            (Some("101955"), Some("1999 RQ36"))
+        );
+
+        let neo: NeoResponse = serde_json::from_value(serde_json::json!({
+            "near_earth_objects": {"2024-06-01": [
+                {"id": "1", "neo_reference_id": "2101955", "name": "101955 Bennu (1999 RQ36)",
+                 "absolute_magnitude_h": 20.2,
+                 "estimated_diameter": {"kilometers": {
+                     "estimated_diameter_min": 0.4, "estimated_diameter_max": 0.5}},
+                 "is_potentially_hazardous_asteroid": true,
+                 "close_approach_data": [approach("2024-06-01", 1000, "30")]},
+                {"id": "2", "neo_reference_id": "3000002", "name": "(2024 AA)",
+                 "absolute_magnitude_h": 25.0,
+                 "estimated_diameter": {"kilometers": {
+                     "estimated_diameter_min": 0.01, "estimated_diameter_max": 0.03}},
+                 "is_potentially_hazardous_asteroid": false,
+                 "close_approach_data": [approach("2024-06-02", 2000, "3")]}
+            ]}
+        }))
+        .unwrap();
+        let mut asteroids = collect_asteroids(neo);
+        join_impact_risks(&mut asteroids, &risks);
+        assert!(asteroids[0].impact_risk.is_some());
+        assert!(asteroids[1].impact_risk.is_none());
+    }
+
+    #[test]
+    fn merges_jpl_approaches_by_designation() {
+        let cad: CadResponse = serde_json::from_value(serde_json::json!({
+            "fields": ["des", "orbit_id", "jd", "cd", "dist", "dist_min", "dist_max",
+                       "v_rel", "v_inf", "t_sigma_f", "h", "diameter", "diameter_sigma",
+                       "fullname"],
+            "data": [
+                ["2010 PK9", "12", "2460463.5", "1970-Jan-01 00:00", "0.00674", "0.0067",
+                 "0.0068", "12.1", "12.0", "< 00:01", "21.5", null, null, "       (2010 PK9)"],
+                ["2010 PK9", "12", "2460503.5", "1970-Feb-10 06:30", "0.0300", "0.0299",
+                 "0.0301", "9.8", "9.7", "< 00:01", "21.5", null, null, "       (2010 PK9)"],
+                ["2024 ZZ", "3", "2460470.5", "1970-Jan-05 12:00", "0.0020", "0.0019",
+                 "0.0021", "7.0", "6.9", "00:05", "27.3", "0.012", null, "       (2024 ZZ)"]
+            ]
+        }))
+        .unwrap();
+        let jpl = collect_close_approaches(cad);
+        assert_eq!(jpl.len(), 2);
+        let zz = jpl.iter().find(|a| a.designation == "2024 ZZ").unwrap();
+        assert_eq!(zz.name, "(2024 ZZ)");
+        assert_eq!(zz.diameter_km_max, 0.012);
+        assert!((zz.miss_distance_lunar - 0.778).abs() < 0.01);
+
+        let neo: NeoResponse = serde_json::from_value(serde_json::json!({
+            "near_earth_objects": {"1970-01-01": [{
+                "id": "3542519", "neo_reference_id": "3542519", "name": "(2010 PK9)",
+                "absolute_magnitude_h": 21.5,
+                "estimated_diameter": {"kilometers": {
+                    "estimated_diameter_min": 0.1, "estimated_diameter_max": 0.3}},
+                "is_potentially_hazardous_asteroid": true,
+                // Same approach as the first JPL row, a few minutes apart
+                "close_approach_data": [approach("1970-01-01", 600_000, "2.6")]
+            }]}
+        }))
+        .unwrap();
+        let mut asteroids = collect_asteroids(neo);
+        merge_close_approaches(&mut asteroids, jpl);
+
+        assert_eq!(asteroids.len(), 2);
+        let pk9 = asteroids
+            .iter()
+            .find(|a| a.designation == "2010 PK9")
+            .unwrap();
+        assert_eq!(pk9.id, "3542519");
+        assert_eq!(pk9.approaches.len(), 2);
+        assert_eq!(pk9.approaches[0].source, "neows");
+        assert_eq!(pk9.approaches[1].source, "jpl");
+        assert_eq!(pk9.miss_distance_lunar, 2.6);
+        assert!(asteroids.iter().any(|a| a.id == "jpl-2024_ZZ"));
+    }
+
+    #[test]
+    fn estimates_diameter_from_h() {
+        // H 22 is the usual ~140 m PHA size cut at albedo 0.14
+        assert!((diameter_from_h(22.0, 0.14) - 0.141).abs() < 0.005);
+    }
 }
@@ -0,0 +1,128 @@
+use super::http::HTTP_CLIENT;
+use crate::models::satellite::DecayPrediction;
+use chrono::NaiveDateTime;
+use serde_json::Value;
+
+/// Load reentry predictions from a URL or a local file. Accepts Space-Track
+/// style JSON arrays (TIP or decay classes), where values may be strings or numbers.
+pub async fn fetch_decay_predictions(source: &str) -> Result<Vec<DecayPrediction>, String> {
+    let text = if source.starts_with("http://") || source.starts_with("https://") {
+        let response = HTTP_CLIENT
+            .get(source)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch decay predictions: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!(
+                "Decay prediction request failed with status {}",
+                response.status()
+            ));
+        }
+
+        response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read decay predictions: {}", e))?
+    } else {
+        tokio::fs::read_to_string(source)
+            .await
+            .map_err(|e| format!("Failed to read decay prediction file: {}", e))?
+    };
+
+    parse_decay_predictions(&text)
+}
+
+pub fn parse_decay_predictions(text: &str) -> Result<Vec<DecayPrediction>, String> {
+    let rows: Vec<Value> = serde_json::from_str(text)
+        .map_err(|e| format!("Failed to parse decay predictions: {}", e))?;
+
+    let predictions = rows
+        .iter()
+        .filter_map(|row| {
+            let norad_id = field_str(row, "NORAD_CAT_ID")?;
+            let decay_epoch = field_str(row, "DECAY_EPOCH").and_then(|s| parse_epoch(&s))?;
+            let window_mins = field_f64(row, "WINDOW");
+
+            Some(DecayPrediction {
+                norad_id,
+                name: field_str(row, "OBJECT_NAME"),
+                // TIP messages carry a window; decay messages do not
+                source: if window_mins.is_some() {
+                    "tip"
+                } else {
+                    "decay"
+                }
+                .to_string(),
+                decay_epoch,
+                window_mins,
+                message_epoch: field_str(row, "MSG_EPOCH").and_then(|s| parse_epoch(&s)),
+                latitude: field_f64(row, "LAT"),
+                longitude: field_f64(row, "LON"),
+                inclination: field_f64(row, "INCL"),
+            })
+        })
+        .collect();
+
+    Ok(predictions)
+}
+
+fn field_str(row: &Value, key: &str) -> Option<String> {
+    match row.get(key)? {
+        Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn field_f64(row: &Value, key: &str) -> Option<f64> {
+    let value = match row.get(key)? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }?;
+    value.is_finite().then_some(value)
+}
+
+fn parse_epoch(s: &str) -> Option<i64> {
+    for fmt in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(dt) = NaiveDateTime::parse_from_str(s, fmt) {
+            return Some(dt.and_utc().timestamp());
+        }
+    }
+    chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(12, 0, 0))
+        .map(|dt| dt.and_utc().timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tip_and_decay_rows() {
+        let predictions =
+            parse_decay_predictions(include_str!("../../fixtures/decay/tip.json")).unwrap();
+        assert_eq!(predictions.len(), 2); // rows without an id or a valid epoch are skipped
+
+        let tip = &predictions[0];
+        assert_eq!(tip.norad_id, "58229");
+        assert_eq!(tip.name.as_deref(), Some("CZ-5B R/B"));
+        assert_eq!(tip.source, "tip");
+        assert_eq!(tip.decay_epoch, 1_709_389_860); // 2024-03-02 14:31 UTC
+        assert_eq!(tip.window_mins, Some(120.0));
+        assert_eq!(tip.message_epoch, Some(1_709_287_200));
+        assert_eq!(tip.latitude, Some(-12.4));
+        assert_eq!(tip.longitude, Some(133.9));
+
+        // Numeric ids and date-only epochs (taken as noon UTC)
+        let decay = &predictions[1];
+        assert_eq!(decay.norad_id, "49044");
+        assert_eq!(decay.source, "decay");
+        assert_eq!(decay.decay_epoch, 1_709_640_000);
+        assert_eq!(decay.window_mins, None);
+
+        assert!(parse_decay_predictions("{}").is_err());
+    }
+}
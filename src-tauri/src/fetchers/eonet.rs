@@ -1,11 +1,57 @@
 use super::http::HTTP_CLIENT;
-use crate::models::eonet::{EonetResponse, NaturalEvent};
+use crate::calculations::{gazetteer, geometry};
+use crate::models::eonet::{
+    EonetCategoriesResponse, EonetCategoryInfo, EonetEvent, EonetGeometry, EonetResponse,
+    EventGeometry, NaturalEvent,
+};
+use crate::models::watchlist::WatchShape;
 
-const EONET_URL: &str = "https://eonet.gsfc.nasa.gov/api/v3/events?status=open&limit=50";
+const EONET_URL: &str = "https://eonet.gsfc.nasa.gov/api/v3/events";
+const EONET_CATEGORIES_URL: &str = "https://eonet.gsfc.nasa.gov/api/v3/categories";
 
+/// Categories shown until the user picks their own in settings.
+pub const DEFAULT_CATEGORIES: [&str; 4] = ["wildfires", "severeStorms", "floods", "volcanoes"];
+
+/// All open events in every category.
 pub async fn fetch_eonet_events() -> Result<Vec<NaturalEvent>, String> {
+    fetch_events(&[("status", "open")]).await
+}
+
+/// Closed events with a date between `start` and `end` (YYYY-MM-DD); EONET
+/// filters on the event's observations, not on when it closed. An empty
+/// category list means every category.
+pub async fn fetch_closed_eonet_events(
+    start: &str,
+    end: &str,
+    categories: &[String],
+) -> Result<Vec<NaturalEvent>, String> {
+    let categories = categories.join(",");
+    let mut query = vec![("status", "closed"), ("start", start), ("end", end)];
+    if !categories.is_empty() {
+        query.push(("category", categories.as_str()));
+    }
+    fetch_events(&query).await
+}
+
+pub async fn fetch_eonet_categories() -> Result<Vec<EonetCategoryInfo>, String> {
+    let response = HTTP_CLIENT
+        .get(EONET_CATEGORIES_URL)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch EONET categories: {}", e))?;
+
+    let categories: EonetCategoriesResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse EONET categories: {}", e))?;
+
+    Ok(categories.categories)
+}
+
+async fn fetch_events(query: &[(&str, &str)]) -> Result<Vec<NaturalEvent>, String> {
     let response = HTTP_CLIENT
         .get(EONET_URL)
+        .query(query)
         .send()
         .await
         .map_err(|e| format!("Failed to fetch EONET events: {}", e))?;
@@ -15,42 +61,107 @@ pub async fn fetch_eonet_events() -> Result<Vec<NaturalEvent>, String> {
         .await
         .map_err(|e| format!("Failed to parse EONET data: {}", e))?;
 
-    let events: Vec<NaturalEvent> = eonet
+    Ok(eonet
         .events
         .into_iter()
-        .filter_map(|event| {
-            let category = event.categories.first()?;
-            // Filter to wildfires, severe storms, volcanoes, floods
-            let dominated = matches!(
-                category.id.as_str(),
-                "wildfires" | "severeStorms" | "floods" | "volcanoes"
-            );
-            if !dominated {
-                return None;
-            }
+        .filter_map(to_natural_event)
+        .collect())
+}
 
-            let geom = event.geometry.last()?;
-            if geom.coordinates.len() < 2 {
-                return None;
-            }
+fn to_natural_event(event: EonetEvent) -> Option<NaturalEvent> {
+    let category = event.categories.first()?;
+    let track: Vec<EventGeometry> = event.geometry.iter().filter_map(to_geometry).collect();
+    let latest = track.last()?;
 
-            let lon = geom.coordinates[0];
-            let lat = geom.coordinates[1];
-            if !lon.is_finite() || !lat.is_finite() {
+    Some(NaturalEvent {
+        category: category.title.clone(),
+        category_id: category.id.clone(),
+        latitude: latest.latitude,
+        longitude: latest.longitude,
+        date: latest.date.clone(),
+        place: gazetteer::describe(latest.latitude, latest.longitude).map(|label| label.label),
+        magnitude_value: latest.magnitude_value,
+        magnitude_unit: latest.magnitude_unit.clone(),
+        id: event.id,
+        title: event.title,
+        description: event.description.filter(|d| !d.trim().is_empty()),
+        closed: event.closed,
+        track,
+    })
+}
+
+fn to_geometry(geom: &EonetGeometry) -> Option<EventGeometry> {
+    let (latitude, longitude, shape) = match geom.geom_type.as_str() {
+        "Point" => {
+            let [lon, lat]: [f64; 2] = serde_json::from_value(geom.coordinates.clone()).ok()?;
+            (lat, lon, None)
+        }
+        "Polygon" => {
+            let rings: Vec<Vec<[f64; 2]>> =
+                serde_json::from_value(geom.coordinates.clone()).ok()?;
+            if rings.first().is_none_or(|ring| ring.len() < 4) {
                 return None;
             }
+            let shape = WatchShape::Polygon { rings };
+            let (lat, lon, _) = geometry::center_and_radius(&shape);
+            (lat, lon, Some(shape))
+        }
+        _ => return None,
+    };
+    if !latitude.is_finite() || !longitude.is_finite() {
+        return None;
+    }
+
+    Some(EventGeometry {
+        date: geom.date.clone(),
+        latitude,
+        longitude,
+        magnitude_value: geom.magnitude_value,
+        magnitude_unit: geom.magnitude_unit.clone(),
+        shape,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_track_polygons_and_magnitudes() {
+        let json = r#"{"events": [
+            {"id": "EONET_1", "title": "Tropical Storm Ana", "description": null, "closed": null,
+             "categories": [{"id": "severeStorms", "title": "Severe Storms"}],
+             "geometry": [
+                {"date": "2024-06-01T00:00:00Z", "type": "Point", "coordinates": [-60.0, 15.0],
+                 "magnitudeValue": 35, "magnitudeUnit": "kts"},
+                {"date": "2024-06-01T06:00:00Z", "type": "Point", "coordinates": [-61.5, 15.8],
+                 "magnitudeValue": 45, "magnitudeUnit": "kts"}
+             ]},
+            {"id": "EONET_2", "title": "Sea ice floe", "closed": "2024-05-20T00:00:00Z",
+             "categories": [{"id": "seaLakeIce", "title": "Sea and Lake Ice"}],
+             "geometry": [
+                {"date": "2024-05-01T00:00:00Z", "type": "Polygon",
+                 "coordinates": [[[-50, 70], [-48, 70], [-48, 72], [-50, 72], [-50, 70]]]}
+             ]}
+        ]}"#;
+        let response: EonetResponse = serde_json::from_str(json).unwrap();
+        let events: Vec<NaturalEvent> = response
+            .events
+            .into_iter()
+            .filter_map(to_natural_event)
+            .collect();
+        assert_eq!(events.len(), 2);
+
+        let storm = &events[0];
+        assert_eq!(storm.track.len(), 2);
+        assert_eq!((storm.latitude, storm.longitude), (15.8, -61.5));
+        assert_eq!(storm.magnitude_value, Some(45.0));
+        assert_eq!(storm.magnitude_unit.as_deref(), Some("kts"));
 
-            Some(NaturalEvent {
-                id: event.id,
-                title: event.title,
-                category: category.title.clone(),
-                category_id: category.id.clone(),
-                longitude: lon,
-                latitude: lat,
-                date: geom.date.clone(),
-            })
-        })
-        .collect();
-
-    Ok(events)
+        let ice = &events[1];
+        assert_eq!(ice.category_id, "seaLakeIce");
+        assert!(ice.closed.is_some());
+        assert!(ice.track[0].shape.is_some());
+        assert!((ice.latitude - 71.0).abs() < 1.0);
+    }
 }
@@ -0,0 +1,552 @@
+//! Streaming parser for RSS 2.0, Atom and CAP 1.2 feeds. Elements are matched
+//! by namespace URI rather than prefix, CDATA is kept, and every format is
+//! mapped into the same `FeedItem` so alert feeds can share one parser.
+
+use crate::models::watchlist::WatchShape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::name::ResolveResult;
+use quick_xml::NsReader;
+
+/// Namespace URIs of the vocabularies the app reads.
+pub mod ns {
+    pub const ATOM: &str = "http://www.w3.org/2005/Atom";
+    pub const CAP: &str = "urn:oasis:names:tc:emergency:cap:1.2";
+    pub const CAP_1_1: &str = "urn:oasis:names:tc:emergency:cap:1.1";
+    pub const GEO: &str = "http://www.w3.org/2003/01/geo/wgs84_pos#";
+    pub const GEORSS: &str = "http://www.georss.org/georss";
+    pub const GDACS: &str = "http://www.gdacs.org";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+    Cap,
+}
+
+#[derive(Debug, Clone)]
+pub struct Feed {
+    pub format: FeedFormat,
+    pub items: Vec<FeedItem>,
+}
+
+/// An RSS item, Atom entry or CAP `<info>` block.
+#[derive(Debug, Clone, Default)]
+pub struct FeedItem {
+    pub id: String,                // guid, id or CAP identifier
+    pub title: String,             // title or CAP headline
+    pub summary: String,           // description, summary/content or CAP description
+    pub link: Option<String>,      // alternate link or CAP web
+    pub links: Vec<FeedLink>,      // every Atom-style <link href=...>
+    pub published: Option<String>, // pubDate, published or CAP sent
+    pub updated: Option<String>,
+    pub point: Option<(f64, f64)>, // (lat, lon) from georss:point or geo:lat/geo:long
+    pub areas: Vec<FeedArea>,      // CAP areas and georss polygons
+    pub fields: Vec<FeedField>,    // every element inside the item, for extensions
+}
+
+#[derive(Debug, Clone)]
+pub struct FeedLink {
+    pub href: String,
+    pub rel: Option<String>,
+    pub media_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FeedArea {
+    pub description: String,
+    pub shapes: Vec<WatchShape>,
+    pub geocodes: Vec<(String, String)>, // CAP (valueName, value), e.g. ("UGC", "AKZ171")
+}
+
+/// An element with its resolved namespace, attributes and text content.
+/// Text includes that of nested elements, with line breaks for `<br/>`.
+#[derive(Debug, Clone)]
+pub struct FeedField {
+    pub namespace: Option<String>,
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub text: String,
+}
+
+impl FeedItem {
+    /// Trimmed text of the first element `name` in namespace `ns`.
+    pub fn field(&self, ns: &str, name: &str) -> Option<&str> {
+        self.find(ns, name).map(|f| f.text.trim())
+    }
+
+    /// Attribute `key` of the first element `name` in namespace `ns`.
+    pub fn attribute(&self, ns: &str, name: &str, key: &str) -> Option<&str> {
+        self.find(ns, name)?
+            .attributes
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn find(&self, ns: &str, name: &str) -> Option<&FeedField> {
+        self.fields
+            .iter()
+            .find(|f| f.name == name && f.namespace.as_deref() == Some(ns))
+    }
+
+    /// Like `field`, for elements without a namespace (plain RSS 2.0).
+    fn plain(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|f| f.name == name && f.namespace.is_none())
+            .map(|f| f.text.trim())
+    }
+}
+
+/// An element still open while streaming.
+struct Open {
+    namespace: Option<String>,
+    name: String,
+    attributes: Vec<(String, String)>,
+    text: String,
+}
+
+pub fn parse_feed(xml: &str) -> Result<Feed, String> {
+    let mut reader = NsReader::from_str(xml);
+    let mut buf = Vec::new();
+
+    let mut format: Option<FeedFormat> = None;
+    let mut stack: Vec<Open> = Vec::new();
+    // Index in `stack` of the element that starts the current item
+    let mut item_depth: Option<usize> = None;
+    let mut fields: Vec<FeedField> = Vec::new();
+    let mut areas: Vec<FeedArea> = Vec::new();
+    // CAP fields outside <info> (identifier, sent) apply to every info block
+    let mut envelope: Vec<FeedField> = Vec::new();
+    let mut items = Vec::new();
+
+    loop {
+        buf.clear();
+        let (resolved, event) = reader
+            .read_resolved_event_into(&mut buf)
+            .map_err(|e| format!("XML parse error: {}", e))?;
+        let namespace = namespace_of(&resolved);
+        match event {
+            Event::Start(ref e) => {
+                let name = local_name(e);
+                if format.is_none() {
+                    format = Some(detect_format(namespace.as_deref(), &name)?);
+                }
+                if item_depth.is_none() && is_item(format, namespace.as_deref(), &name) {
+                    item_depth = Some(stack.len());
+                    fields.clear();
+                    areas.clear();
+                } else if item_depth.is_some() && is_cap(namespace.as_deref()) && name == "area" {
+                    areas.push(FeedArea::default());
+                }
+                stack.push(Open {
+                    namespace,
+                    name,
+                    attributes: attributes(e),
+                    text: String::new(),
+                });
+            }
+            Event::Empty(ref e) => {
+                let name = local_name(e);
+                if let Some(top) = stack.last_mut() {
+                    if name == "br" {
+                        top.text.push('\n');
+                    }
+                }
+                if item_depth.is_some() {
+                    fields.push(FeedField {
+                        namespace,
+                        name,
+                        attributes: attributes(e),
+                        text: String::new(),
+                    });
+                }
+            }
+            Event::Text(ref e) => {
+                if let Some(top) = stack.last_mut() {
+                    top.text.push_str(&e.unescape().unwrap_or_default());
+                }
+            }
+            Event::CData(ref e) => {
+                if let Some(top) = stack.last_mut() {
+                    top.text.push_str(&String::from_utf8_lossy(e.as_ref()));
+                }
+            }
+            Event::End(_) => {
+                let Some(open) = stack.pop() else {
+                    continue;
+                };
+                // Parents see the text of their children, e.g. XHTML summaries
+                if let Some(parent) = stack.last_mut() {
+                    parent.text.push_str(&open.text);
+                    if matches!(open.name.as_str(), "p" | "div" | "li") {
+                        parent.text.push('\n');
+                    }
+                }
+
+                if item_depth == Some(stack.len()) {
+                    item_depth = None;
+                    let mut item_fields = std::mem::take(&mut fields);
+                    item_fields.extend(envelope.iter().cloned());
+                    items.push(build_item(
+                        format.unwrap_or(FeedFormat::Rss),
+                        item_fields,
+                        std::mem::take(&mut areas),
+                    ));
+                    continue;
+                }
+
+                let field = FeedField {
+                    namespace: open.namespace,
+                    name: open.name,
+                    attributes: open.attributes,
+                    text: open.text,
+                };
+                if item_depth.is_some() {
+                    if is_cap(field.namespace.as_deref()) {
+                        if let Some(area) = areas.last_mut() {
+                            read_cap_area(area, &field);
+                        }
+                    } else if field.namespace.as_deref() == Some(ns::GEORSS)
+                        && field.name == "polygon"
+                    {
+                        areas.extend(georss_polygon(&field.text));
+                    }
+                    fields.push(field);
+                } else if format == Some(FeedFormat::Cap) && stack.len() == 1 {
+                    envelope.push(field);
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(Feed {
+        format: format.ok_or("Empty feed")?,
+        items,
+    })
+}
+
+fn detect_format(namespace: Option<&str>, root: &str) -> Result<FeedFormat, String> {
+    match (namespace, root) {
+        (_, "rss") | (_, "RDF") => Ok(FeedFormat::Rss),
+        (Some(ns::ATOM), "feed") => Ok(FeedFormat::Atom),
+        (ns, "alert") if is_cap(ns) => Ok(FeedFormat::Cap),
+        _ => Err(format!("Unsupported feed root element <{}>", root)),
+    }
+}
+
+fn is_cap(namespace: Option<&str>) -> bool {
+    matches!(namespace, Some(ns::CAP) | Some(ns::CAP_1_1))
+}
+
+fn is_item(format: Option<FeedFormat>, namespace: Option<&str>, name: &str) -> bool {
+    match format {
+        Some(FeedFormat::Rss) => name == "item",
+        Some(FeedFormat::Atom) => namespace == Some(ns::ATOM) && name == "entry",
+        Some(FeedFormat::Cap) => is_cap(namespace) && name == "info",
+        None => false,
+    }
+}
+
+fn namespace_of(resolved: &ResolveResult) -> Option<String> {
+    match resolved {
+        ResolveResult::Bound(ns) => Some(String::from_utf8_lossy(ns.as_ref()).to_string()),
+        _ => None,
+    }
+}
+
+fn local_name(e: &BytesStart) -> String {
+    String::from_utf8_lossy(e.local_name().as_ref()).to_string()
+}
+
+fn attributes(e: &BytesStart) -> Vec<(String, String)> {
+    e.attributes()
+        .flatten()
+        .filter_map(|a| {
+            let key = String::from_utf8_lossy(a.key.local_name().as_ref()).to_string();
+            Some((key, a.unescape_value().ok()?.to_string()))
+        })
+        .collect()
+}
+
+fn build_item(format: FeedFormat, fields: Vec<FeedField>, areas: Vec<FeedArea>) -> FeedItem {
+    let mut item = FeedItem {
+        fields,
+        areas,
+        ..FeedItem::default()
+    };
+
+    for field in item.fields.iter().filter(|f| f.name == "link") {
+        let attr = |key: &str| {
+            field
+                .attributes
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.clone())
+        };
+        if let Some(href) = attr("href") {
+            item.links.push(FeedLink {
+                href,
+                rel: attr("rel"),
+                media_type: attr("type"),
+            });
+        }
+    }
+
+    match format {
+        FeedFormat::Rss => {
+            item.id = item.plain("guid").unwrap_or_default().to_string();
+            item.title = item.plain("title").unwrap_or_default().to_string();
+            item.summary = item.plain("description").unwrap_or_default().to_string();
+            item.link = item
+                .plain("link")
+                .filter(|l| !l.is_empty())
+                .map(String::from);
+            item.published = item.plain("pubDate").map(String::from);
+        }
+        FeedFormat::Atom => {
+            item.id = item.field(ns::ATOM, "id").unwrap_or_default().to_string();
+            item.title = item
+                .field(ns::ATOM, "title")
+                .unwrap_or_default()
+                .to_string();
+            item.summary = item
+                .field(ns::ATOM, "summary")
+                .or_else(|| item.field(ns::ATOM, "content"))
+                .unwrap_or_default()
+                .to_string();
+            item.link = item
+                .links
+                .iter()
+                .find(|l| l.rel.as_deref().is_none_or(|r| r == "alternate"))
+                .map(|l| l.href.clone());
+            item.published = item.field(ns::ATOM, "published").map(String::from);
+            item.updated = item.field(ns::ATOM, "updated").map(String::from);
+            if item.areas.is_empty() {
+                item.areas.extend(cap_entry_area(&item));
+            }
+        }
+        FeedFormat::Cap => {
+            let cap = |name: &str| {
+                item.field(ns::CAP, name)
+                    .or_else(|| item.field(ns::CAP_1_1, name))
+                    .map(String::from)
+            };
+            let (id, title, summary, link, published) = (
+                cap("identifier").unwrap_or_default(),
+                cap("headline").or_else(|| cap("event")).unwrap_or_default(),
+                cap("description").unwrap_or_default(),
+                cap("web"),
+                cap("sent"),
+            );
+            item.id = id;
+            item.title = title;
+            item.summary = summary;
+            item.link = link;
+            item.published = published;
+            item.updated = item.published.clone();
+        }
+    }
+
+    item.point = georss_point(&item).or_else(|| {
+        let lat = item.field(ns::GEO, "lat")?.parse().ok()?;
+        let lon = item.field(ns::GEO, "long")?.parse().ok()?;
+        Some((lat, lon))
+    });
+    item
+}
+
+/// "lat lon"
+fn georss_point(item: &FeedItem) -> Option<(f64, f64)> {
+    let mut parts = item.field(ns::GEORSS, "point")?.split_whitespace();
+    let lat = parts.next()?.parse().ok()?;
+    let lon = parts.next()?.parse().ok()?;
+    Some((lat, lon))
+}
+
+/// "lat lon lat lon ..."
+fn georss_polygon(text: &str) -> Option<FeedArea> {
+    let values: Vec<f64> = text
+        .split_whitespace()
+        .filter_map(|v| v.parse().ok())
+        .collect();
+    let ring: Vec<[f64; 2]> = values.chunks_exact(2).map(|c| [c[1], c[0]]).collect();
+    (ring.len() >= 4).then(|| FeedArea {
+        description: String::new(),
+        shapes: vec![WatchShape::Polygon { rings: vec![ring] }],
+        geocodes: Vec::new(),
+    })
+}
+
+fn read_cap_area(area: &mut FeedArea, field: &FeedField) {
+    match field.name.as_str() {
+        "areaDesc" => area.description = field.text.trim().to_string(),
+        "polygon" => area.shapes.extend(cap_polygon(&field.text)),
+        "circle" => area.shapes.extend(cap_circle(&field.text)),
+        "valueName" => area
+            .geocodes
+            .push((field.text.trim().to_string(), String::new())),
+        "value" => {
+            if let Some((_, value)) = area.geocodes.last_mut().filter(|(_, v)| v.is_empty()) {
+                *value = field.text.trim().to_string();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Atom entries carrying CAP fields, as in the NWS and MeteoAlarm feeds,
+/// describe a single area with `cap:areaDesc`, `cap:polygon` and
+/// `cap:geocode`. The geocode's `valueName`/`value` children are in the Atom
+/// namespace, so pairs are taken from the fields preceding each geocode.
+fn cap_entry_area(item: &FeedItem) -> Option<FeedArea> {
+    let mut area = FeedArea::default();
+    let mut pending: Vec<(String, String)> = Vec::new();
+    let mut found = false;
+
+    for field in &item.fields {
+        let cap = is_cap(field.namespace.as_deref());
+        match field.name.as_str() {
+            "valueName" if !cap => pending.push((field.text.trim().to_string(), String::new())),
+            "value" if !cap => {
+                if let Some((_, value)) = pending.last_mut().filter(|(_, v)| v.is_empty()) {
+                    *value = field.text.trim().to_string();
+                }
+            }
+            "geocode" if cap => {
+                area.geocodes.append(&mut pending);
+                found = true;
+            }
+            "parameter" if cap => pending.clear(),
+            "areaDesc" if cap => {
+                area.description = field.text.trim().to_string();
+                found = true;
+            }
+            "polygon" if cap => {
+                area.shapes.extend(cap_polygon(&field.text));
+                found = true;
+            }
+            _ => {}
+        }
+    }
+    found.then_some(area)
+}
+
+/// CAP polygons are "lat,lon lat,lon ..." with the first point repeated last.
+fn cap_polygon(text: &str) -> Option<WatchShape> {
+    let ring: Vec<[f64; 2]> = text
+        .split_whitespace()
+        .filter_map(|pair| {
+            let (lat, lon) = pair.split_once(',')?;
+            Some([lon.parse().ok()?, lat.parse().ok()?])
+        })
+        .collect();
+    (ring.len() >= 4).then(|| WatchShape::Polygon { rings: vec![ring] })
+}
+
+/// CAP circles are "lat,lon radius_km".
+fn cap_circle(text: &str) -> Option<WatchShape> {
+    let (point, radius) = text.trim().split_once(' ')?;
+    let (lat, lon) = point.split_once(',')?;
+    Some(WatchShape::Circle {
+        latitude: lat.parse().ok()?,
+        longitude: lon.parse().ok()?,
+        radius_km: radius.trim().parse().ok()?,
+    })
+}
+
+/// Plain text of an HTML fragment, with whitespace collapsed.
+pub fn strip_html(s: &str) -> String {
+    let mut result = String::new();
+    let mut in_tag = false;
+    for c in s.chars() {
+        if c == '<' {
+            in_tag = true;
+        } else if c == '>' {
+            in_tag = false;
+        } else if !in_tag {
+            result.push(c);
+        }
+    }
+    // Collapse whitespace
+    result.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculations::geometry;
+
+    const GDACS_RSS: &str = include_str!("../../fixtures/feeds/gdacs_rss.xml");
+    const NTWC_ATOM: &str = include_str!("../../fixtures/feeds/ntwc_atom.xml");
+    const NTWC_CAP: &str = include_str!("../../fixtures/feeds/ntwc_cap.xml");
+
+    #[test]
+    fn rss_items_with_extensions_and_cdata() {
+        let feed = parse_feed(GDACS_RSS).unwrap();
+        assert_eq!(feed.format, FeedFormat::Rss);
+        assert_eq!(feed.items.len(), 2);
+
+        let cyclone = &feed.items[0];
+        assert_eq!(cyclone.title, "Red alert for tropical cyclone HAGUPIT-24");
+        // CDATA markup is kept as text
+        assert!(cyclone.summary.starts_with("<p>Maximum wind speed"));
+        assert_eq!(cyclone.point, Some((14.2, 128.5)));
+        assert_eq!(cyclone.field(ns::GDACS, "alertlevel"), Some("Red"));
+        assert_eq!(
+            cyclone.attribute(ns::GDACS, "severity", "unit"),
+            Some("km/h")
+        );
+        assert_eq!(cyclone.areas.len(), 1);
+    }
+
+    #[test]
+    fn namespaces_resolve_regardless_of_prefix() {
+        let renamed = GDACS_RSS
+            .replace("xmlns:gdacs=", "xmlns:g=")
+            .replace("<gdacs:", "<g:")
+            .replace("</gdacs:", "</g:");
+        let feed = parse_feed(&renamed).unwrap();
+        assert_eq!(feed.items[1].field(ns::GDACS, "alertlevel"), Some("Green"));
+        assert_eq!(feed.items[1].field(ns::GDACS, "eventtype"), Some("EQ"));
+    }
+
+    #[test]
+    fn atom_entries_flatten_xhtml_summaries() {
+        let feed = parse_feed(NTWC_ATOM).unwrap();
+        assert_eq!(feed.format, FeedFormat::Atom);
+        let entry = &feed.items[0];
+        assert_eq!(entry.id, "urn:uuid:1a2b");
+        assert_eq!(entry.updated.as_deref(), Some("2024-03-01T10:12:00Z"));
+        assert_eq!(entry.point, Some((56.1, -151.3)));
+        assert!(entry.summary.contains("Category: Warning\n"));
+        assert!(entry
+            .links
+            .iter()
+            .any(|l| l.href.ends_with(".cap") && l.rel.as_deref() == Some("related")));
+    }
+
+    #[test]
+    fn cap_info_blocks_become_items() {
+        let feed = parse_feed(NTWC_CAP).unwrap();
+        assert_eq!(feed.format, FeedFormat::Cap);
+        assert_eq!(feed.items.len(), 2); // English and Spanish
+        let info = &feed.items[0];
+        assert_eq!(info.id, "PAAQ-1-abc");
+        assert_eq!(info.published.as_deref(), Some("2024-03-01T10:12:00-00:00"));
+        assert_eq!(info.title, "Tsunami Warning in effect for Kodiak Island");
+        assert!(info.summary.contains("KODIAK"));
+        assert_eq!(info.areas[0].description, "Kodiak Island");
+        assert!(geometry::contains(&info.areas[0].shapes[0], 57.5, -153.0));
+        assert!(geometry::contains(&info.areas[1].shapes[0], 60.1, -149.4));
+    }
+
+    #[test]
+    fn unknown_documents_are_rejected() {
+        assert!(parse_feed("<html><body/></html>").is_err());
+        assert!(parse_feed("").is_err());
+    }
+}
@@ -1,9 +1,10 @@
+use super::feed::{ns, parse_feed, strip_html, FeedItem};
 use super::http::HTTP_CLIENT;
+use crate::calculations::gazetteer;
 use crate::models::gdacs::GdacsAlert;
-use quick_xml::events::Event;
-use quick_xml::Reader;
 
 const GDACS_RSS_URL: &str = "https://www.gdacs.org/xml/rss.xml";
+const GDACS_GEOMETRY_URL: &str = "https://www.gdacs.org/gdacsapi/api/polygons/getgeometry";
 
 pub async fn fetch_gdacs_alerts() -> Result<Vec<GdacsAlert>, String> {
     let response = HTTP_CLIENT
@@ -20,137 +21,212 @@ pub async fn fetch_gdacs_alerts() -> Result<Vec<GdacsAlert>, String> {
     parse_gdacs_rss(&text)
 }
 
-fn parse_gdacs_rss(xml: &str) -> Result<Vec<GdacsAlert>, String> {
-    let mut reader = Reader::from_str(xml);
-    let mut alerts = Vec::new();
-    let mut buf = Vec::new();
-
-    let mut in_item = false;
-    let mut current_tag = String::new();
-
-    // Item fields
-    let mut title = String::new();
-    let mut description = String::new();
-    let mut link = String::new();
-    let mut pub_date = String::new();
-    let mut alert_level = String::new();
-    let mut event_type = String::new();
-    let mut country = String::new();
-    let mut lat: Option<f64> = None;
-    let mut lon: Option<f64> = None;
-    let mut event_id = String::new();
-
-    loop {
-        match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(ref e)) => {
-                let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
-                current_tag = name.clone();
-
-                if name == "item" {
-                    in_item = true;
-                    title.clear();
-                    description.clear();
-                    link.clear();
-                    pub_date.clear();
-                    alert_level.clear();
-                    event_type.clear();
-                    country.clear();
-                    lat = None;
-                    lon = None;
-                    event_id.clear();
-                }
-            }
-            Ok(Event::Empty(_)) => {}
-            Ok(Event::Text(ref e)) => {
-                if !in_item {
-                    buf.clear();
-                    continue;
-                }
-
-                let text = e.unescape().unwrap_or_default().to_string();
-                let text = text.trim().to_string();
-                if text.is_empty() {
-                    buf.clear();
-                    continue;
-                }
-
-                match current_tag.as_str() {
-                    "title" => title = text,
-                    "description" => description = text,
-                    "link" => link = text,
-                    "pubDate" => pub_date = text,
-                    "gdacs:alertlevel" => alert_level = text,
-                    "gdacs:eventtype" => event_type = text,
-                    "gdacs:country" => country = text,
-                    "gdacs:eventid" => event_id = text,
-                    "geo:lat" => lat = text.parse().ok(),
-                    "geo:long" => lon = text.parse().ok(),
-                    "georss:point" => {
-                        // Format: "lat lon"
-                        let parts: Vec<&str> = text.split_whitespace().collect();
-                        if parts.len() == 2 {
-                            lat = parts[0].parse().ok();
-                            lon = parts[1].parse().ok();
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            Ok(Event::End(ref e)) => {
-                let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
-                if name == "item" && in_item {
-                    in_item = false;
-
-                    if let (Some(latitude), Some(longitude)) = (lat, lon) {
-                        if !latitude.is_finite() || !longitude.is_finite() {
-                            buf.clear();
-                            continue;
-                        }
-                        let id = if event_id.is_empty() {
-                            // Use lat/lon + pub_date for uniqueness when event_id is missing
-                            let date_slug: String = pub_date.chars().filter(|c| c.is_alphanumeric()).collect();
-                            format!("gdacs-{}-{:.4}-{:.4}-{}", event_type, latitude, longitude, date_slug)
-                        } else {
-                            format!("gdacs-{}-{}", event_type, event_id)
-                        };
-
-                        alerts.push(GdacsAlert {
-                            id,
-                            title: title.clone(),
-                            description: strip_html(&description),
-                            alert_type: event_type.clone(),
-                            severity: alert_level.clone(),
-                            latitude,
-                            longitude,
-                            pub_date: pub_date.clone(),
-                            link: link.clone(),
-                            country: country.clone(),
-                        });
-                    }
-                }
+/// Footprint of one GDACS event episode as a GeoJSON FeatureCollection. Each
+/// feature gets a `kind` property: "track", "forecast_track", "wind_buffer",
+/// "intensity", "flood_area", "area" or "point".
+pub async fn fetch_gdacs_geometry(
+    event_type: &str,
+    event_id: &str,
+    episode_id: Option<&str>,
+) -> Result<serde_json::Value, String> {
+    let mut query = vec![("eventtype", event_type), ("eventid", event_id)];
+    if let Some(episode) = episode_id {
+        query.push(("episodeid", episode));
+    }
+    let response = HTTP_CLIENT
+        .get(GDACS_GEOMETRY_URL)
+        .query(&query)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch GDACS geometry: {}", e))?;
+
+    let value: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse GDACS geometry: {}", e))?;
+
+    label_geometry(event_type, value)
+}
+
+fn label_geometry(
+    event_type: &str,
+    mut value: serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let features = value
+        .get_mut("features")
+        .and_then(|f| f.as_array_mut())
+        .ok_or("GDACS geometry response has no features")?;
+
+    for feature in features.iter_mut() {
+        let geometry_type = feature
+            .pointer("/geometry/type")
+            .and_then(|t| t.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let class = feature
+            .pointer("/properties/Class")
+            .and_then(|c| c.as_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let kind = match (event_type, geometry_type.as_str()) {
+            (_, "Point" | "MultiPoint") => "point",
+            ("TC", "LineString" | "MultiLineString") if class.contains("forecast") => {
+                "forecast_track"
             }
-            Ok(Event::Eof) => break,
-            Err(e) => return Err(format!("XML parse error: {}", e)),
-            _ => {}
+            (_, "LineString" | "MultiLineString") => "track",
+            ("TC", _) => "wind_buffer",
+            ("EQ", _) => "intensity",
+            ("FL", _) => "flood_area",
+            _ => "area",
+        };
+        if let Some(properties) = feature
+            .as_object_mut()
+            .map(|f| {
+                f.entry("properties")
+                    .or_insert_with(|| serde_json::json!({}))
+            })
+            .and_then(|p| p.as_object_mut())
+        {
+            properties.insert("kind".to_string(), kind.into());
         }
-        buf.clear();
     }
 
-    Ok(alerts)
+    Ok(value)
 }
 
-fn strip_html(s: &str) -> String {
-    let mut result = String::new();
-    let mut in_tag = false;
-    for c in s.chars() {
-        if c == '<' {
-            in_tag = true;
-        } else if c == '>' {
-            in_tag = false;
-        } else if !in_tag {
-            result.push(c);
-        }
+fn parse_gdacs_rss(xml: &str) -> Result<Vec<GdacsAlert>, String> {
+    let feed = parse_feed(xml)?;
+    Ok(feed.items.iter().filter_map(item_to_alert).collect())
+}
+
+fn item_to_alert(item: &FeedItem) -> Option<GdacsAlert> {
+    let (latitude, longitude) = item.point?;
+    if !latitude.is_finite() || !longitude.is_finite() {
+        return None;
+    }
+    let gdacs = |name: &str| item.field(ns::GDACS, name).unwrap_or_default().to_string();
+    // Values and units are attributes; the element text is a readable summary
+    let value = |name: &str| {
+        item.attribute(ns::GDACS, name, "value")
+            .and_then(|v| v.parse().ok())
+    };
+
+    let event_type = gdacs("eventtype");
+    let event_id = gdacs("eventid");
+    let pub_date = item.published.clone().unwrap_or_default();
+    let id = if event_id.is_empty() {
+        // Use lat/lon + pub_date for uniqueness when event_id is missing
+        let date_slug: String = pub_date.chars().filter(|c| c.is_alphanumeric()).collect();
+        format!(
+            "gdacs-{}-{:.4}-{:.4}-{}",
+            event_type, latitude, longitude, date_slug
+        )
+    } else {
+        format!("gdacs-{}-{}", event_type, event_id)
+    };
+
+    Some(GdacsAlert {
+        id,
+        title: item.title.clone(),
+        description: strip_html(&item.summary),
+        alert_type: event_type,
+        severity: gdacs("alertlevel"),
+        latitude,
+        longitude,
+        pub_date,
+        link: item.link.clone().unwrap_or_default(),
+        country: gdacs("country"),
+        // Offshore events often come without a country
+        place: gazetteer::describe(latitude, longitude).map(|label| label.label),
+        event_id,
+        episode_id: Some(gdacs("episodeid")).filter(|e| !e.is_empty()),
+        severity_value: value("severity"),
+        severity_unit: item
+            .attribute(ns::GDACS, "severity", "unit")
+            .filter(|u| !u.is_empty())
+            .map(String::from),
+        severity_text: gdacs("severity"),
+        population: value("population"),
+        population_text: gdacs("population"),
+        from_date: gdacs("fromdate"),
+        to_date: gdacs("todate"),
+        is_current: !gdacs("iscurrent").eq_ignore_ascii_case("false"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{label_geometry, parse_gdacs_rss};
+
+    #[test]
+    fn parses_gdacs_fixture() {
+        let alerts = parse_gdacs_rss(include_str!("../../fixtures/feeds/gdacs_rss.xml")).unwrap();
+        assert_eq!(alerts.len(), 2);
+
+        let cyclone = &alerts[0];
+        assert_eq!(cyclone.id, "gdacs-TC-1001100");
+        assert_eq!(cyclone.severity, "Red");
+        assert_eq!(
+            cyclone.description,
+            "Maximum wind speed of 213 km/h and storm surge."
+        );
+        assert_eq!(cyclone.severity_value, Some(213.0));
+        assert_eq!(cyclone.severity_unit.as_deref(), Some("km/h"));
+        assert_eq!(cyclone.population, Some(1_250_000.0));
+        assert_eq!(cyclone.episode_id.as_deref(), Some("14"));
+        assert!(cyclone.is_current);
+
+        let quake = &alerts[1];
+        assert_eq!((quake.latitude, quake.longitude), (-20.5, -174.9));
+        // The gazetteer label never stands in for a missing country
+        assert_eq!(cyclone.country, "Philippines");
+        assert!(quake.country.is_empty());
+        assert!(quake.place.as_deref().is_some_and(|p| p.contains("Tonga")));
+        assert_eq!(quake.population, Some(0.0));
+        assert!(!quake.is_current);
+    }
+
+    #[test]
+    fn labels_geometry_kinds() {
+        let feature = |geometry: &str, class: &str| {
+            serde_json::json!({
+                "type": "Feature",
+                "geometry": { "type": geometry, "coordinates": [] },
+                "properties": { "Class": class },
+            })
+        };
+        let value = serde_json::json!({
+            "type": "FeatureCollection",
+            "features": [
+                feature("Point", "Point_Centroid"),
+                feature("LineString", "Line_Line_Forecast"),
+                feature("LineString", "Line_Line"),
+                feature("Polygon", "Poly_Green"),
+                { "type": "Feature", "geometry": { "type": "Polygon", "coordinates": [] } },
+            ],
+        });
+        let labelled = label_geometry("TC", value).unwrap();
+        let kinds: Vec<&str> = labelled["features"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["properties"]["kind"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                "point",
+                "forecast_track",
+                "track",
+                "wind_buffer",
+                "wind_buffer"
+            ]
+        );
+
+        let quake = serde_json::json!({ "features": [feature("MultiPolygon", "Poly_Intensity")] });
+        let labelled = label_geometry("EQ", quake).unwrap();
+        assert_eq!(labelled["features"][0]["properties"]["kind"], "intensity");
+
+        assert!(label_geometry("FL", serde_json::json!({})).is_err());
     }
-    // Collapse whitespace
-    result.split_whitespace().collect::<Vec<_>>().join(" ")
 }
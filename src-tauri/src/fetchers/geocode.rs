@@ -0,0 +1,103 @@
+use super::http::HTTP_CLIENT;
+use crate::models::geocode::Place;
+
+const USER_AGENT: &str = concat!("EarthPulse/", env!("CARGO_PKG_VERSION"));
+
+/// Forward search against a Nominatim-compatible server (e.g. https://nominatim.openstreetmap.org).
+pub async fn search_nominatim(
+    base_url: &str,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<Place>, String> {
+    let url = format!("{}/search", base_url.trim_end_matches('/'));
+    let limit = limit.to_string();
+    let response = HTTP_CLIENT
+        .get(&url)
+        .header("User-Agent", USER_AGENT)
+        .query(&[
+            ("q", query),
+            ("format", "jsonv2"),
+            ("addressdetails", "1"),
+            ("limit", limit.as_str()),
+        ])
+        .send()
+        .await
+        .map_err(|e| format!("Failed to query geocoder: {}", e))?;
+
+    let results: Vec<serde_json::Value> = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse geocoder response: {}", e))?;
+
+    Ok(results.iter().filter_map(parse_nominatim_place).collect())
+}
+
+/// Reverse lookup against a Nominatim-compatible server.
+pub async fn reverse_nominatim(
+    base_url: &str,
+    lat: f64,
+    lon: f64,
+) -> Result<Option<Place>, String> {
+    let url = format!("{}/reverse", base_url.trim_end_matches('/'));
+    let (lat, lon) = (lat.to_string(), lon.to_string());
+    let response = HTTP_CLIENT
+        .get(&url)
+        .header("User-Agent", USER_AGENT)
+        .query(&[
+            ("lat", lat.as_str()),
+            ("lon", lon.as_str()),
+            ("format", "jsonv2"),
+            ("zoom", "10"),
+        ])
+        .send()
+        .await
+        .map_err(|e| format!("Failed to query geocoder: {}", e))?;
+
+    let result: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse geocoder response: {}", e))?;
+
+    // Open ocean and other unlabelled points come back as {"error": "..."}
+    if result.get("error").is_some() {
+        return Ok(None);
+    }
+    Ok(parse_nominatim_place(&result))
+}
+
+fn parse_nominatim_place(value: &serde_json::Value) -> Option<Place> {
+    let latitude: f64 = value.get("lat")?.as_str()?.parse().ok()?;
+    let longitude: f64 = value.get("lon")?.as_str()?.parse().ok()?;
+    let address = value.get("address");
+    let address_str = |key: &str| {
+        address
+            .and_then(|a| a.get(key))
+            .and_then(|v| v.as_str())
+            .map(String::from)
+    };
+
+    let name = value
+        .get("name")
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+        .or_else(|| address_str("city"))
+        .or_else(|| {
+            value
+                .get("display_name")
+                .and_then(|v| v.as_str())
+                .map(String::from)
+        })?;
+
+    Some(Place {
+        name,
+        country: address_str("country").unwrap_or_default(),
+        country_code: address_str("country_code")
+            .unwrap_or_default()
+            .to_uppercase(),
+        latitude,
+        longitude,
+        population: None,
+        source: "nominatim".to_string(),
+    })
+}
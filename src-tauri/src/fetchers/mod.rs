@@ -1,9 +1,12 @@
 pub(crate) mod http;
 pub mod air_quality;
 pub mod asteroid;
+pub mod decay;
 pub mod earthquake;
 pub mod eonet;
+pub mod feed;
 pub mod gdacs;
+pub mod geocode;
 pub mod historical;
 pub mod iss;
 pub mod meteor;
@@ -12,5 +15,8 @@ pub mod solar;
 pub mod solar_event;
 pub mod sst;
 pub mod tle;
+pub mod tsunami;
+pub mod vaac;
 pub mod volcano;
 pub mod weather;
+pub mod weather_alert;
@@ -0,0 +1,289 @@
+use super::feed::{parse_feed, FeedFormat};
+use super::http::HTTP_CLIENT;
+use crate::calculations::orbit::great_circle_km;
+use crate::models::earthquake::Earthquake;
+use crate::models::tsunami::{TsunamiArrival, TsunamiBulletin, TsunamiZone};
+
+/// Atom feeds of the National and Pacific Tsunami Warning Centers.
+const FEEDS: [(&str, &str); 2] = [
+    ("NTWC", "https://www.tsunami.gov/events/xml/PAAQAtom.xml"),
+    ("PTWC", "https://www.tsunami.gov/events/xml/PHEBAtom.xml"),
+];
+
+/// A bulletin is issued within minutes of the quake, usually close to the
+/// USGS epicentre.
+const LINK_WINDOW_MS: i64 = 3 * 3600 * 1000;
+const LINK_RADIUS_KM: f64 = 150.0;
+
+pub async fn fetch_tsunami_bulletins() -> Result<Vec<TsunamiBulletin>, String> {
+    let mut bulletins = Vec::new();
+    let mut errors = Vec::new();
+
+    for (center, url) in FEEDS {
+        // One broken feed shouldn't hide the other center's bulletins
+        match fetch_text(url)
+            .await
+            .and_then(|xml| parse_atom(&xml, center))
+        {
+            Ok(parsed) => bulletins.extend(parsed),
+            Err(e) => {
+                log::warn!("Failed to load {} tsunami feed: {}", center, e);
+                errors.push(e);
+            }
+        }
+    }
+    if bulletins.is_empty() && !errors.is_empty() {
+        return Err(errors.join("; "));
+    }
+
+    // Only bulletins that call for action carry threat zones worth fetching
+    for bulletin in bulletins.iter_mut().filter(|b| b.is_actionable()) {
+        let Some(url) = bulletin.cap_url.clone() else {
+            continue;
+        };
+        match fetch_text(&url).await.and_then(|xml| parse_cap(&xml)) {
+            Ok(cap) => {
+                if !cap.headline.is_empty() {
+                    bulletin.headline = cap.headline;
+                }
+                bulletin.zones = cap.zones;
+                bulletin.arrivals = cap.arrivals;
+            }
+            Err(e) => log::warn!("Failed to load tsunami CAP message {}: {}", url, e),
+        }
+    }
+
+    Ok(bulletins)
+}
+
+async fn fetch_text(url: &str) -> Result<String, String> {
+    let response = HTTP_CLIENT
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch tsunami feed: {}", e))?;
+
+    response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read tsunami feed response: {}", e))
+}
+
+/// Closest cached quake that happened shortly before the bulletin was issued.
+pub fn link_earthquake(bulletin: &TsunamiBulletin, quakes: &[Earthquake]) -> Option<String> {
+    let issued = chrono::DateTime::parse_from_rfc3339(&bulletin.issued)
+        .ok()?
+        .timestamp_millis();
+    quakes
+        .iter()
+        .filter(|q| q.time <= issued && issued - q.time <= LINK_WINDOW_MS)
+        .map(|q| {
+            let distance = great_circle_km(
+                bulletin.latitude,
+                bulletin.longitude,
+                q.latitude,
+                q.longitude,
+            );
+            (q, distance)
+        })
+        .filter(|(_, distance)| *distance <= LINK_RADIUS_KM)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(q, _)| q.id.clone())
+}
+
+fn parse_atom(xml: &str, center: &str) -> Result<Vec<TsunamiBulletin>, String> {
+    let feed = parse_feed(xml)?;
+    Ok(feed
+        .items
+        .iter()
+        .filter_map(|entry| {
+            let (latitude, longitude) = entry.point?;
+            let issued = entry.updated.clone().unwrap_or_default();
+            let category = summary_field(&entry.summary, "Category").unwrap_or_default();
+            Some(TsunamiBulletin {
+                id: if entry.id.is_empty() {
+                    format!("{}-{}", center, issued)
+                } else {
+                    entry.id.clone()
+                },
+                center: center.to_string(),
+                threat_level: threat_level(&category),
+                title: entry.title.clone(),
+                headline: entry.title.clone(),
+                issued,
+                latitude,
+                longitude,
+                magnitude: summary_field(&entry.summary, "Preliminary Magnitude")
+                    .and_then(|m| leading_number(&m)),
+                affected_region: summary_field(&entry.summary, "Affected Region")
+                    .unwrap_or_default(),
+                zones: Vec::new(),
+                arrivals: Vec::new(),
+                cap_url: entry
+                    .links
+                    .iter()
+                    .find(|l| {
+                        l.href.ends_with(".cap")
+                            || l.media_type.as_deref() == Some("application/cap+xml")
+                    })
+                    .map(|l| l.href.clone()),
+                earthquake_id: None,
+            })
+        })
+        .collect())
+}
+
+struct CapMessage {
+    headline: String,
+    zones: Vec<TsunamiZone>,
+    arrivals: Vec<TsunamiArrival>,
+}
+
+/// The first `<info>` block; later ones repeat it in other languages.
+fn parse_cap(xml: &str) -> Result<CapMessage, String> {
+    let feed = parse_feed(xml)?;
+    if feed.format != FeedFormat::Cap {
+        return Err("Not a CAP message".to_string());
+    }
+    let info = feed
+        .items
+        .into_iter()
+        .next()
+        .ok_or("CAP message has no info block")?;
+    Ok(CapMessage {
+        headline: info.title,
+        arrivals: parse_arrivals(&info.summary),
+        zones: info
+            .areas
+            .into_iter()
+            .map(|area| TsunamiZone {
+                description: area.description,
+                shapes: area.shapes,
+            })
+            .collect(),
+    })
+}
+
+/// Value of a "Label: value" line in the Atom summary.
+fn summary_field(summary: &str, label: &str) -> Option<String> {
+    summary.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim().eq_ignore_ascii_case(label)).then(|| value.trim().to_string())
+    })
+}
+
+fn threat_level(category: &str) -> String {
+    let category = category.to_ascii_lowercase();
+    ["warning", "advisory", "watch", "threat"]
+        .into_iter()
+        .find(|level| category.contains(level))
+        .unwrap_or("information")
+        .to_string()
+}
+
+/// "7.5(Mwp)" -> 7.5
+fn leading_number(text: &str) -> Option<f64> {
+    let end = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    text[..end].parse().ok()
+}
+
+/// Rows of the "estimated arrival times" table in the bulletin text, e.g.
+/// "  HILO HAWAII        19.7N 155.1W    1102 HST MAR 11". Columns are separated
+/// by runs of spaces; the first is the place and the last the arrival time.
+fn parse_arrivals(description: &str) -> Vec<TsunamiArrival> {
+    let mut arrivals = Vec::new();
+    let mut in_table = false;
+
+    for line in description.lines() {
+        let upper = line.to_ascii_uppercase();
+        if !in_table {
+            in_table = upper.contains("ARRIVAL") && upper.contains("TIME");
+            continue;
+        }
+        if line.trim().is_empty() {
+            if arrivals.is_empty() {
+                continue;
+            }
+            break;
+        }
+        let columns: Vec<&str> = line
+            .split("  ")
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .collect();
+        // Skip the column header and underlines
+        if columns.len() < 2 || columns[0].chars().all(|c| c == '-') || columns[0] == "LOCATION" {
+            continue;
+        }
+        arrivals.push(TsunamiArrival {
+            location: columns[0].to_string(),
+            time: columns[columns.len() - 1].to_string(),
+        });
+    }
+
+    arrivals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ATOM: &str = include_str!("../../fixtures/feeds/ntwc_atom.xml");
+    const CAP: &str = include_str!("../../fixtures/feeds/ntwc_cap.xml");
+
+    #[test]
+    fn parses_atom_entries() {
+        let bulletins = parse_atom(ATOM, "NTWC").unwrap();
+        assert_eq!(bulletins.len(), 2);
+        assert_eq!(bulletins[1].threat_level, "information");
+        assert!(!bulletins[1].is_actionable());
+        let b = &bulletins[0];
+        assert_eq!(b.id, "urn:uuid:1a2b");
+        assert_eq!(b.threat_level, "warning");
+        assert_eq!(b.magnitude, Some(7.5));
+        assert_eq!(b.affected_region, "Kodiak Island");
+        assert!(b.cap_url.as_deref().unwrap().ends_with("WEAK51.cap"));
+        assert!(b.is_actionable());
+    }
+
+    #[test]
+    fn parses_cap_zones_and_arrivals() {
+        let cap = parse_cap(CAP).unwrap();
+        assert_eq!(cap.headline, "Tsunami Warning in effect for Kodiak Island");
+        assert_eq!(cap.zones.len(), 2);
+        assert!(crate::calculations::geometry::contains(
+            &cap.zones[0].shapes[0],
+            57.5,
+            -153.0
+        ));
+        assert_eq!(cap.arrivals.len(), 2);
+        assert_eq!(cap.arrivals[0].location, "KODIAK");
+        assert_eq!(cap.arrivals[1].time, "1120 AKST MAR 1");
+    }
+
+    #[test]
+    fn links_the_nearest_recent_quake() {
+        let mut bulletin = parse_atom(ATOM, "NTWC").unwrap().remove(0);
+        let quake = |id: &str, lat: f64, minutes_before: i64| Earthquake {
+            id: id.to_string(),
+            magnitude: 7.4,
+            latitude: lat,
+            longitude: -151.3,
+            depth: 20.0,
+            place: String::new(),
+            time: 1_709_287_920_000 - minutes_before * 60_000,
+            tsunami: true,
+            title: String::new(),
+            alert: None,
+        };
+        let quakes = vec![
+            quake("far", 40.0, 5),
+            quake("near", 56.2, 5),
+            quake("old", 56.1, 600),
+        ];
+        bulletin.earthquake_id = link_earthquake(&bulletin, &quakes);
+        assert_eq!(bulletin.earthquake_id.as_deref(), Some("near"));
+    }
+}
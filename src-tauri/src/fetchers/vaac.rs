@@ -0,0 +1,706 @@
+use super::http::HTTP_CLIENT;
+use crate::calculations::orbit::great_circle_km;
+use crate::calculations::watch_rules;
+use crate::models::vaac::{AshAdvisory, AshCloud};
+use crate::models::volcano::Volcano;
+use crate::models::watchlist::WatchShape;
+use chrono::{DateTime, Datelike, Months, NaiveDate, NaiveDateTime, Utc};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+/// Advisories whose position lies this close to a known volcano are linked to
+/// it even when the names differ.
+const LINK_RADIUS_KM: f64 = 30.0;
+
+/// Load ash advisories from a VAAC product URL. Accepts ICAO text advisories
+/// (one or more, also wrapped in an HTML page) and IWXXM XML.
+pub async fn fetch_ash_advisories(url: &str) -> Result<Vec<AshAdvisory>, String> {
+    let response = HTTP_CLIENT
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch ash advisories: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!(
+            "Ash advisory request failed with status {}",
+            response.status()
+        ));
+    }
+
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read ash advisories: {}", e))?;
+
+    parse_advisories(&text)
+}
+
+pub fn parse_advisories(text: &str) -> Result<Vec<AshAdvisory>, String> {
+    let trimmed = text.trim_start();
+    if !trimmed.starts_with('<') {
+        return Ok(parse_text(text));
+    }
+    if trimmed.contains("VolcanicAshAdvisory") {
+        return parse_iwxxm(text);
+    }
+    Ok(parse_text(&strip_tags(text)))
+}
+
+/// Known volcano matching the advisory by number, name or position.
+pub fn link_volcano(advisory: &AshAdvisory, volcanoes: &[Volcano]) -> Option<String> {
+    if let Some(number) = &advisory.volcano_number {
+        if let Some(v) = volcanoes.iter().find(|v| &v.id == number) {
+            return Some(v.id.clone());
+        }
+    }
+    let name = fold_name(&advisory.volcano_name);
+    if let Some(v) = volcanoes.iter().find(|v| fold_name(&v.name) == name) {
+        return Some(v.id.clone());
+    }
+    let (Some(lat), Some(lon)) = (advisory.latitude, advisory.longitude) else {
+        return None;
+    };
+    volcanoes
+        .iter()
+        .map(|v| (v, great_circle_km(lat, lon, v.latitude, v.longitude)))
+        .filter(|(_, distance)| *distance <= LINK_RADIUS_KM)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(v, _)| v.id.clone())
+}
+
+/// Ash clouds as a GeoJSON FeatureCollection. Each feature's `layer` property
+/// is "observed" or "forecast_6h", "forecast_12h" and so on.
+pub fn to_geojson(advisories: &[AshAdvisory]) -> serde_json::Value {
+    let features: Vec<serde_json::Value> = advisories
+        .iter()
+        .flat_map(|a| a.clouds.iter().map(move |c| (a, c)))
+        .filter_map(|(a, c)| {
+            let WatchShape::Polygon { rings } = &c.shape else {
+                return None;
+            };
+            let layer = if c.kind == "observed" {
+                "observed".to_string()
+            } else {
+                format!("forecast_{}h", c.hours_ahead)
+            };
+            Some(serde_json::json!({
+                "type": "Feature",
+                "geometry": { "type": "Polygon", "coordinates": rings },
+                "properties": {
+                    "layer": layer,
+                    "advisory_id": a.id,
+                    "vaac": a.vaac,
+                    "volcano_name": a.volcano_name,
+                    "volcano_id": a.volcano_id,
+                    "issued": a.issued,
+                    "aviation_color": a.aviation_color,
+                    "valid_time": c.valid_time,
+                    "base_fl": c.base_fl,
+                    "top_fl": c.top_fl,
+                    "movement": c.movement,
+                }
+            }))
+        })
+        .collect();
+
+    serde_json::json!({ "type": "FeatureCollection", "features": features })
+}
+
+// -- ICAO text advisories --
+
+fn parse_text(text: &str) -> Vec<AshAdvisory> {
+    let starts: Vec<usize> = text.match_indices("VA ADVISORY").map(|(i, _)| i).collect();
+    starts
+        .iter()
+        .enumerate()
+        .filter_map(|(n, &start)| {
+            let end = starts.get(n + 1).copied().unwrap_or(text.len());
+            parse_text_advisory(&text[start..end])
+        })
+        .collect()
+}
+
+fn parse_text_advisory(text: &str) -> Option<AshAdvisory> {
+    let fields = text_fields(text);
+    let field = |key: &str| {
+        fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    };
+
+    let issued = NaiveDateTime::parse_from_str(field("DTG")?, "%Y%m%d/%H%MZ").ok()?;
+
+    let observed_at = field("OBS VA DTG").and_then(|t| resolve_short_time(t, issued));
+    let mut clouds = field("OBS VA CLD")
+        .map(|v| parse_layers(v, "observed", 0, observed_at.clone()))
+        .unwrap_or_default();
+    for (key, value) in &fields {
+        let Some(hours) = key
+            .strip_prefix("FCST VA CLD +")
+            .and_then(|rest| rest.trim_end_matches("HR").trim().parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let (time, rest) = value.split_once(' ').unwrap_or((value.as_str(), ""));
+        let valid = resolve_short_time(time, issued);
+        clouds.extend(parse_layers(rest, "forecast", hours, valid));
+    }
+
+    Some(build_advisory(AdvisoryFields {
+        vaac: field("VAAC").unwrap_or_default(),
+        volcano: field("VOLCANO").unwrap_or_default(),
+        position: field("PSN").and_then(parse_position),
+        issued: rfc3339(issued),
+        advisory_number: field("ADVISORY NR").unwrap_or_default(),
+        aviation_color: field("AVIATION COLOUR CODE"),
+        eruption_details: field("ERUPTION DETAILS").unwrap_or_default(),
+        clouds,
+        remarks: field("RMK").unwrap_or_default(),
+        next_advisory: field("NXT ADVISORY")
+            .and_then(|t| NaiveDateTime::parse_from_str(t, "%Y%m%d/%H%MZ").ok())
+            .map(rfc3339),
+    }))
+}
+
+/// "KEY: value" lines; lines without a key continue the previous value.
+fn text_fields(text: &str) -> Vec<(String, String)> {
+    let mut fields: Vec<(String, String)> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        let line = line.trim_end_matches('=');
+        match line.split_once(':') {
+            Some((key, value)) if is_field_key(key) => {
+                fields.push((key.trim().to_string(), value.trim().to_string()));
+            }
+            _ if line.is_empty() => {}
+            _ => {
+                if let Some((_, value)) = fields.last_mut() {
+                    value.push(' ');
+                    value.push_str(line);
+                }
+            }
+        }
+    }
+    fields
+}
+
+fn is_field_key(key: &str) -> bool {
+    let key = key.trim();
+    !key.is_empty()
+        && key.len() <= 30
+        && key.starts_with(|c: char| c.is_ascii_uppercase())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == ' ' || c == '+')
+}
+
+/// Layers of one cloud field, e.g. "SFC/FL220 N1902 W09838 - N1910 W09820 -
+/// N1855 W09815 - N1902 W09838 MOV E 10KT FL220/300 ...". Fields such as
+/// "NO VA EXP" have no layers.
+fn parse_layers(
+    value: &str,
+    kind: &str,
+    hours_ahead: u32,
+    valid_time: Option<String>,
+) -> Vec<AshCloud> {
+    let mut clouds = Vec::new();
+    let mut layer: Option<(u32, u32)> = None;
+    let mut ring: Vec<[f64; 2]> = Vec::new();
+    let mut movement: Vec<&str> = Vec::new();
+    let mut in_movement = false;
+
+    let mut finish = |layer: Option<(u32, u32)>, ring: &mut Vec<[f64; 2]>, movement: &[&str]| {
+        if let (Some((base_fl, top_fl)), Some(shape)) = (layer, close_ring(std::mem::take(ring))) {
+            clouds.push(AshCloud {
+                kind: kind.to_string(),
+                hours_ahead,
+                valid_time: valid_time.clone(),
+                base_fl,
+                top_fl,
+                shape,
+                movement: Some(movement.join(" ")).filter(|m| !m.is_empty()),
+            });
+        }
+    };
+
+    let tokens: Vec<&str> = value.split_whitespace().collect();
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+        if let Some(range) = parse_level_range(token) {
+            finish(layer, &mut ring, &movement);
+            layer = Some(range);
+            movement.clear();
+            in_movement = false;
+        } else if token == "MOV" {
+            in_movement = true;
+        } else if in_movement {
+            movement.push(token);
+        } else if let Some((lat, lon)) = tokens
+            .get(i + 1)
+            .and_then(|next| parse_position(&format!("{} {}", token, next)))
+        {
+            ring.push([lon, lat]);
+            i += 1;
+        }
+        i += 1;
+    }
+    finish(layer, &mut ring, &movement);
+
+    clouds
+}
+
+/// "SFC/FL220" -> (0, 220), "FL220/300" -> (220, 300)
+fn parse_level_range(token: &str) -> Option<(u32, u32)> {
+    let (base, top) = token.split_once('/')?;
+    let base = match base {
+        "SFC" => 0,
+        _ => base.strip_prefix("FL")?.parse().ok()?,
+    };
+    let top = top.strip_prefix("FL").unwrap_or(top).parse().ok()?;
+    Some((base, top))
+}
+
+/// "N1901 W09837" -> (19.0167, -98.6167). Degrees with optional minutes.
+fn parse_position(text: &str) -> Option<(f64, f64)> {
+    let (lat, lon) = text.trim().split_once(' ')?;
+    let lat = parse_angle(lat, 'N', 'S', 2)?;
+    let lon = parse_angle(lon.trim(), 'E', 'W', 3)?;
+    ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon)).then_some((lat, lon))
+}
+
+fn parse_angle(token: &str, positive: char, negative: char, degree_digits: usize) -> Option<f64> {
+    let mut chars = token.chars();
+    let sign = match chars.next()? {
+        c if c == positive => 1.0,
+        c if c == negative => -1.0,
+        _ => return None,
+    };
+    let digits = chars.as_str();
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let (degrees, minutes) = match digits.len() {
+        n if n == degree_digits => (digits.parse::<f64>().ok()?, 0.0),
+        n if n == degree_digits + 2 => (
+            digits[..degree_digits].parse::<f64>().ok()?,
+            digits[degree_digits..].parse::<f64>().ok()?,
+        ),
+        _ => return None,
+    };
+    Some(sign * (degrees + minutes / 60.0))
+}
+
+/// "10/1150Z" is day and time only; pick the month that puts it closest to
+/// the advisory's issue time.
+fn resolve_short_time(text: &str, issued: NaiveDateTime) -> Option<String> {
+    let (day, time) = text.trim().split_once('/')?;
+    let day: u32 = day.parse().ok()?;
+    let time = chrono::NaiveTime::parse_from_str(time, "%H%MZ").ok()?;
+    let month_start = NaiveDate::from_ymd_opt(issued.year(), issued.month(), 1)?;
+    [
+        month_start.checked_sub_months(Months::new(1)),
+        Some(month_start),
+        month_start.checked_add_months(Months::new(1)),
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(|m| m.with_day(day))
+    .map(|date| date.and_time(time))
+    .min_by_key(|candidate| (*candidate - issued).num_seconds().abs())
+    .map(rfc3339)
+}
+
+// -- IWXXM advisories --
+
+fn parse_iwxxm(xml: &str) -> Result<Vec<AshAdvisory>, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+    let mut stack: Vec<(String, String)> = Vec::new(); // (local name, text)
+    let mut advisories = Vec::new();
+
+    let mut current: Option<IwxxmAdvisory> = None;
+    let mut valid_time: Option<String> = None;
+    let mut limits: (u32, u32) = (0, 0);
+    let mut ring: Vec<[f64; 2]> = Vec::new();
+
+    loop {
+        buf.clear();
+        let event = reader
+            .read_event_into(&mut buf)
+            .map_err(|e| format!("XML parse error: {}", e))?;
+        match event {
+            Event::Start(ref e) => {
+                let name = local_name(e);
+                match name.as_str() {
+                    "VolcanicAshAdvisory" => current = Some(IwxxmAdvisory::default()),
+                    "observation" | "forecast" => valid_time = None,
+                    "colourCode" => read_colour_code(e, current.as_mut()),
+                    _ => {}
+                }
+                stack.push((name, String::new()));
+            }
+            Event::Empty(ref e) if local_name(e) == "colourCode" => {
+                read_colour_code(e, current.as_mut());
+            }
+            Event::Text(ref e) => {
+                if let Some((_, text)) = stack.last_mut() {
+                    text.push_str(&e.unescape().unwrap_or_default());
+                }
+            }
+            Event::End(_) => {
+                let Some((name, text)) = stack.pop() else {
+                    continue;
+                };
+                let text = text.trim();
+                let inside = |parent: &str| stack.iter().any(|(n, _)| n == parent);
+                let Some(adv) = current.as_mut() else {
+                    continue;
+                };
+                match name.as_str() {
+                    "timePosition" if inside("issueTime") => adv.issued = text.to_string(),
+                    "timePosition" if inside("nextAdvisoryTime") => {
+                        adv.next_advisory = Some(text.to_string())
+                    }
+                    "timePosition" if inside("phenomenonTime") => {
+                        valid_time = Some(text.to_string())
+                    }
+                    "name" if inside("issuingVolcanicAshAdvisoryCentre") => {
+                        adv.vaac = text.to_string()
+                    }
+                    "name" if inside("EruptingVolcano") => adv.volcano = text.to_string(),
+                    "pos" if inside("EruptingVolcano") => {
+                        let mut parts = text.split_whitespace().filter_map(|p| p.parse().ok());
+                        adv.position = parts.next().zip(parts.next());
+                    }
+                    "advisoryNumber" => adv.advisory_number = text.to_string(),
+                    "eruptionDetails" => adv.eruption_details = text.to_string(),
+                    "remarks" => adv.remarks = text.to_string(),
+                    "upperLimit" => limits.1 = text.parse().unwrap_or(0),
+                    "lowerLimit" => limits.0 = text.parse().unwrap_or(0),
+                    "posList" => {
+                        let values: Vec<f64> = text
+                            .split_whitespace()
+                            .filter_map(|v| v.parse().ok())
+                            .collect();
+                        ring = values.chunks_exact(2).map(|p| [p[1], p[0]]).collect();
+                    }
+                    "VolcanicAshCloudObservedOrEstimated" | "VolcanicAshCloudForecast" => {
+                        if let Some(shape) = close_ring(std::mem::take(&mut ring)) {
+                            adv.clouds.push(AshCloud {
+                                kind: if inside("forecast") {
+                                    "forecast"
+                                } else {
+                                    "observed"
+                                }
+                                .to_string(),
+                                hours_ahead: 0,
+                                valid_time: valid_time.clone(),
+                                base_fl: limits.0,
+                                top_fl: limits.1,
+                                shape,
+                                movement: None,
+                            });
+                        }
+                        limits = (0, 0);
+                    }
+                    "VolcanicAshAdvisory" => {
+                        if let Some(adv) = current.take() {
+                            advisories.extend(adv.finish());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(advisories)
+}
+
+#[derive(Default)]
+struct IwxxmAdvisory {
+    vaac: String,
+    volcano: String,
+    position: Option<(f64, f64)>,
+    issued: String,
+    advisory_number: String,
+    aviation_color: Option<String>,
+    eruption_details: String,
+    clouds: Vec<AshCloud>,
+    remarks: String,
+    next_advisory: Option<String>,
+}
+
+impl IwxxmAdvisory {
+    fn finish(mut self) -> Option<AshAdvisory> {
+        let issued = DateTime::parse_from_rfc3339(&self.issued).ok()?;
+        // Forecast offsets are measured from the observation, else from issue
+        let base = self
+            .clouds
+            .iter()
+            .find(|c| c.kind == "observed")
+            .and_then(|c| c.valid_time.as_deref())
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .unwrap_or(issued);
+        for cloud in self.clouds.iter_mut().filter(|c| c.kind == "forecast") {
+            if let Some(valid) = cloud
+                .valid_time
+                .as_deref()
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            {
+                let minutes = (valid - base).num_minutes().max(0);
+                // Round to the nearest 6-hour step
+                cloud.hours_ahead = ((minutes + 180) / 360 * 6) as u32;
+            }
+        }
+
+        Some(build_advisory(AdvisoryFields {
+            vaac: &self.vaac,
+            volcano: &self.volcano,
+            position: self.position,
+            issued: issued.with_timezone(&Utc).to_rfc3339(),
+            advisory_number: &self.advisory_number,
+            aviation_color: self.aviation_color.as_deref(),
+            eruption_details: &self.eruption_details,
+            clouds: self.clouds,
+            remarks: &self.remarks,
+            next_advisory: self.next_advisory,
+        }))
+    }
+}
+
+/// The colour code is a code-list reference such as ".../ORANGE".
+fn read_colour_code(e: &BytesStart, advisory: Option<&mut IwxxmAdvisory>) {
+    let Some(advisory) = advisory else {
+        return;
+    };
+    advisory.aviation_color = e
+        .attributes()
+        .flatten()
+        .find(|a| a.key.local_name().as_ref() == b"href")
+        .and_then(|a| a.unescape_value().ok())
+        .and_then(|href| href.rsplit('/').next().map(str::to_string));
+}
+
+fn local_name(e: &BytesStart) -> String {
+    String::from_utf8_lossy(e.local_name().as_ref()).to_string()
+}
+
+// -- Shared helpers --
+
+/// Fields read from either advisory format, before normalising.
+struct AdvisoryFields<'a> {
+    vaac: &'a str,
+    volcano: &'a str, // name, optionally followed by the volcano number
+    position: Option<(f64, f64)>,
+    issued: String, // RFC 3339
+    advisory_number: &'a str,
+    aviation_color: Option<&'a str>,
+    eruption_details: &'a str,
+    clouds: Vec<AshCloud>,
+    remarks: &'a str,
+    next_advisory: Option<String>,
+}
+
+fn build_advisory(fields: AdvisoryFields) -> AshAdvisory {
+    let AdvisoryFields {
+        vaac,
+        volcano,
+        position,
+        issued,
+        advisory_number,
+        aviation_color,
+        eruption_details,
+        clouds,
+        remarks,
+        next_advisory,
+    } = fields;
+    let (volcano_name, volcano_number) = split_volcano(volcano);
+    let vaac = vaac.trim().to_string();
+    let advisory_number = advisory_number.trim().to_string();
+    let id = if advisory_number.is_empty() {
+        format!("{}-{}-{}", vaac, volcano_name, issued)
+    } else {
+        format!("{}-{}", vaac, advisory_number)
+    }
+    .to_ascii_lowercase()
+    .replace(' ', "_");
+
+    AshAdvisory {
+        id,
+        vaac,
+        volcano_name,
+        volcano_number,
+        latitude: position.map(|(lat, _)| lat),
+        longitude: position.map(|(_, lon)| lon),
+        issued,
+        advisory_number,
+        aviation_color: aviation_color
+            .map(|c| c.trim().to_ascii_lowercase())
+            .filter(|c| watch_rules::aviation_color_rank(c).is_some()),
+        eruption_details: eruption_details.trim().to_string(),
+        clouds,
+        remarks: remarks.trim().to_string(),
+        next_advisory,
+        volcano_id: None,
+    }
+}
+
+/// "POPOCATEPETL 341090" -> ("POPOCATEPETL", Some("341090"))
+fn split_volcano(text: &str) -> (String, Option<String>) {
+    let text = text.trim();
+    match text.rsplit_once(' ') {
+        Some((name, number)) if number.len() >= 6 && number.chars().all(|c| c.is_ascii_digit()) => {
+            (name.trim().to_string(), Some(number.to_string()))
+        }
+        _ => (text.to_string(), None),
+    }
+}
+
+fn close_ring(mut ring: Vec<[f64; 2]>) -> Option<WatchShape> {
+    if ring.len() < 3 {
+        return None;
+    }
+    if ring.first() != ring.last() {
+        ring.push(ring[0]);
+    }
+    (ring.len() >= 4).then(|| WatchShape::Polygon { rings: vec![ring] })
+}
+
+/// Upper-case ASCII letters and digits only, with accents folded.
+fn fold_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            'á' | 'à' | 'â' | 'ä' | 'ã' | 'Á' | 'À' | 'Â' | 'Ä' | 'Ã' => 'a',
+            'é' | 'è' | 'ê' | 'ë' | 'É' | 'È' | 'Ê' | 'Ë' => 'e',
+            'í' | 'ì' | 'î' | 'ï' | 'Í' | 'Ì' | 'Î' | 'Ï' => 'i',
+            'ó' | 'ò' | 'ô' | 'ö' | 'õ' | 'Ó' | 'Ò' | 'Ô' | 'Ö' | 'Õ' => 'o',
+            'ú' | 'ù' | 'û' | 'ü' | 'Ú' | 'Ù' | 'Û' | 'Ü' => 'u',
+            'ñ' | 'Ñ' => 'n',
+            c => c,
+        })
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// Drop markup but keep line breaks, for advisories served inside HTML pages.
+fn strip_tags(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text
+}
+
+fn rfc3339(time: NaiveDateTime) -> String {
+    time.and_utc().to_rfc3339()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_text_advisory() {
+        let advisories =
+            parse_advisories(include_str!("../../fixtures/vaac/washington.txt")).unwrap();
+        assert_eq!(advisories.len(), 1);
+        let a = &advisories[0];
+        assert_eq!(a.id, "washington-2024/1234");
+        assert_eq!(a.volcano_name, "POPOCATEPETL");
+        assert_eq!(a.volcano_number.as_deref(), Some("341090"));
+        assert_eq!(a.issued, "2024-10-10T12:30:00+00:00");
+        assert_eq!(a.aviation_color, None);
+        assert!((a.latitude.unwrap() - 19.0167).abs() < 1e-3);
+        assert!((a.longitude.unwrap() + 98.6167).abs() < 1e-3);
+        assert_eq!(
+            a.remarks,
+            "EMISSIONS CONTINUE. ASH IS VISIBLE IN SATELLITE IMAGERY."
+        );
+        assert_eq!(
+            a.next_advisory.as_deref(),
+            Some("2024-10-10T18:30:00+00:00")
+        );
+
+        // Two observed layers, +6 and +12 h forecasts, nothing expected at +18 h
+        assert_eq!(a.clouds.len(), 4);
+        let low = &a.clouds[0];
+        assert_eq!((low.base_fl, low.top_fl), (0, 220));
+        assert_eq!(low.movement.as_deref(), Some("E 10KT"));
+        assert_eq!(low.valid_time.as_deref(), Some("2024-10-10T11:50:00+00:00"));
+        assert_eq!((a.clouds[1].base_fl, a.clouds[1].top_fl), (220, 300));
+        assert_eq!(a.clouds[1].movement.as_deref(), Some("NE 15KT"));
+        let last = &a.clouds[3];
+        assert_eq!((last.kind.as_str(), last.hours_ahead), ("forecast", 12));
+        assert_eq!(
+            last.valid_time.as_deref(),
+            Some("2024-10-11T00:00:00+00:00")
+        );
+        assert!(crate::calculations::geometry::contains(
+            &low.shape, 19.05, -98.5
+        ));
+    }
+
+    #[test]
+    fn parses_iwxxm_advisory() {
+        let advisories =
+            parse_advisories(include_str!("../../fixtures/vaac/tokyo_iwxxm.xml")).unwrap();
+        assert_eq!(advisories.len(), 1);
+        let a = &advisories[0];
+        assert_eq!(a.vaac, "TOKYO");
+        assert_eq!(a.volcano_name, "SAKURAJIMA (AIRA CALDERA)");
+        assert_eq!(a.volcano_number.as_deref(), Some("282080"));
+        assert_eq!((a.latitude, a.longitude), (Some(31.58), Some(130.67)));
+        assert_eq!(a.clouds.len(), 2);
+        assert_eq!((a.clouds[0].base_fl, a.clouds[0].top_fl), (0, 150));
+        assert_eq!(
+            (a.clouds[1].kind.as_str(), a.clouds[1].hours_ahead),
+            ("forecast", 6)
+        );
+
+        let layers = to_geojson(&advisories);
+        let features = layers["features"].as_array().unwrap();
+        assert_eq!(features.len(), 2);
+        assert_eq!(features[1]["properties"]["layer"], "forecast_6h");
+        assert_eq!(features[0]["geometry"]["coordinates"][0][0][0], 130.67);
+    }
+
+    #[test]
+    fn links_by_number_name_or_position() {
+        let volcano = |id: &str, name: &str, lat: f64, lon: f64| Volcano {
+            id: id.to_string(),
+            name: name.to_string(),
+            latitude: lat,
+            longitude: lon,
+            status: "watch".to_string(),
+            last_eruption: String::new(),
+            description: String::new(),
+            aviation_color: None,
+            observatory: None,
+            activity: None,
+            report_url: None,
+            updated: None,
+            sources: Vec::new(),
+        };
+        let mut advisory = parse_text(include_str!("../../fixtures/vaac/washington.txt")).remove(0);
+        let volcanoes = vec![volcano("popo", "Popocatépetl", 19.023, -98.622)];
+        assert_eq!(link_volcano(&advisory, &volcanoes).as_deref(), Some("popo"));
+
+        advisory.volcano_name = "UNNAMED".to_string();
+        assert_eq!(link_volcano(&advisory, &volcanoes).as_deref(), Some("popo"));
+
+        advisory.latitude = Some(10.0);
+        assert_eq!(link_volcano(&advisory, &volcanoes), None);
+    }
+}
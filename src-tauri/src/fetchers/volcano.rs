@@ -1,143 +1,238 @@
-use crate::models::volcano::Volcano;
-
-/// Returns a curated list of notable active volcanoes worldwide.
-/// In a future iteration, this could fetch from the Smithsonian GVP RSS feed.
-pub fn get_active_volcanoes() -> Vec<Volcano> {
-    vec![
-        Volcano {
-            id: "kilauea".into(),
-            name: "Kilauea".into(),
-            latitude: 19.421,
-            longitude: -155.287,
-            status: "warning".into(),
-            last_eruption: "2023-ongoing".into(),
-            description: "Shield volcano, Hawaii".into(),
-        },
-        Volcano {
-            id: "etna".into(),
-            name: "Mount Etna".into(),
-            latitude: 37.748,
-            longitude: 14.999,
-            status: "watch".into(),
-            last_eruption: "2024".into(),
-            description: "Stratovolcano, Sicily, Italy".into(),
-        },
-        Volcano {
-            id: "stromboli".into(),
-            name: "Stromboli".into(),
-            latitude: 38.789,
-            longitude: 15.213,
-            status: "advisory".into(),
-            last_eruption: "2024".into(),
-            description: "Stratovolcano, Aeolian Islands, Italy".into(),
-        },
-        Volcano {
-            id: "piton".into(),
-            name: "Piton de la Fournaise".into(),
-            latitude: -21.244,
-            longitude: 55.708,
-            status: "watch".into(),
-            last_eruption: "2024".into(),
-            description: "Shield volcano, Réunion Island".into(),
-        },
-        Volcano {
-            id: "sakurajima".into(),
-            name: "Sakurajima".into(),
-            latitude: 31.585,
-            longitude: 130.657,
-            status: "warning".into(),
-            last_eruption: "2024-ongoing".into(),
-            description: "Stratovolcano, Kyushu, Japan".into(),
-        },
-        Volcano {
-            id: "popocatepetl".into(),
-            name: "Popocatépetl".into(),
-            latitude: 19.023,
-            longitude: -98.622,
-            status: "warning".into(),
-            last_eruption: "2024-ongoing".into(),
-            description: "Stratovolcano, Mexico".into(),
-        },
-        Volcano {
-            id: "merapi".into(),
-            name: "Mount Merapi".into(),
-            latitude: -7.541,
-            longitude: 110.446,
-            status: "watch".into(),
-            last_eruption: "2024".into(),
-            description: "Stratovolcano, Java, Indonesia".into(),
-        },
-        Volcano {
-            id: "semeru".into(),
-            name: "Semeru".into(),
-            latitude: -8.108,
-            longitude: 112.922,
-            status: "watch".into(),
-            last_eruption: "2024".into(),
-            description: "Stratovolcano, Java, Indonesia".into(),
-        },
-        Volcano {
-            id: "erebus".into(),
-            name: "Mount Erebus".into(),
-            latitude: -77.53,
-            longitude: 167.17,
-            status: "normal".into(),
-            last_eruption: "Continuous".into(),
-            description: "Stratovolcano, Antarctica".into(),
-        },
-        Volcano {
-            id: "fuego".into(),
-            name: "Volcán de Fuego".into(),
-            latitude: 14.473,
-            longitude: -90.88,
-            status: "warning".into(),
-            last_eruption: "2024-ongoing".into(),
-            description: "Stratovolcano, Guatemala".into(),
-        },
-        Volcano {
-            id: "taal".into(),
-            name: "Taal".into(),
-            latitude: 14.002,
-            longitude: 120.993,
-            status: "advisory".into(),
-            last_eruption: "2022".into(),
-            description: "Caldera, Luzon, Philippines".into(),
-        },
-        Volcano {
-            id: "villarrica".into(),
-            name: "Villarrica".into(),
-            latitude: -39.42,
-            longitude: -71.93,
-            status: "advisory".into(),
-            last_eruption: "2024".into(),
-            description: "Stratovolcano, Chile".into(),
-        },
-        Volcano {
-            id: "mauna_loa".into(),
-            name: "Mauna Loa".into(),
-            latitude: 19.475,
-            longitude: -155.608,
-            status: "normal".into(),
-            last_eruption: "2022".into(),
-            description: "Shield volcano, Hawaii".into(),
-        },
-        Volcano {
-            id: "aso".into(),
-            name: "Mount Aso".into(),
-            latitude: 32.884,
-            longitude: 131.104,
-            status: "advisory".into(),
-            last_eruption: "2024".into(),
-            description: "Caldera, Kyushu, Japan".into(),
-        },
-        Volcano {
-            id: "ruang".into(),
-            name: "Ruang".into(),
-            latitude: 2.30,
-            longitude: 125.37,
-            status: "warning".into(),
-            last_eruption: "2024".into(),
-            description: "Stratovolcano, Sulawesi, Indonesia".into(),
-        },
-    ]
+use super::feed::{parse_feed, strip_html, FeedItem};
+use super::http::HTTP_CLIENT;
+use crate::calculations::watch_rules;
+use crate::models::volcano::{HansVolcano, Volcano};
+use std::collections::BTreeMap;
+
+/// Smithsonian / USGS Weekly Volcanic Activity Report.
+const GVP_WEEKLY_URL: &str = "https://volcano.si.edu/news/WeeklyVolcanoRSS.xml";
+/// US volcanoes above normal alert level, from the USGS Volcano Hazards Program.
+const HANS_ELEVATED_URL: &str =
+    "https://volcanoes.usgs.gov/hans-public/api/volcano/getElevatedVolcanoes";
+
+/// Volcanoes in this week's GVP report merged with elevated US volcanoes.
+/// USGS alert levels and colour codes are official; volcanoes only in the GVP
+/// report get "watch" for new activity or unrest and "advisory" for
+/// continuing activity.
+pub async fn fetch_active_volcanoes() -> Result<Vec<Volcano>, String> {
+    let (gvp, hans) = tokio::join!(fetch_gvp_reports(), fetch_hans_elevated());
+
+    let (gvp, hans) = match (gvp, hans) {
+        (Err(a), Err(b)) => return Err(format!("{}; {}", a, b)),
+        (gvp, hans) => (
+            gvp.unwrap_or_else(|e| {
+                log::warn!("{}", e);
+                Vec::new()
+            }),
+            hans.unwrap_or_else(|e| {
+                log::warn!("{}", e);
+                Vec::new()
+            }),
+        ),
+    };
+
+    Ok(merge(gvp, hans))
+}
+
+async fn fetch_gvp_reports() -> Result<Vec<Volcano>, String> {
+    let response = HTTP_CLIENT
+        .get(GVP_WEEKLY_URL)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch GVP weekly report: {}", e))?;
+
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read GVP weekly report: {}", e))?;
+
+    parse_gvp_reports(&text)
+}
+
+async fn fetch_hans_elevated() -> Result<Vec<HansVolcano>, String> {
+    let response = HTTP_CLIENT
+        .get(HANS_ELEVATED_URL)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch USGS volcano alert levels: {}", e))?;
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse USGS volcano alert levels: {}", e))
+}
+
+fn parse_gvp_reports(xml: &str) -> Result<Vec<Volcano>, String> {
+    let feed = parse_feed(xml)?;
+    Ok(feed.items.iter().filter_map(report_to_volcano).collect())
+}
+
+/// Titles look like "Etna (Italy) - Report for 2 October-8 October 2024 -
+/// NEW ACTIVITY/UNREST".
+fn report_to_volcano(item: &FeedItem) -> Option<Volcano> {
+    let (latitude, longitude) = item.point?;
+    let mut parts = item.title.split(" - ");
+    let heading = parts.next()?.trim();
+    let kind = parts.last().unwrap_or_default().to_ascii_lowercase();
+    let (name, country) = match heading.rsplit_once(" (") {
+        Some((name, rest)) => (name.trim(), rest.trim_end_matches(')').trim()),
+        None => (heading, ""),
+    };
+    if name.is_empty() {
+        return None;
+    }
+
+    Some(Volcano {
+        id: item
+            .link
+            .as_deref()
+            .and_then(volcano_number)
+            .unwrap_or_else(|| slug(name)),
+        name: name.to_string(),
+        latitude,
+        longitude,
+        status: if kind.contains("new") || kind.contains("unrest") {
+            "watch"
+        } else {
+            "advisory"
+        }
+        .to_string(),
+        last_eruption: String::new(),
+        description: country.to_string(),
+        aviation_color: None,
+        observatory: None,
+        activity: Some(strip_html(&item.summary)).filter(|a| !a.is_empty()),
+        report_url: item.link.clone(),
+        updated: item.published.clone(),
+        sources: vec!["gvp".to_string()],
+    })
+}
+
+/// Combine both sources on the Smithsonian volcano number, falling back to
+/// the name for entries without one.
+fn merge(gvp: Vec<Volcano>, hans: Vec<HansVolcano>) -> Vec<Volcano> {
+    let mut volcanoes: BTreeMap<String, Volcano> =
+        gvp.into_iter().map(|v| (v.id.clone(), v)).collect();
+
+    for h in hans {
+        let Some(status) = Some(h.alert_level.trim().to_ascii_lowercase())
+            .filter(|s| watch_rules::volcano_level_rank(s).is_some())
+        else {
+            continue;
+        };
+        let color = h
+            .color_code
+            .as_deref()
+            .map(|c| c.trim().to_ascii_lowercase())
+            .filter(|c| watch_rules::aviation_color_rank(c).is_some());
+        let id = h
+            .vnum
+            .clone()
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| slug(&h.volcano_name));
+        let key = volcanoes
+            .iter()
+            .find(|(k, v)| **k == id || v.name.eq_ignore_ascii_case(&h.volcano_name))
+            .map(|(k, _)| k.clone());
+
+        match key.and_then(|k| volcanoes.get_mut(&k)) {
+            Some(v) => {
+                v.status = status;
+                v.aviation_color = color;
+                v.observatory = h.obs_fullname;
+                v.updated = h.sent_utc.or(v.updated.take());
+                v.sources.push("usgs".to_string());
+            }
+            None => {
+                // Without the weekly report there is no other source of coordinates
+                let (Some(latitude), Some(longitude)) = (h.latitude, h.longitude) else {
+                    log::debug!("No location for elevated volcano {}", h.volcano_name);
+                    continue;
+                };
+                volcanoes.insert(
+                    id.clone(),
+                    Volcano {
+                        id,
+                        name: h.volcano_name,
+                        latitude,
+                        longitude,
+                        status,
+                        last_eruption: String::new(),
+                        description: "United States".to_string(),
+                        aviation_color: color,
+                        observatory: h.obs_fullname,
+                        activity: None,
+                        report_url: h.notice_url,
+                        updated: h.sent_utc,
+                        sources: vec!["usgs".to_string()],
+                    },
+                );
+            }
+        }
+    }
+
+    volcanoes.into_values().collect()
+}
+
+/// "https://volcano.si.edu/volcano.cfm?vn=211060" -> "211060"
+fn volcano_number(url: &str) -> Option<String> {
+    let (_, query) = url.split_once('?')?;
+    query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("vn="))
+        .filter(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        .map(String::from)
+}
+
+fn slug(name: &str) -> String {
+    name.to_ascii_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_weekly_report_and_merges_usgs_levels() {
+        let gvp = parse_gvp_reports(include_str!("../../fixtures/feeds/gvp_weekly.xml")).unwrap();
+        assert_eq!(gvp.len(), 2);
+        let etna = &gvp[0];
+        assert_eq!(etna.id, "211060");
+        assert_eq!(etna.name, "Etna");
+        assert_eq!(etna.description, "Italy");
+        assert_eq!(etna.status, "watch");
+        assert_eq!(
+            etna.activity.as_deref(),
+            Some("INGV reported that Strombolian activity resumed at Voragine Crater.")
+        );
+        assert_eq!(gvp[1].status, "advisory");
+
+        let hans: Vec<HansVolcano> = serde_json::from_str(
+            r#"[
+                {"volcano_name": "Great Sitkin", "vnum": "311120", "alert_level": "WATCH",
+                 "color_code": "ORANGE", "obs_fullname": "Alaska Volcano Observatory",
+                 "sent_utc": "2024-10-08 20:15:00"},
+                {"volcano_name": "Kilauea", "vnum": "332010", "alert_level": "ADVISORY",
+                 "color_code": "YELLOW", "latitude": 19.421, "longitude": -155.287},
+                {"volcano_name": "Mystery", "vnum": "999999", "alert_level": "WATCH"}
+            ]"#,
+        )
+        .unwrap();
+        let volcanoes = merge(gvp, hans);
+        assert_eq!(volcanoes.len(), 3);
+
+        let sitkin = volcanoes.iter().find(|v| v.id == "311120").unwrap();
+        assert_eq!(sitkin.status, "watch");
+        assert_eq!(sitkin.aviation_color.as_deref(), Some("orange"));
+        assert_eq!(sitkin.sources, ["gvp", "usgs"]);
+        assert!(sitkin.activity.is_some());
+
+        let kilauea = volcanoes.iter().find(|v| v.id == "332010").unwrap();
+        assert_eq!(kilauea.status, "advisory");
+        assert_eq!(kilauea.sources, ["usgs"]);
+    }
 }
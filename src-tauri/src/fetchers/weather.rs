@@ -1,5 +1,18 @@
 use super::http::HTTP_CLIENT;
-use crate::models::weather::{OpenMeteoResponse, Weather};
+use crate::models::weather::{
+    DailyForecast, Forecast, HourlyForecast, LocalConditions, OpenMeteoForecastResponse,
+    OpenMeteoResponse, Weather,
+};
+
+const HOURLY_VARIABLES: &str = "temperature_2m,precipitation,precipitation_probability,cloud_cover,visibility,wind_speed_10m,wind_gusts_10m,pressure_msl,weather_code";
+const DAILY_VARIABLES: &str = "weather_code,temperature_2m_max,temperature_2m_min,precipitation_sum,precipitation_probability_max,wind_gusts_10m_max,sunrise,sunset";
+
+/// Days of forecast requested; covers the 24 hour pass prediction window
+/// with room for planning ahead.
+const FORECAST_DAYS: u32 = 7;
+
+/// Furthest a forecast hour may be from the requested time to describe it.
+const MAX_CONDITIONS_OFFSET_SECS: i64 = 3600;
 
 fn wmo_description(code: u32) -> &'static str {
     match code {
@@ -67,3 +80,184 @@ pub async fn fetch_weather(lat: f64, lon: f64) -> Result<Weather, String> {
         humidity_pct: data.current.relative_humidity_2m,
     })
 }
+
+/// Hourly and daily forecast for one point, times in unix seconds (UTC).
+pub async fn fetch_forecast(lat: f64, lon: f64) -> Result<Forecast, String> {
+    if !lat.is_finite() || !lon.is_finite() {
+        return Err("Invalid coordinates".into());
+    }
+
+    let response = HTTP_CLIENT
+        .get("https://api.open-meteo.com/v1/forecast")
+        .query(&[
+            ("latitude", lat.to_string()),
+            ("longitude", lon.to_string()),
+            ("hourly", HOURLY_VARIABLES.to_string()),
+            ("daily", DAILY_VARIABLES.to_string()),
+            ("forecast_days", FORECAST_DAYS.to_string()),
+            ("timeformat", "unixtime".to_string()),
+            ("timezone", "GMT".to_string()),
+        ])
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch forecast: {}", e))?;
+
+    let data: OpenMeteoForecastResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse forecast data: {}", e))?;
+
+    Ok(to_forecast(data))
+}
+
+fn to_forecast(data: OpenMeteoForecastResponse) -> Forecast {
+    fn at<T: Copy>(series: &[Option<T>], i: usize) -> Option<T> {
+        series.get(i).copied().flatten()
+    }
+
+    let h = &data.hourly;
+    let hourly = h
+        .time
+        .iter()
+        .enumerate()
+        .map(|(i, &time)| {
+            let weather_code = at(&h.weather_code, i);
+            HourlyForecast {
+                time,
+                temperature_c: at(&h.temperature_2m, i),
+                precipitation_mm: at(&h.precipitation, i),
+                precipitation_probability: at(&h.precipitation_probability, i),
+                cloud_cover_pct: at(&h.cloud_cover, i),
+                visibility_m: at(&h.visibility, i),
+                wind_speed_kmh: at(&h.wind_speed_10m, i),
+                wind_gusts_kmh: at(&h.wind_gusts_10m, i),
+                pressure_hpa: at(&h.pressure_msl, i),
+                weather_code,
+                weather_description: weather_code.map(|c| wmo_description(c).to_string()),
+            }
+        })
+        .collect();
+
+    let d = &data.daily;
+    let daily = d
+        .time
+        .iter()
+        .enumerate()
+        .map(|(i, &date)| {
+            let weather_code = at(&d.weather_code, i);
+            DailyForecast {
+                date,
+                weather_code,
+                weather_description: weather_code.map(|c| wmo_description(c).to_string()),
+                temperature_max_c: at(&d.temperature_2m_max, i),
+                temperature_min_c: at(&d.temperature_2m_min, i),
+                precipitation_mm: at(&d.precipitation_sum, i),
+                precipitation_probability: at(&d.precipitation_probability_max, i),
+                wind_gusts_max_kmh: at(&d.wind_gusts_10m_max, i),
+                sunrise: at(&d.sunrise, i),
+                sunset: at(&d.sunset, i),
+            }
+        })
+        .collect();
+
+    Forecast {
+        latitude: data.latitude,
+        longitude: data.longitude,
+        hourly,
+        daily,
+    }
+}
+
+/// Conditions from the forecast hour nearest to `time` (unix seconds), if
+/// the forecast covers it.
+pub fn conditions_at(forecast: &Forecast, time: i64) -> Option<LocalConditions> {
+    let hour = forecast
+        .hourly
+        .iter()
+        .min_by_key(|h| (h.time - time).abs())
+        .filter(|h| (h.time - time).abs() <= MAX_CONDITIONS_OFFSET_SECS)?;
+
+    let mut summary = match hour.cloud_cover_pct {
+        Some(cover) => format!("{}, {:.0}% cloud", sky_description(cover), cover),
+        None => hour
+            .weather_description
+            .clone()
+            .unwrap_or_else(|| "No cloud forecast".to_string()),
+    };
+    if hour.visibility_m.is_some_and(|v| v < 1000.0) {
+        summary.push_str(", poor visibility");
+    }
+    if let Some(p) = hour.precipitation_probability.filter(|p| *p >= 50.0) {
+        summary.push_str(&format!(", {:.0}% chance of precipitation", p));
+    }
+
+    Some(LocalConditions {
+        time: hour.time,
+        cloud_cover_pct: hour.cloud_cover_pct,
+        visibility_m: hour.visibility_m,
+        precipitation_probability: hour.precipitation_probability,
+        weather_description: hour.weather_description.clone(),
+        summary,
+    })
+}
+
+fn sky_description(cloud_cover_pct: f64) -> &'static str {
+    match cloud_cover_pct {
+        c if c < 20.0 => "Clear",
+        c if c < 50.0 => "Partly cloudy",
+        c if c < 85.0 => "Mostly cloudy",
+        _ => "Overcast",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forecast_series_and_conditions_at_pass_time() {
+        let data: OpenMeteoForecastResponse = serde_json::from_str(
+            r#"{
+                "latitude": 51.5, "longitude": -0.12,
+                "hourly": {
+                    "time": [1700000000, 1700003600, 1700007200],
+                    "cloud_cover": [10, 90, null],
+                    "visibility": [24000, 800, null],
+                    "precipitation_probability": [0, 70, null],
+                    "weather_code": [0, 3, null]
+                },
+                "daily": {
+                    "time": [1699920000],
+                    "temperature_2m_max": [12.5],
+                    "sunset": [1699977000]
+                }
+            }"#,
+        )
+        .unwrap();
+        let forecast = to_forecast(data);
+        assert_eq!(forecast.hourly.len(), 3);
+        assert_eq!(
+            forecast.hourly[1].weather_description.as_deref(),
+            Some("Overcast")
+        );
+        assert_eq!(forecast.hourly[0].pressure_hpa, None);
+        assert_eq!(forecast.daily[0].temperature_max_c, Some(12.5));
+        assert_eq!(forecast.daily[0].sunset, Some(1699977000));
+
+        let clear = conditions_at(&forecast, 1_700_000_900).unwrap();
+        assert_eq!(clear.time, 1_700_000_000);
+        assert_eq!(clear.summary, "Clear, 10% cloud");
+
+        let overcast = conditions_at(&forecast, 1_700_003_000).unwrap();
+        assert_eq!(
+            overcast.summary,
+            "Overcast, 90% cloud, poor visibility, 70% chance of precipitation"
+        );
+
+        assert_eq!(
+            conditions_at(&forecast, 1_700_007_000).unwrap().summary,
+            "No cloud forecast"
+        );
+        assert!(conditions_at(&forecast, 1_700_020_000).is_none());
+    }
+}
@@ -0,0 +1,203 @@
+use super::feed::{ns, parse_feed, FeedFormat, FeedItem};
+use super::http::HTTP_CLIENT;
+use crate::models::weather_alert::{Geocode, WeatherAlert, WeatherAlertArea};
+use std::collections::HashSet;
+
+/// Active NWS alerts for the whole US, used when no feed is configured.
+pub const DEFAULT_FEED_URL: &str = "https://api.weather.gov/alerts/active.atom";
+
+/// api.weather.gov rejects requests without a User-Agent.
+const USER_AGENT: &str = concat!("EarthPulse/", env!("CARGO_PKG_VERSION"));
+
+/// Alerts currently in force from one feed.
+pub async fn fetch_weather_alerts(url: &str) -> Result<Vec<WeatherAlert>, String> {
+    let xml = fetch_text(url).await?;
+    let now = chrono::Utc::now().timestamp();
+    Ok(parse_alerts(&xml, url)?
+        .into_iter()
+        .filter(|a| a.is_active(now))
+        .collect())
+}
+
+async fn fetch_text(url: &str) -> Result<String, String> {
+    let response = HTTP_CLIENT
+        .get(url)
+        .header("User-Agent", USER_AGENT)
+        .header(
+            "Accept",
+            "application/atom+xml, application/cap+xml, application/xml",
+        )
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch weather alerts: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Weather alert feed returned {}", response.status()));
+    }
+    response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read weather alert feed: {}", e))
+}
+
+/// Alerts in a CAP message or an Atom feed of CAP entries. Test and exercise
+/// messages are dropped, as are RSS items and entries without CAP fields.
+/// A CAP message repeats its info block per language; the English one is
+/// kept, or else the first.
+pub fn parse_alerts(xml: &str, feed_url: &str) -> Result<Vec<WeatherAlert>, String> {
+    let feed = parse_feed(xml)?;
+    if feed.format == FeedFormat::Rss {
+        return Err("RSS feeds carry no CAP fields".to_string());
+    }
+
+    let mut items: Vec<&FeedItem> = feed.items.iter().collect();
+    // Stable sort, so the feed order is kept within each group
+    items.sort_by_key(|item| !cap(item, "language").is_some_and(|l| l.starts_with("en")));
+
+    let mut seen = HashSet::new();
+    let mut alerts: Vec<WeatherAlert> = items
+        .into_iter()
+        .filter(|item| cap(item, "status").is_none_or(|s| s.eq_ignore_ascii_case("actual")))
+        .filter_map(|item| to_alert(item, feed_url))
+        .filter(|alert| seen.insert(alert.id.clone()))
+        .collect();
+    alerts.sort_by(|a, b| b.sent.cmp(&a.sent));
+    Ok(alerts)
+}
+
+fn to_alert(item: &FeedItem, feed_url: &str) -> Option<WeatherAlert> {
+    let event = cap(item, "event")?.to_string();
+    let id = cap(item, "identifier")
+        .map(String::from)
+        .or_else(|| Some(item.id.clone()).filter(|id| !id.is_empty()))?;
+    let lower = |name: &str| {
+        cap(item, name)
+            .map(|v| v.to_ascii_lowercase())
+            .unwrap_or_else(|| "unknown".to_string())
+    };
+
+    Some(WeatherAlert {
+        id,
+        feed_url: feed_url.to_string(),
+        sender: cap(item, "senderName")
+            .or_else(|| cap(item, "sender"))
+            .map(String::from),
+        headline: if item.title.is_empty() {
+            event.clone()
+        } else {
+            item.title.clone()
+        },
+        event,
+        description: item.summary.trim().to_string(),
+        instruction: cap(item, "instruction").map(String::from),
+        msg_type: cap(item, "msgType")
+            .map(|t| t.to_ascii_lowercase())
+            .unwrap_or_else(|| "alert".to_string()),
+        severity: lower("severity"),
+        urgency: lower("urgency"),
+        certainty: lower("certainty"),
+        sent: cap(item, "sent")
+            .map(String::from)
+            .or_else(|| item.published.clone())
+            .or_else(|| item.updated.clone()),
+        onset: cap(item, "onset")
+            .or_else(|| cap(item, "effective"))
+            .map(String::from),
+        expires: cap(item, "expires").map(String::from),
+        areas: item
+            .areas
+            .iter()
+            .map(|area| WeatherAlertArea {
+                description: area.description.clone(),
+                shapes: area.shapes.clone(),
+                geocodes: area
+                    .geocodes
+                    .iter()
+                    .map(|(name, value)| Geocode {
+                        name: name.clone(),
+                        value: value.clone(),
+                    })
+                    .collect(),
+            })
+            .collect(),
+        web: cap(item, "web")
+            .map(String::from)
+            .or_else(|| item.link.clone()),
+        // "sender,identifier,sent" triples separated by whitespace
+        references: cap(item, "references")
+            .unwrap_or_default()
+            .split_whitespace()
+            .filter_map(|r| r.split(',').nth(1))
+            .map(String::from)
+            .collect(),
+    })
+}
+
+/// Non-empty text of a CAP 1.2 or 1.1 field.
+fn cap<'a>(item: &'a FeedItem, name: &str) -> Option<&'a str> {
+    item.field(ns::CAP, name)
+        .or_else(|| item.field(ns::CAP_1_1, name))
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calculations::geometry;
+
+    #[test]
+    fn nws_atom_entries_become_alerts() {
+        let alerts = parse_alerts(
+            include_str!("../../fixtures/feeds/nws_alerts.xml"),
+            DEFAULT_FEED_URL,
+        )
+        .unwrap();
+        assert_eq!(alerts.len(), 2); // the test message is dropped
+
+        let tornado = alerts
+            .iter()
+            .find(|a| a.event == "Tornado Warning")
+            .unwrap();
+        assert_eq!(tornado.severity, "extreme");
+        assert_eq!(tornado.urgency, "immediate");
+        assert_eq!(tornado.certainty, "observed");
+        assert_eq!(tornado.expires_at(), Some(1_714_000_500));
+        assert!(tornado.is_warning());
+        assert!(tornado.references.is_empty());
+        assert_eq!(
+            WeatherAlert::message_id(&tornado.id),
+            "urn:oid:2.49.0.1.840.0.tornado.1"
+        );
+        let area = &tornado.areas[0];
+        assert_eq!(area.description, "Cleveland, OK; McClain, OK");
+        assert!(geometry::contains(&area.shapes[0], 35.1, -97.4));
+        assert_eq!(area.geocodes.len(), 3);
+        assert_eq!(area.geocodes[2].name, "UGC");
+        assert_eq!(area.geocodes[2].value, "OKC027");
+
+        let advisory = alerts.iter().find(|a| a.event == "Wind Advisory").unwrap();
+        assert!(!advisory.is_warning());
+        assert!(advisory.areas[0].shapes.is_empty());
+        assert!(advisory.is_active(1_714_000_000));
+        assert!(!advisory.is_active(1_714_100_000));
+    }
+
+    #[test]
+    fn cap_message_keeps_english_info() {
+        let alerts = parse_alerts(
+            include_str!("../../fixtures/feeds/meteoalarm_cap.xml"),
+            "https://feeds.meteoalarm.org/",
+        )
+        .unwrap();
+        assert_eq!(alerts.len(), 1);
+        let alert = &alerts[0];
+        assert_eq!(alert.headline, "Orange Thunderstorm Warning for Tirol");
+        assert_eq!(alert.severity, "severe");
+        assert_eq!(alert.msg_type, "update");
+        assert_eq!(alert.references, ["2.49.0.0.40.0.AT.240715.tirol.0"]);
+        assert_eq!(alert.areas[0].geocodes[0].name, "EMMA_ID");
+        assert_eq!(alert.areas[0].geocodes[0].value, "AT007");
+        assert!(geometry::contains(&alert.areas[0].shapes[0], 47.3, 11.4));
+    }
+}
@@ -0,0 +1,176 @@
+use crate::models::satellite::PassPrediction;
+use crate::notifications::azimuth_to_cardinal;
+use chrono::{DateTime, Utc};
+
+/// Minutes before AOS the calendar reminder fires.
+const ALARM_LEAD_MINS: i64 = 10;
+
+/// RFC 5545 caps content lines at 75 octets, excluding the CRLF.
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Build an iCalendar document with one VEVENT per pass.
+pub fn passes_to_ics(passes: &[PassPrediction], generated_at: i64) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//EarthPulse//Satellite Passes//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:Satellite passes".to_string(),
+    ];
+
+    let stamp = format_utc(generated_at);
+    for pass in passes {
+        let summary = format!("{} pass, max {:.0}°", pass.name, pass.max_elevation);
+        let description = format!(
+            "AOS {} at {:.0}° {}\nMax elevation {:.0}° at {:.0}° {}\nLOS {} at {:.0}° {}{}",
+            format_clock(pass.start_time),
+            normalize_azimuth(pass.start_azimuth),
+            azimuth_to_cardinal(pass.start_azimuth),
+            pass.max_elevation,
+            normalize_azimuth(pass.max_azimuth),
+            azimuth_to_cardinal(pass.max_azimuth),
+            format_clock(pass.end_time),
+            normalize_azimuth(pass.end_azimuth),
+            azimuth_to_cardinal(pass.end_azimuth),
+            if pass.is_visible {
+                "\nVisible to the naked eye"
+            } else {
+                ""
+            },
+        );
+
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}@earthpulse", pass.event_key()));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART:{}", format_utc(pass.start_time)));
+        lines.push(format!("DTEND:{}", format_utc(pass.end_time)));
+        lines.push(format!("SUMMARY:{}", escape_text(&summary)));
+        lines.push(format!("DESCRIPTION:{}", escape_text(&description)));
+        lines.push("CATEGORIES:Satellite pass".to_string());
+        lines.push("TRANSP:TRANSPARENT".to_string());
+        lines.push("BEGIN:VALARM".to_string());
+        lines.push("ACTION:DISPLAY".to_string());
+        lines.push(format!("DESCRIPTION:{}", escape_text(&summary)));
+        lines.push(format!("TRIGGER:-PT{}M", ALARM_LEAD_MINS));
+        lines.push("END:VALARM".to_string());
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+
+    lines
+        .iter()
+        .map(|line| fold_line(line))
+        .collect::<Vec<_>>()
+        .join("")
+}
+
+fn format_utc(timestamp: i64) -> String {
+    DateTime::<Utc>::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+fn format_clock(timestamp: i64) -> String {
+    DateTime::<Utc>::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .format("%H:%M:%S UTC")
+        .to_string()
+}
+
+fn normalize_azimuth(az: f64) -> f64 {
+    ((az % 360.0) + 360.0) % 360.0
+}
+
+/// Escape TEXT values: backslash, semicolon, comma and newlines.
+fn escape_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Fold a content line into CRLF-terminated chunks of at most 75 octets,
+/// never splitting a UTF-8 character; continuation lines start with a space.
+fn fold_line(line: &str) -> String {
+    let mut out = String::with_capacity(line.len() + 8);
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            octets = 1;
+        }
+        out.push(c);
+        octets += c.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_pass() -> PassPrediction {
+        PassPrediction {
+            satellite_id: "sat-25544".to_string(),
+            name: "ISS (ZARYA)".to_string(),
+            start_time: 1_700_000_000,
+            end_time: 1_700_000_600,
+            max_elevation: 62.4,
+            start_azimuth: -135.0,
+            max_azimuth: 160.0,
+            end_azimuth: 45.0,
+            is_visible: true,
+            cloud_cover_pct: None,
+        }
+    }
+
+    #[test]
+    fn calendar_contains_one_event_per_pass() {
+        let ics = passes_to_ics(&[sample_pass(), sample_pass()], 1_699_990_000);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ics.contains("DTSTART:20231114T221320Z\r\n"));
+        assert!(ics.contains("DTEND:20231114T222320Z\r\n"));
+        assert!(ics.contains("TRIGGER:-PT10M\r\n"));
+    }
+
+    #[test]
+    fn uid_is_stable_within_the_aos_minute() {
+        let mut pass = sample_pass();
+        pass.start_time = 1_700_000_010;
+        let first = passes_to_ics(&[pass.clone()], 1_699_990_000);
+        pass.start_time = 1_700_000_035;
+        let second = passes_to_ics(&[pass], 1_699_990_000);
+        assert!(first.contains("UID:sat-25544-1699999980@earthpulse\r\n"));
+        assert!(second.contains("UID:sat-25544-1699999980@earthpulse\r\n"));
+    }
+
+    #[test]
+    fn text_values_are_escaped() {
+        assert_eq!(escape_text("a,b;c\\d\ne"), "a\\,b\\;c\\\\d\\ne");
+    }
+
+    #[test]
+    fn long_lines_fold_on_character_boundaries() {
+        let line = format!("DESCRIPTION:{}", "°".repeat(60));
+        let folded = fold_line(&line);
+        for chunk in folded.split("\r\n").filter(|c| !c.is_empty()) {
+            assert!(chunk.len() <= MAX_LINE_OCTETS);
+        }
+        let unfolded = folded.replace("\r\n ", "");
+        assert_eq!(unfolded.trim_end(), line);
+    }
+}
@@ -1,13 +1,17 @@
 mod calculations;
+mod calendar_server;
 mod commands;
 mod db;
+mod delivery;
 mod fetchers;
+mod ics;
 mod models;
 mod notifications;
 mod tray;
 
+use calculations::rule_engine::RuleEvent;
 use db::Database;
-use models::iss::IssData;
+use models::iss::{IssData, IssPosition};
 use notifications::NotificationTracker;
 use std::sync::Arc;
 use std::time::Duration;
@@ -19,6 +23,7 @@ struct SourceHealthEvent {
     ok: bool,
     timestamp_ms: i64,
     error: Option<String>,
+    discrepancy_km: Option<f64>,
 }
 
 fn emit_source_health(
@@ -26,12 +31,24 @@ fn emit_source_health(
     source: &'static str,
     ok: bool,
     error: Option<String>,
+) {
+    emit_source_health_with_discrepancy(handle, source, ok, error, None);
+}
+
+/// Health event for a cross-check source, carrying how far it disagrees with our own value.
+fn emit_source_health_with_discrepancy(
+    handle: &tauri::AppHandle,
+    source: &'static str,
+    ok: bool,
+    error: Option<String>,
+    discrepancy_km: Option<f64>,
 ) {
     let payload = SourceHealthEvent {
         source,
         ok,
         timestamp_ms: chrono::Utc::now().timestamp_millis(),
         error,
+        discrepancy_km,
     };
     handle.emit("source:health", payload).ok();
 }
@@ -66,7 +83,6 @@ pub fn run() {
 
             // Background: earthquake polling (every 60s)
             let handle = app.handle().clone();
-            let eq_tracker = Arc::clone(&tracker);
             tauri::async_runtime::spawn(async move {
                 loop {
                     match fetchers::earthquake::fetch_earthquakes().await {
@@ -77,30 +93,34 @@ pub fn run() {
 
                             // Read user settings from DB, falling back to defaults
                             let settings = db.get_settings();
-                            let user_lat = settings.user_lat.unwrap_or(37.3382);
-                            let user_lon = settings.user_lon.unwrap_or(-121.8863);
+                            let mut observers = db.get_observer_profiles();
+                            if observers.is_empty() {
+                                observers.push(commands::observer::active_observer(&db));
+                            }
                             let mag_threshold = settings.mag_threshold.unwrap_or(5.0);
                             let proximity_km = settings.proximity_km.unwrap_or(500.0);
 
                             if settings.notify_earthquakes.unwrap_or(true) {
                                 notifications::check_earthquake_notifications(
                                     &handle,
-                                    &eq_tracker,
                                     &quakes,
                                     mag_threshold,
-                                    user_lat,
-                                    user_lon,
+                                    &observers,
                                     proximity_km,
                                 );
                             }
 
                             // Check watchlists
-                            notifications::check_watchlist_notifications(
-                                &handle,
-                                &eq_tracker,
-                                &quakes,
-                                &db,
-                            );
+                            notifications::check_watchlist_notifications(&handle, &quakes, &db);
+
+                            // User alert rules see quakes from the last 5 minutes
+                            let now = chrono::Utc::now().timestamp_millis();
+                            let recent: Vec<RuleEvent> = quakes
+                                .iter()
+                                .filter(|q| now - q.time <= 5 * 60 * 1000)
+                                .map(RuleEvent::earthquake)
+                                .collect();
+                            notifications::check_alert_rules(&handle, &recent, &db);
 
                             // Update tray with strongest quake
                             let strongest = quakes.iter().max_by(|a, b| {
@@ -126,28 +146,105 @@ pub fn run() {
                 }
             });
 
-            // Background: ISS position polling (every 5s)
+            // Optional: pass calendar subscription endpoint
+            if let Some(addr) = app.state::<Database>().get_settings().calendar_server_addr {
+                let handle = app.handle().clone();
+                tauri::async_runtime::spawn(calendar_server::serve(handle, addr));
+            }
+
+            // Background: ISS position from the cached TLE (every 1s, stored every 5s)
             let handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
+                let mut propagator = None;
+                let mut loaded_at = 0;
+                let mut trail = Vec::new();
+                let mut stored_at = 0;
                 loop {
-                    match fetchers::iss::fetch_iss_position().await {
-                        Ok(pos) => {
-                            let db = handle.state::<Database>();
-                            db.store_iss_position(&pos);
-                            let trail = db.get_iss_trail();
+                    let db = handle.state::<Database>();
+                    let now = chrono::Utc::now().timestamp();
+
+                    // Cheap while the TLE cache is warm; refetches after it expires
+                    if propagator.is_none() || now - loaded_at > 600 {
+                        if let Some(p) = commands::satellite::load_iss_propagator(&db).await {
+                            propagator = Some(p);
+                        }
+                        loaded_at = now;
+                    }
+
+                    match propagator.as_ref().and_then(|p| p.geodetic_at(now)) {
+                        Some((latitude, longitude, _)) => {
+                            let pos = IssPosition {
+                                latitude,
+                                longitude,
+                                timestamp: now,
+                            };
+                            if now - stored_at >= 5 {
+                                stored_at = now;
+                                db.store_iss_position(&pos);
+                                trail = db.get_iss_trail();
+                                emit_source_health(&handle, "iss", true, None);
+                            }
                             let data = IssData {
                                 current: pos,
-                                trail,
+                                trail: trail.clone(),
                             };
                             handle.emit("iss:update", &data).ok();
-                            emit_source_health(&handle, "iss", true, None);
                         }
-                        Err(e) => {
-                            emit_source_health(&handle, "iss", false, Some(e.clone()));
-                            log::error!("ISS fetch error: {}", e)
+                        None => {
+                            if now - stored_at >= 5 {
+                                stored_at = now;
+                                let e = "No ISS element set available".to_string();
+                                emit_source_health(&handle, "iss", false, Some(e.clone()));
+                                log::error!("ISS propagation error: {}", e)
+                            }
+                        }
+                    }
+
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+            });
+
+            // Background: optional open-notify cross-check of the ISS position (every 60s)
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    let db = handle.state::<Database>();
+
+                    if db.get_settings().iss_crosscheck.unwrap_or(false) {
+                        match fetchers::iss::fetch_iss_position().await {
+                            Ok(remote) => {
+                                let local = commands::satellite::load_iss_propagator(&db)
+                                    .await
+                                    .and_then(|p| p.geodetic_at(remote.timestamp));
+                                if let Some((lat, lon, _)) = local {
+                                    let discrepancy = calculations::orbit::great_circle_km(
+                                        lat,
+                                        lon,
+                                        remote.latitude,
+                                        remote.longitude,
+                                    );
+                                    emit_source_health_with_discrepancy(
+                                        &handle,
+                                        "iss-crosscheck",
+                                        true,
+                                        None,
+                                        Some(discrepancy),
+                                    );
+                                }
+                            }
+                            Err(e) => {
+                                emit_source_health(
+                                    &handle,
+                                    "iss-crosscheck",
+                                    false,
+                                    Some(e.clone()),
+                                );
+                                log::warn!("ISS cross-check error: {}", e)
+                            }
                         }
                     }
-                    tokio::time::sleep(Duration::from_secs(5)).await;
+
+                    tokio::time::sleep(Duration::from_secs(60)).await;
                 }
             });
 
@@ -162,6 +259,15 @@ pub fn run() {
                 }
             });
 
+            // Background: notification digest (every 60s)
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    notifications::flush_digest(&handle);
+                }
+            });
+
             // Background: solar data polling (every 15min)
             let handle = app.handle().clone();
             let kp_tracker = Arc::clone(&tracker);
@@ -169,14 +275,27 @@ pub fn run() {
                 loop {
                     match fetchers::solar::fetch_kp_index().await {
                         Ok(data) => {
-                            let settings = handle.state::<Database>().get_settings();
+                            let db = handle.state::<Database>();
+                            let settings = db.get_settings();
                             if settings.notify_aurora.unwrap_or(true) {
+                                // Only storms notify, so skip the forecast otherwise
+                                let sky = if data.kp_index >= 5.0 {
+                                    commands::weather::get_local_conditions_now(&db).await
+                                } else {
+                                    None
+                                };
                                 notifications::check_kp_notification(
                                     &handle,
                                     &kp_tracker,
                                     data.kp_index,
+                                    sky.as_ref(),
                                 );
                             }
+                            notifications::check_alert_rules(
+                                &handle,
+                                &[RuleEvent::kp(data.kp_index, &data.kp_timestamp)],
+                                &db,
+                            );
                             tray::update_tray_menu(
                                 &handle,
                                 "",
@@ -196,12 +315,60 @@ pub fn run() {
                 }
             });
 
-            // Emit volcano data once at startup
+            // Background: volcano activity and alert levels (every hour)
             let handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
-                let volcanoes = fetchers::volcano::get_active_volcanoes();
-                handle.emit("volcanoes:update", &volcanoes).ok();
-                emit_source_health(&handle, "volcanoes", true, None);
+                loop {
+                    match fetchers::volcano::fetch_active_volcanoes().await {
+                        Ok(volcanoes) => {
+                            let db = handle.state::<Database>();
+                            if let Ok(json) = serde_json::to_string(&volcanoes) {
+                                db.set_cached_response("volcano:active", &json);
+                            }
+                            notifications::check_watchlist_volcanoes(&handle, &volcanoes, &db);
+                            notifications::check_volcano_escalation(&handle, &volcanoes, &db);
+                            handle.emit("volcanoes:update", &volcanoes).ok();
+                            emit_source_health(&handle, "volcanoes", true, None);
+                            log::info!("Fetched {} active volcanoes", volcanoes.len());
+                        }
+                        Err(e) => {
+                            emit_source_health(&handle, "volcanoes", false, Some(e.clone()));
+                            log::error!("Volcano fetch error: {}", e)
+                        }
+                    }
+                    tokio::time::sleep(Duration::from_secs(3600)).await;
+                }
+            });
+
+            // Background: volcanic ash advisories (every 15min, when a VAAC URL is set)
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    let db = handle.state::<Database>();
+
+                    if let Some(url) = db.get_settings().vaac_url {
+                        match fetchers::vaac::fetch_ash_advisories(&url).await {
+                            Ok(mut advisories) => {
+                                commands::vaac::link_volcanoes(&db, &mut advisories);
+                                if let Ok(json) = serde_json::to_string(&advisories) {
+                                    let key = commands::vaac::cache_key(&url);
+                                    db.set_cached_response(&key, &json);
+                                }
+                                handle
+                                    .emit("ash:update", fetchers::vaac::to_geojson(&advisories))
+                                    .ok();
+                                emit_source_health(&handle, "vaac", true, None);
+                                log::info!("Fetched {} ash advisories", advisories.len());
+                            }
+                            Err(e) => {
+                                emit_source_health(&handle, "vaac", false, Some(e.clone()));
+                                log::error!("Ash advisory fetch error: {}", e)
+                            }
+                        }
+                    }
+
+                    tokio::time::sleep(Duration::from_secs(900)).await;
+                }
             });
 
             // Emit meteor shower data once at startup
@@ -231,9 +398,21 @@ pub fn run() {
                             if let Ok(json) = serde_json::to_string(&alerts) {
                                 db.set_cached_response("gdacs:rss", &json);
                             }
+                            notifications::check_watchlist_gdacs(&handle, &alerts, &db);
+                            notifications::check_gdacs_escalation(&handle, &alerts, &db);
+                            let events: Vec<RuleEvent> =
+                                alerts.iter().map(RuleEvent::gdacs).collect();
+                            notifications::check_alert_rules(&handle, &events, &db);
                             handle.emit("gdacs:update", &alerts).ok();
                             emit_source_health(&handle, "gdacs", true, None);
                             log::info!("Fetched {} GDACS alerts", alerts.len());
+
+                            // Warm the footprint cache for alerts still in progress
+                            for alert in alerts.iter().filter(|a| a.is_current) {
+                                if let Err(e) = commands::gdacs::alert_geometry(&db, alert).await {
+                                    log::warn!("GDACS geometry for {}: {}", alert.id, e);
+                                }
+                            }
                         }
                         Err(e) => {
                             emit_source_health(&handle, "gdacs", false, Some(e.clone()));
@@ -244,9 +423,66 @@ pub fn run() {
                 }
             });
 
+            // Background: tsunami warning centre bulletins (every 5min)
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    match fetchers::tsunami::fetch_tsunami_bulletins().await {
+                        Ok(mut bulletins) => {
+                            let db = handle.state::<Database>();
+                            commands::tsunami::link_earthquakes(&db, &mut bulletins);
+                            if let Ok(json) = serde_json::to_string(&bulletins) {
+                                db.set_cached_response("tsunami:bulletins", &json);
+                            }
+                            let mut observers = db.get_observer_profiles();
+                            if observers.is_empty() {
+                                observers.push(commands::observer::active_observer(&db));
+                            }
+                            notifications::check_tsunami_notification(
+                                &handle, &bulletins, &observers,
+                            );
+                            handle.emit("tsunami:update", &bulletins).ok();
+                            emit_source_health(&handle, "tsunami", true, None);
+                            log::info!("Fetched {} tsunami bulletins", bulletins.len());
+                        }
+                        Err(e) => {
+                            emit_source_health(&handle, "tsunami", false, Some(e.clone()));
+                            log::error!("Tsunami fetch error: {}", e)
+                        }
+                    }
+                    tokio::time::sleep(Duration::from_secs(300)).await;
+                }
+            });
+
+            // Background: CAP severe weather warnings (every 5min)
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    let db = handle.state::<Database>();
+                    match commands::weather_alert::refresh_weather_alerts(&db).await {
+                        Ok(alerts) => {
+                            let mut observers = db.get_observer_profiles();
+                            if observers.is_empty() {
+                                observers.push(commands::observer::active_observer(&db));
+                            }
+                            notifications::check_weather_alert_notifications(
+                                &handle, &alerts, &observers, &db,
+                            );
+                            handle.emit("weather_alerts:update", &alerts).ok();
+                            emit_source_health(&handle, "weather_alerts", true, None);
+                            log::info!("Loaded {} weather alerts", alerts.len());
+                        }
+                        Err(e) => {
+                            emit_source_health(&handle, "weather_alerts", false, Some(e.clone()));
+                            log::error!("Weather alert fetch error: {}", e)
+                        }
+                    }
+                    tokio::time::sleep(Duration::from_secs(300)).await;
+                }
+            });
+
             // Background: satellite positions + pass predictions (every 5min)
             let handle = app.handle().clone();
-            let sat_tracker = Arc::clone(&tracker);
             tauri::async_runtime::spawn(async move {
                 loop {
                     let db = handle.state::<Database>();
@@ -266,7 +502,7 @@ pub fn run() {
                     // Calculate pass predictions
                     match commands::satellite::get_pass_predictions_inner(&db).await {
                         Ok(passes) => {
-                            notifications::check_pass_notification(&handle, &sat_tracker, &passes);
+                            notifications::check_pass_notification(&handle, &passes);
 
                             // Update tray with next ISS pass only
                             let now = chrono::Utc::now().timestamp();
@@ -292,6 +528,69 @@ pub fn run() {
                 }
             });
 
+            // Background: conjunction screening between tracked satellites (every 6 hours)
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    let db = handle.state::<Database>();
+                    match commands::satellite::get_conjunctions_inner(&db).await {
+                        Ok(conjunctions) => {
+                            if let Ok(json) = serde_json::to_string(&conjunctions) {
+                                db.set_cached_response("conjunctions", &json);
+                            }
+
+                            if db.get_settings().notify_conjunctions.unwrap_or(true) {
+                                notifications::check_conjunction_notification(
+                                    &handle,
+                                    &conjunctions,
+                                );
+                            }
+
+                            handle.emit("conjunctions:update", &conjunctions).ok();
+                            emit_source_health(&handle, "conjunctions", true, None);
+                            log::info!("Screened {} conjunctions", conjunctions.len());
+                        }
+                        Err(e) => {
+                            emit_source_health(&handle, "conjunctions", false, Some(e.clone()));
+                            log::error!("Conjunction screening error: {}", e)
+                        }
+                    }
+                    tokio::time::sleep(Duration::from_secs(21600)).await;
+                }
+            });
+
+            // Background: decay predictions + reentry windows (every 6 hours)
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    let db = handle.state::<Database>();
+
+                    if let Some(source) = db.get_settings().decay_source {
+                        match fetchers::decay::fetch_decay_predictions(&source).await {
+                            Ok(predictions) => {
+                                if let Ok(json) = serde_json::to_string(&predictions) {
+                                    db.set_cached_response("decay:predictions", &json);
+                                }
+                                emit_source_health(&handle, "decay", true, None);
+                                log::info!("Loaded {} decay predictions", predictions.len());
+                            }
+                            Err(e) => {
+                                emit_source_health(&handle, "decay", false, Some(e.clone()));
+                                log::error!("Decay prediction fetch error: {}", e)
+                            }
+                        }
+                    }
+
+                    let report = commands::reentry::get_reentry_predictions_inner(&db).await;
+                    if let Ok(json) = serde_json::to_string(&report) {
+                        db.set_cached_response("reentry:report", &json);
+                    }
+                    handle.emit("reentry:update", &report).ok();
+
+                    tokio::time::sleep(Duration::from_secs(21600)).await;
+                }
+            });
+
             // Background: EONET natural events (every 30 min)
             let handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
@@ -302,7 +601,13 @@ pub fn run() {
                             if let Ok(json) = serde_json::to_string(&events) {
                                 db.set_cached_response("eonet:events", &json);
                             }
-                            handle.emit("eonet:update", &events).ok();
+                            // Only events in the selected categories are shown or notified
+                            let selected = commands::eonet::filter_selected(&db, events.clone());
+                            notifications::check_watchlist_eonet(&handle, &selected, &db);
+                            let rule_events: Vec<RuleEvent> =
+                                selected.iter().map(RuleEvent::eonet).collect();
+                            notifications::check_alert_rules(&handle, &rule_events, &db);
+                            handle.emit("eonet:update", &selected).ok();
                             emit_source_health(&handle, "eonet", true, None);
                             log::info!("Fetched {} EONET events", events.len());
                         }
@@ -317,22 +622,21 @@ pub fn run() {
 
             // Background: asteroid close approaches (every 6 hours)
             let handle = app.handle().clone();
-            let ast_tracker = Arc::clone(&tracker);
             tauri::async_runtime::spawn(async move {
                 loop {
-                    match fetchers::asteroid::fetch_asteroids().await {
+                    let db = handle.state::<Database>();
+                    match commands::asteroid::refresh_asteroids(&db).await {
                         Ok(asteroids) => {
-                            let db = handle.state::<Database>();
                             if let Ok(json) = serde_json::to_string(&asteroids) {
-                                db.set_cached_response("nasa:neo", &json);
+                                let key = commands::asteroid::cache_key(&db.get_settings());
+                                db.set_cached_response(&key, &json);
                             }
 
                             // Check for hazardous close approaches
-                            notifications::check_asteroid_notification(
-                                &handle,
-                                &ast_tracker,
-                                &asteroids,
-                            );
+                            notifications::check_asteroid_notification(&handle, &asteroids);
+                            let events: Vec<RuleEvent> =
+                                asteroids.iter().map(RuleEvent::asteroid).collect();
+                            notifications::check_alert_rules(&handle, &events, &db);
 
                             handle.emit("asteroids:update", &asteroids).ok();
                             emit_source_health(&handle, "asteroids", true, None);
@@ -349,7 +653,6 @@ pub fn run() {
 
             // Background: solar flares & CMEs (every 3 hours)
             let handle = app.handle().clone();
-            let flare_tracker = Arc::clone(&tracker);
             tauri::async_runtime::spawn(async move {
                 loop {
                     match fetchers::solar_event::fetch_solar_activity().await {
@@ -359,11 +662,13 @@ pub fn run() {
                                 db.set_cached_response("nasa:donki", &json);
                             }
 
-                            notifications::check_solar_flare_notification(
-                                &handle,
-                                &flare_tracker,
-                                &activity,
-                            );
+                            notifications::check_solar_flare_notification(&handle, &activity);
+                            let flares: Vec<RuleEvent> =
+                                activity.flares.iter().map(RuleEvent::flare).collect();
+                            notifications::check_alert_rules(&handle, &flares, &db);
+                            let cmes: Vec<RuleEvent> =
+                                activity.cmes.iter().map(RuleEvent::cme).collect();
+                            notifications::check_alert_rules(&handle, &cmes, &db);
 
                             handle.emit("solar_activity:update", &activity).ok();
                             emit_source_health(&handle, "solar_activity", true, None);
@@ -400,25 +705,66 @@ pub fn run() {
             commands::iss::get_iss_position,
             commands::terminator::get_terminator,
             commands::solar::get_solar_data,
+            commands::vaac::get_ash_advisories,
+            commands::vaac::get_ash_layers,
             commands::volcano::get_volcanoes,
+            commands::volcano::get_volcano_history,
             commands::replay::get_historical_data,
             commands::settings::get_settings,
             commands::settings::save_settings,
             commands::gdacs::get_gdacs_alerts,
+            commands::gdacs::get_gdacs_history,
+            commands::gdacs::get_gdacs_geometry,
+            commands::tsunami::get_tsunami_bulletins,
             commands::historical::get_historical_earthquakes,
             commands::satellite::get_satellite_positions,
             commands::satellite::get_pass_predictions,
+            commands::satellite::get_satellite_coverage,
+            commands::satellite::get_conjunctions,
+            commands::satellite::get_tle_history,
+            commands::satellite::export_pass_calendar,
+            commands::observer::get_observer_profiles,
+            commands::observer::add_observer_profile,
+            commands::observer::update_observer_profile,
+            commands::observer::set_active_observer_profile,
+            commands::observer::remove_observer_profile,
+            commands::geocode::geocode,
+            commands::geocode::reverse_geocode,
+            commands::reentry::get_reentry_predictions,
             commands::plate::get_plates,
+            commands::notification::get_notification_history,
+            commands::notification::acknowledge_notifications,
+            commands::notification::snooze_notifications,
+            commands::notification::get_notification_snoozes,
+            commands::notification::cancel_notification_snooze,
             commands::meteor::get_meteors,
+            commands::channel::get_alert_channels,
+            commands::channel::save_alert_channel,
+            commands::channel::remove_alert_channel,
+            commands::channel::send_test_alert,
             commands::asteroid::get_asteroids,
+            commands::asteroid::get_asteroid_approaches,
             commands::solar_event::get_solar_activity,
             commands::eonet::get_eonet_events,
+            commands::eonet::get_eonet_categories,
+            commands::eonet::get_closed_eonet_events,
             commands::weather::get_weather,
+            commands::weather::get_forecast,
+            commands::weather::get_local_conditions,
+            commands::weather_alert::get_weather_alerts,
+            commands::weather_alert::get_weather_alerts_at,
             commands::air_quality::get_air_quality,
+            commands::alert_rule::get_alert_rules,
+            commands::alert_rule::validate_rule,
+            commands::alert_rule::save_rule,
+            commands::alert_rule::remove_rule,
             commands::sst::get_sst,
             commands::summary::generate_summary,
             commands::watchlist::get_watchlists,
             commands::watchlist::add_watchlist,
+            commands::watchlist::add_watchlist_shape,
+            commands::watchlist::import_watchlists_geojson,
+            commands::watchlist::update_watchlist,
             commands::watchlist::remove_watchlist,
         ])
         .run(tauri::generate_context!())
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub id: i64,
+    pub name: String,
+    pub expression: String, // e.g. "earthquake.magnitude >= 6 && distance(home) < 1000"
+    pub enabled: bool,
+    pub created_at: i64,
+}
@@ -9,8 +9,8 @@ pub struct NeoResponse {
 #[derive(Debug, Deserialize)]
 pub struct NeoObject {
     pub id: String,
+    pub neo_reference_id: String, // JPL SPK-ID
     pub name: String,
-    #[allow(dead_code)]
     pub absolute_magnitude_h: Option<f64>,
     pub estimated_diameter: DiameterEstimate,
     pub is_potentially_hazardous_asteroid: bool,
@@ -34,6 +34,7 @@ pub struct CloseApproach {
     pub epoch_date_close_approach: i64,
     pub relative_velocity: Velocity,
     pub miss_distance: MissDistance,
+    pub orbiting_body: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -59,4 +60,103 @@ pub struct Asteroid {
     pub velocity_kps: f64,
     pub miss_distance_km: f64,
     pub miss_distance_lunar: f64,
+    #[serde(default)]
+    pub orbiting_body: String, // of the next approach, usually "Earth"
+    #[serde(default)]
+    pub absolute_magnitude_h: Option<f64>,
+    #[serde(default)]
+    pub spk_id: String, // empty for objects only known from the JPL approach list
+    #[serde(default)]
+    pub designation: String, // number if numbered, else provisional designation
+    #[serde(default)]
+    pub orbit_class: Option<OrbitClass>,
+    #[serde(default)]
+    pub approaches: Vec<AsteroidApproach>, // every approach in the window, soonest first
+    #[serde(default)]
+    pub impact_risk: Option<ImpactRisk>,
+}
+
+/// The top-level approach fields of `Asteroid` repeat the first of these.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AsteroidApproach {
+    pub date: String,
+    pub time: i64,
+    pub velocity_kps: f64,
+    pub miss_distance_km: f64,
+    pub miss_distance_lunar: f64,
+    pub orbiting_body: String,
+    #[serde(default)]
+    pub source: String, // "neows" or "jpl"
+}
+
+/// JPL orbit class, e.g. code "APO", name "Apollo".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrbitClass {
+    pub code: String,
+    pub name: String,
+}
+
+/// Impact risk from the JPL Sentry system. Objects without an entry have no
+/// known impact solutions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImpactRisk {
+    pub designation: String,
+    pub impact_probability: f64, // cumulative over all potential impacts
+    pub palermo_cumulative: f64,
+    pub palermo_max: f64,
+    pub torino_max: u8,
+    pub potential_impacts: u32,
+    pub years: String, // e.g. "2069-2122"
+}
+
+// JPL Sentry summary response; numbers come as strings
+
+#[derive(Debug, Deserialize)]
+pub struct SentryResponse {
+    #[serde(default)]
+    pub data: Vec<SentryObject>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SentryObject {
+    pub des: String,
+    pub ip: String,
+    pub ps_cum: String,
+    pub ps_max: String,
+    pub ts_max: Option<String>,
+    pub n_imp: serde_json::Value, // number or string depending on API version
+    pub range: String,
+}
+
+// JPL SBDB close-approach (CAD) response: a field list plus rows of values
+
+#[derive(Debug, Deserialize)]
+pub struct CadResponse {
+    pub fields: Vec<String>,
+    #[serde(default)]
+    pub data: Vec<Vec<Option<String>>>,
+}
+
+/// Filters and ordering for `get_asteroids`. Sort is "time" (default),
+/// "distance", "size" or "velocity".
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AsteroidQuery {
+    pub sort: Option<String>,
+    pub max_distance_lunar: Option<f64>,
+    pub min_diameter_km: Option<f64>,
+    pub min_velocity_kps: Option<f64>,
+    pub hazardous_only: bool,
+}
+
+// JPL Small-Body Database lookup, only the orbit class is used
+
+#[derive(Debug, Deserialize)]
+pub struct SbdbResponse {
+    pub object: SbdbObject,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SbdbObject {
+    pub orbit_class: OrbitClass,
 }
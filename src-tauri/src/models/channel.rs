@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+
+/// Somewhere alerts are delivered besides the desktop toast.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertChannel {
+    pub id: i64,
+    pub name: String,
+    pub config: ChannelConfig,
+    pub template: Option<String>, // message text, e.g. "{title}: {body}"; None uses the default
+    pub enabled: bool,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChannelConfig {
+    /// POSTs the notification as JSON, with the rendered template as `text`
+    Webhook {
+        url: String,
+    },
+    Slack {
+        webhook_url: String,
+    },
+    Discord {
+        webhook_url: String,
+    },
+    Ntfy {
+        server: String, // e.g. "https://ntfy.sh"
+        topic: String,
+        #[serde(default)]
+        token: Option<String>,
+    },
+    Gotify {
+        server: String,
+        app_token: String,
+    },
+    Email {
+        host: String,
+        port: u16,
+        from: String,
+        to: Vec<String>,
+        #[serde(default)]
+        username: Option<String>,
+        #[serde(default)]
+        password: Option<String>,
+        /// None picks implicit TLS on port 465, plain SMTP for a relay on
+        /// this machine and STARTTLS otherwise
+        #[serde(default)]
+        security: Option<SmtpSecurity>,
+    },
+}
+
+/// Stands in for secrets in configs sent to the frontend; saving it back
+/// keeps the stored value.
+pub const REDACTED: &str = "********";
+
+impl ChannelConfig {
+    /// Copy with webhook URLs, tokens and passwords replaced by [`REDACTED`].
+    pub fn redacted(&self) -> Self {
+        let mut config = self.clone();
+        match &mut config {
+            ChannelConfig::Webhook { url: webhook_url }
+            | ChannelConfig::Slack { webhook_url }
+            | ChannelConfig::Discord { webhook_url } => *webhook_url = REDACTED.to_string(),
+            ChannelConfig::Gotify { app_token, .. } => *app_token = REDACTED.to_string(),
+            ChannelConfig::Ntfy { token: secret, .. }
+            | ChannelConfig::Email {
+                password: secret, ..
+            } => {
+                if secret.is_some() {
+                    *secret = Some(REDACTED.to_string());
+                }
+            }
+        }
+        config
+    }
+
+    /// Put back secrets the frontend left as [`REDACTED`] from the stored
+    /// config of the same channel.
+    pub fn restore_secrets(&mut self, stored: &ChannelConfig) {
+        let keep = |value: &mut String, old: &String| {
+            if value == REDACTED {
+                *value = old.clone();
+            }
+        };
+        let keep_opt = |value: &mut Option<String>, old: &Option<String>| {
+            if value.as_deref() == Some(REDACTED) {
+                *value = old.clone();
+            }
+        };
+        match (self, stored) {
+            (ChannelConfig::Webhook { url }, ChannelConfig::Webhook { url: old }) => keep(url, old),
+            (ChannelConfig::Slack { webhook_url }, ChannelConfig::Slack { webhook_url: old })
+            | (
+                ChannelConfig::Discord { webhook_url },
+                ChannelConfig::Discord { webhook_url: old },
+            ) => keep(webhook_url, old),
+            (ChannelConfig::Ntfy { token, .. }, ChannelConfig::Ntfy { token: old, .. }) => {
+                keep_opt(token, old)
+            }
+            (
+                ChannelConfig::Gotify { app_token, .. },
+                ChannelConfig::Gotify { app_token: old, .. },
+            ) => keep(app_token, old),
+            (ChannelConfig::Email { password, .. }, ChannelConfig::Email { password: old, .. }) => {
+                keep_opt(password, old)
+            }
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    /// Implicit TLS from the first byte, usually port 465
+    Tls,
+    /// Plain connection upgraded with STARTTLS, usually port 587
+    StartTls,
+    /// No encryption; only for a relay such as a local MTA
+    None,
+}
@@ -21,7 +21,6 @@ pub struct UsgsProperties {
     pub updated: Option<i64>,
     pub tsunami: Option<i32>,
     pub title: Option<String>,
-    #[allow(dead_code)]
     pub alert: Option<String>,
 }
 
@@ -41,6 +40,8 @@ pub struct Earthquake {
     pub time: i64,
     pub tsunami: bool,
     pub title: String,
+    #[serde(default)]
+    pub alert: Option<String>, // PAGER level: green, yellow, orange or red
 }
 
 impl Earthquake {
@@ -77,6 +78,7 @@ impl Earthquake {
                 .title
                 .clone()
                 .unwrap_or_else(|| "Unknown earthquake".to_string()),
+            alert: feature.properties.alert.clone(),
         })
     }
 }
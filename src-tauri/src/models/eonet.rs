@@ -1,3 +1,4 @@
+use crate::models::watchlist::WatchShape;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize)]
@@ -9,8 +10,8 @@ pub struct EonetResponse {
 pub struct EonetEvent {
     pub id: String,
     pub title: String,
-    #[allow(dead_code)]
     pub description: Option<String>,
+    pub closed: Option<String>,
     pub categories: Vec<EonetCategory>,
     pub geometry: Vec<EonetGeometry>,
 }
@@ -21,13 +22,31 @@ pub struct EonetCategory {
     pub title: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct EonetCategoriesResponse {
+    pub categories: Vec<EonetCategoryInfo>,
+}
+
+/// Entry of the EONET category list, offered in settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EonetCategoryInfo {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct EonetGeometry {
     pub date: String,
     #[serde(rename = "type")]
-    #[allow(dead_code)]
     pub geom_type: String,
-    pub coordinates: Vec<f64>,
+    // [lon, lat] for points, rings of [lon, lat] for polygons
+    pub coordinates: serde_json::Value,
+    #[serde(rename = "magnitudeValue")]
+    pub magnitude_value: Option<f64>,
+    #[serde(rename = "magnitudeUnit")]
+    pub magnitude_unit: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,4 +58,28 @@ pub struct NaturalEvent {
     pub latitude: f64,
     pub longitude: f64,
     pub date: String,
+    #[serde(default)]
+    pub place: Option<String>, // nearest-city label, e.g. "30 km N of Catania, Italy"
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub magnitude_value: Option<f64>, // latest observation, e.g. wind speed or burned area
+    #[serde(default)]
+    pub magnitude_unit: Option<String>, // e.g. "kts" or "acres"
+    #[serde(default)]
+    pub closed: Option<String>, // when the event ended; None while open
+    #[serde(default)]
+    pub track: Vec<EventGeometry>, // every observation, oldest first
+}
+
+/// One dated observation of an event. `latitude`/`longitude` are the point
+/// itself or the centre of `shape` for polygon observations.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventGeometry {
+    pub date: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub magnitude_value: Option<f64>,
+    pub magnitude_unit: Option<String>,
+    pub shape: Option<WatchShape>,
 }
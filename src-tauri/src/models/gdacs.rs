@@ -11,5 +11,46 @@ pub struct GdacsAlert {
     pub longitude: f64,
     pub pub_date: String,
     pub link: String,
-    pub country: String,
+    pub country: String, // as given by GDACS; empty for most offshore events
+    #[serde(default)]
+    pub place: Option<String>, // nearest gazetteer place, e.g. "120 km SE of Suva, Fiji"
+    #[serde(default)]
+    pub event_id: String,
+    #[serde(default)]
+    pub episode_id: Option<String>,
+    #[serde(default)]
+    pub severity_value: Option<f64>, // e.g. wind speed or magnitude
+    #[serde(default)]
+    pub severity_unit: Option<String>, // e.g. "km/h" or "M"
+    #[serde(default)]
+    pub severity_text: String,
+    #[serde(default)]
+    pub population: Option<f64>, // people exposed
+    #[serde(default)]
+    pub population_text: String,
+    #[serde(default)]
+    pub from_date: String,
+    #[serde(default)]
+    pub to_date: String,
+    // Alerts cached before this field existed were all current
+    #[serde(default = "default_true")]
+    pub is_current: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Alert level and impact of one GDACS episode, recorded as episodes arrive
+/// so escalations can be traced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GdacsEpisode {
+    pub episode_id: String,
+    pub alert_level: String,
+    pub severity_value: Option<f64>,
+    pub severity_unit: Option<String>,
+    pub population: Option<f64>,
+    pub from_date: String,
+    pub to_date: String,
+    pub recorded_at: i64,
 }
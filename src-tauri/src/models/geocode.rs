@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Place {
+    pub name: String,
+    pub country: String,
+    pub country_code: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub population: Option<u64>,
+    pub source: String, // "gazetteer", "nominatim" or "coordinates"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaceLabel {
+    pub label: String, // e.g. "42 km NE of Catania, Italy"
+    pub place: Place,
+    pub distance_km: f64,
+}
@@ -1,15 +1,23 @@
 pub mod air_quality;
+pub mod alert_rule;
 pub mod asteroid;
+pub mod channel;
 pub mod earthquake;
 pub mod eonet;
 pub mod gdacs;
+pub mod geocode;
 pub mod iss;
 pub mod meteor;
+pub mod notification;
+pub mod observer;
 pub mod plate;
 pub mod satellite;
 pub mod solar;
 pub mod solar_event;
+pub mod tsunami;
 pub mod sst;
+pub mod vaac;
 pub mod volcano;
 pub mod watchlist;
 pub mod weather;
+pub mod weather_alert;
@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationRecord {
+    pub id: i64,
+    pub source: String,   // event type, e.g. "earthquake", "kp", "gdacs"
+    pub event_id: String, // id of the event in its feed
+    pub rule: String,     // what fired, e.g. "magnitude_threshold" or "rule:Big quakes"
+    pub title: String,
+    pub body: String,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub suppressed: bool, // recorded without a toast (snoozed, quiet hours, rate limit, digest)
+    pub held: bool,       // waiting to be summarised in the next digest
+    pub severity: Option<f64>, // magnitude or Kp, used to pick the strongest event in a digest
+    pub acknowledged_at: Option<i64>,
+    pub created_at: i64,
+}
+
+/// Circle used to acknowledge or snooze notifications by area.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationRegion {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub radius_km: f64,
+}
+
+/// Silences toasts matching `source` and `region` until `until`. A `None`
+/// field matches everything.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationSnooze {
+    pub id: i64,
+    pub source: Option<String>,
+    pub region: Option<NotificationRegion>,
+    pub until: i64,
+    pub created_at: i64,
+}
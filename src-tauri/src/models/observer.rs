@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObserverProfile {
+    pub id: i64,
+    pub name: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub elevation_m: f64,
+    pub timezone: String, // IANA name, e.g. "America/Los_Angeles"
+    pub is_active: bool,
+    pub created_at: i64,
+}
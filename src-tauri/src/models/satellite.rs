@@ -9,6 +9,8 @@ pub struct SatellitePosition {
     pub altitude_km: f64,
     pub velocity_kmh: f64,
     pub timestamp: i64,
+    pub footprint_radius_km: f64,
+    pub footprint: Vec<[f64; 2]>, // [lat, lon] horizon circle
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +23,17 @@ pub struct OrbitTrack {
 pub struct SatelliteData {
     pub positions: Vec<SatellitePosition>,
     pub orbits: Vec<OrbitTrack>,
+    pub warnings: Vec<TleWarning>,
+}
+
+/// Raised when a position was propagated from an element set older than the configured limit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TleWarning {
+    pub satellite_id: String,
+    pub name: String,
+    pub tle_epoch: i64, // unix seconds
+    pub age_hours: f64,
+    pub message: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,5 +44,132 @@ pub struct PassPrediction {
     pub end_time: i64,
     pub max_elevation: f64, // degrees
     pub start_azimuth: f64, // degrees
+    pub max_azimuth: f64,   // degrees, at max elevation
+    pub end_azimuth: f64,   // degrees
     pub is_visible: bool,
+    /// Forecast cloud cover at the observer when the pass starts.
+    #[serde(default)]
+    pub cloud_cover_pct: Option<f64>,
+}
+
+impl PassPrediction {
+    /// Identifier that survives re-prediction: the satellite and AOS to the minute.
+    pub fn event_key(&self) -> String {
+        format!("{}-{}", self.satellite_id, self.start_time.div_euclid(60) * 60)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwathSample {
+    pub timestamp: i64,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub radius_km: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverageWindow {
+    pub enter_time: i64, // unix seconds
+    pub exit_time: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SatelliteCoverage {
+    pub satellite_id: String,
+    pub name: String,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub min_elevation: f64,
+    pub swath: Vec<SwathSample>,
+    pub left_edge: Vec<[f64; 2]>,  // [lat, lon]
+    pub right_edge: Vec<[f64; 2]>, // [lat, lon]
+    pub windows: Vec<CoverageWindow>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Conjunction {
+    pub id: String,
+    pub primary_id: String,
+    pub primary_name: String,
+    pub secondary_id: String,
+    pub secondary_name: String,
+    pub tca: i64, // unix seconds, time of closest approach
+    pub miss_distance_km: f64,
+    pub relative_velocity_kms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TleRecord {
+    pub norad_id: String,
+    pub name: String,
+    pub epoch: i64, // unix seconds
+    pub line1: String,
+    pub line2: String,
+}
+
+/// An externally published reentry prediction (Space-Track TIP or decay message).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecayPrediction {
+    pub norad_id: String,
+    pub name: Option<String>,
+    pub source: String,
+    pub decay_epoch: i64,         // unix seconds
+    pub window_mins: Option<f64>, // +/- uncertainty
+    pub message_epoch: Option<i64>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub inclination: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecayStatus {
+    pub satellite_id: String,
+    pub name: String,
+    pub perigee_km: f64,
+    pub apogee_km: f64,
+    pub decay_rate_km_per_day: f64,
+    pub history_points: usize,
+    pub estimated_reentry: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReentryWindow {
+    pub satellite_id: String,
+    pub name: String,
+    pub source: String, // "tip", "decay" or "estimate"
+    pub predicted_time: i64,
+    pub window_start: i64,
+    pub window_end: i64,
+    pub latitude_band: [f64; 2],         // [min, max] latitudes the ground track spans
+    pub ground_track: Vec<[f64; 2]>,     // [lat, lon] across the window
+    pub predicted_point: Option<[f64; 2]>, // [lat, lon] from the prediction message
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecayReport {
+    pub objects: Vec<DecayStatus>,
+    pub reentries: Vec<ReentryWindow>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TleHistoryPoint {
+    pub epoch: i64, // unix seconds
+    pub semi_major_axis_km: f64,
+    pub perigee_km: f64,
+    pub apogee_km: f64,
+    pub mean_altitude_km: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Maneuver {
+    pub epoch: i64,          // epoch of the first element set after the burn
+    pub delta_sma_km: f64,   // positive for reboosts
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TleHistory {
+    pub satellite_id: String,
+    pub name: String,
+    pub points: Vec<TleHistoryPoint>,
+    pub maneuvers: Vec<Maneuver>,
 }
@@ -43,6 +43,27 @@ pub struct SolarFlare {
     pub source_location: Option<String>,
 }
 
+impl SolarFlare {
+    /// Peak 1-8 Å X-ray flux in W/m² from the GOES class, e.g. "X2.1" is
+    /// 2.1e-4. None for classes that don't parse.
+    pub fn peak_flux(&self) -> Option<f64> {
+        let class = self.class_type.trim();
+        let scale = match class.chars().next()?.to_ascii_uppercase() {
+            'A' => 1e-8,
+            'B' => 1e-7,
+            'C' => 1e-6,
+            'M' => 1e-5,
+            'X' => 1e-4,
+            _ => return None,
+        };
+        let multiplier = match class[1..].trim() {
+            "" => 1.0,
+            digits => digits.parse::<f64>().ok()?,
+        };
+        Some(scale * multiplier)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CoronalMassEjection {
     pub id: String,
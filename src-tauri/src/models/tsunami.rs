@@ -0,0 +1,42 @@
+use crate::models::watchlist::WatchShape;
+use serde::{Deserialize, Serialize};
+
+/// Bulletin from a NOAA tsunami warning centre (NTWC in Palmer, PTWC in Honolulu).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TsunamiBulletin {
+    pub id: String,
+    pub center: String,       // "NTWC" or "PTWC"
+    pub threat_level: String, // "warning", "advisory", "watch", "threat" or "information"
+    pub title: String,
+    pub headline: String,
+    pub issued: String, // RFC 3339
+    pub latitude: f64,  // epicentre of the originating quake
+    pub longitude: f64,
+    pub magnitude: Option<f64>,
+    pub affected_region: String,
+    pub zones: Vec<TsunamiZone>,
+    pub arrivals: Vec<TsunamiArrival>,
+    pub cap_url: Option<String>,
+    pub earthquake_id: Option<String>, // matching USGS event, if one is cached
+}
+
+/// Area under threat from one CAP `<area>` block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TsunamiZone {
+    pub description: String,
+    pub shapes: Vec<WatchShape>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TsunamiArrival {
+    pub location: String,
+    pub time: String, // as written in the bulletin, e.g. "1102 HST MAR 11"
+}
+
+impl TsunamiBulletin {
+    /// Warnings, advisories, watches and threat messages call for action;
+    /// information statements do not.
+    pub fn is_actionable(&self) -> bool {
+        self.threat_level != "information"
+    }
+}
@@ -0,0 +1,33 @@
+use crate::models::watchlist::WatchShape;
+use serde::{Deserialize, Serialize};
+
+/// Volcanic ash advisory from a Volcanic Ash Advisory Centre (VAAC).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AshAdvisory {
+    pub id: String,
+    pub vaac: String,
+    pub volcano_name: String,
+    pub volcano_number: Option<String>, // Smithsonian number, when given
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub issued: String, // RFC 3339
+    pub advisory_number: String,
+    pub aviation_color: Option<String>,
+    pub eruption_details: String,
+    pub clouds: Vec<AshCloud>,
+    pub remarks: String,
+    pub next_advisory: Option<String>,
+    pub volcano_id: Option<String>, // matching `Volcano`, if one is known
+}
+
+/// One observed or forecast ash cloud layer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AshCloud {
+    pub kind: String,     // "observed" or "forecast"
+    pub hours_ahead: u32, // 0 for observed clouds, else 6, 12 or 18
+    pub valid_time: Option<String>,
+    pub base_fl: u32, // flight level, 0 for the surface
+    pub top_fl: u32,
+    pub shape: WatchShape,
+    pub movement: Option<String>, // e.g. "E 10KT"
+}
@@ -2,11 +2,74 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Volcano {
-    pub id: String,
+    pub id: String, // Smithsonian volcano number where known
     pub name: String,
     pub latitude: f64,
     pub longitude: f64,
-    pub status: String,
+    pub status: String, // "normal", "advisory", "watch" or "warning"
     pub last_eruption: String,
     pub description: String,
+    #[serde(default)]
+    pub aviation_color: Option<String>, // "green", "yellow", "orange" or "red"
+    #[serde(default)]
+    pub observatory: Option<String>,
+    #[serde(default)]
+    pub activity: Option<String>, // text of the latest GVP weekly report
+    #[serde(default)]
+    pub report_url: Option<String>,
+    #[serde(default)]
+    pub updated: Option<String>,
+    #[serde(default)]
+    pub sources: Vec<String>, // "gvp" and/or "usgs"
+}
+
+impl Volcano {
+    /// Whether the status and colour code are an observatory's official alert
+    /// level. Volcanoes only in the GVP weekly report get a status derived
+    /// from the report, which is not comparable with official levels.
+    pub fn has_official_status(&self) -> bool {
+        self.sources.iter().any(|s| s == "usgs")
+    }
+
+    /// Source recorded with this volcano's status history.
+    pub fn status_source(&self) -> &'static str {
+        if self.has_official_status() {
+            "usgs"
+        } else {
+            "gvp"
+        }
+    }
+}
+
+/// Status recorded whenever a volcano's alert level or colour code changes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolcanoStatusChange {
+    pub status: String,
+    pub aviation_color: Option<String>,
+    pub source: String, // "usgs" for official levels, "gvp" for derived ones
+    pub recorded_at: i64,
+}
+
+// USGS Volcano Hazards Notification System (HANS) elevated-volcano response
+
+#[derive(Debug, Deserialize)]
+pub struct HansVolcano {
+    #[serde(alias = "vName")]
+    pub volcano_name: String,
+    #[serde(default)]
+    pub vnum: Option<String>,
+    #[serde(alias = "alertLevel")]
+    pub alert_level: String,
+    #[serde(default, alias = "colorCode")]
+    pub color_code: Option<String>,
+    #[serde(default, alias = "lat")]
+    pub latitude: Option<f64>,
+    #[serde(default, alias = "long", alias = "lng")]
+    pub longitude: Option<f64>,
+    #[serde(default)]
+    pub obs_fullname: Option<String>,
+    #[serde(default)]
+    pub sent_utc: Option<String>,
+    #[serde(default)]
+    pub notice_url: Option<String>,
 }
@@ -4,8 +4,77 @@ use serde::{Deserialize, Serialize};
 pub struct Watchlist {
     pub id: i64,
     pub name: String,
-    pub latitude: f64,
+    pub latitude: f64,  // shape centre
     pub longitude: f64,
-    pub radius_km: f64,
+    pub radius_km: f64, // enclosing radius for non-circle shapes
+    pub shape: WatchShape,
+    pub rules: WatchRules,
     pub created_at: i64,
 }
+
+/// Area a watchlist covers. Coordinates in lists are GeoJSON-ordered [lon, lat].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WatchShape {
+    Circle {
+        latitude: f64,
+        longitude: f64,
+        radius_km: f64,
+    },
+    Polygon {
+        rings: Vec<Vec<[f64; 2]>>, // outer ring first, then holes
+    },
+    MultiPolygon {
+        polygons: Vec<Vec<Vec<[f64; 2]>>>,
+    },
+    BoundingBox {
+        min_lon: f64, // greater than max_lon when crossing the antimeridian
+        min_lat: f64,
+        max_lon: f64,
+        max_lat: f64,
+    },
+    Corridor {
+        line: Vec<[f64; 2]>,
+        buffer_km: f64,
+    },
+}
+
+/// What a watchlist alerts on. Missing fields in stored JSON fall back to the defaults.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchRules {
+    pub earthquakes: bool,
+    pub min_magnitude: Option<f64>,
+    pub max_depth_km: Option<f64>,
+    pub gdacs_min_level: Option<String>, // "green", "orange" or "red"; None disables
+    pub eonet_categories: Vec<String>,   // EONET category ids, e.g. "wildfires"
+    pub volcano_status_changes: bool,
+    pub weather_warnings: bool, // CAP warnings whose area overlaps the watchlist; opt-in
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl Default for WatchRules {
+    fn default() -> Self {
+        WatchRules {
+            earthquakes: true,
+            min_magnitude: None,
+            max_depth_km: None,
+            gdacs_min_level: None,
+            eonet_categories: Vec::new(),
+            volcano_status_changes: false,
+            weather_warnings: false,
+            quiet_hours: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: String, // "HH:MM" local time
+    pub end: String,
+    /// IANA zone, e.g. "Europe/Berlin"; None follows the active observer
+    /// profile. Older rules stored a fixed `utc_offset_minutes`, which is
+    /// ignored because it breaks across daylight saving changes.
+    #[serde(default)]
+    pub timezone: Option<String>,
+}
@@ -29,3 +29,92 @@ pub struct Weather {
     pub wind_direction: f64,
     pub humidity_pct: f64,
 }
+
+/// Open-Meteo forecast requested with `timeformat=unixtime`. Every series
+/// is parallel to its `time` array; values can be null at the end of the
+/// forecast range.
+#[derive(Debug, Deserialize)]
+pub struct OpenMeteoForecastResponse {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub hourly: HourlySeries,
+    pub daily: DailySeries,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct HourlySeries {
+    pub time: Vec<i64>,
+    pub temperature_2m: Vec<Option<f64>>,
+    pub precipitation: Vec<Option<f64>>,
+    pub precipitation_probability: Vec<Option<f64>>,
+    pub cloud_cover: Vec<Option<f64>>,
+    pub visibility: Vec<Option<f64>>,
+    pub wind_speed_10m: Vec<Option<f64>>,
+    pub wind_gusts_10m: Vec<Option<f64>>,
+    pub pressure_msl: Vec<Option<f64>>,
+    pub weather_code: Vec<Option<u32>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DailySeries {
+    pub time: Vec<i64>,
+    pub weather_code: Vec<Option<u32>>,
+    pub temperature_2m_max: Vec<Option<f64>>,
+    pub temperature_2m_min: Vec<Option<f64>>,
+    pub precipitation_sum: Vec<Option<f64>>,
+    pub precipitation_probability_max: Vec<Option<f64>>,
+    pub wind_gusts_10m_max: Vec<Option<f64>>,
+    pub sunrise: Vec<Option<i64>>,
+    pub sunset: Vec<Option<i64>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HourlyForecast {
+    pub time: i64, // unix seconds
+    pub temperature_c: Option<f64>,
+    pub precipitation_mm: Option<f64>,
+    pub precipitation_probability: Option<f64>,
+    pub cloud_cover_pct: Option<f64>,
+    pub visibility_m: Option<f64>,
+    pub wind_speed_kmh: Option<f64>,
+    pub wind_gusts_kmh: Option<f64>,
+    pub pressure_hpa: Option<f64>,
+    pub weather_code: Option<u32>,
+    pub weather_description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyForecast {
+    pub date: i64, // unix seconds, start of the day in UTC
+    pub weather_code: Option<u32>,
+    pub weather_description: Option<String>,
+    pub temperature_max_c: Option<f64>,
+    pub temperature_min_c: Option<f64>,
+    pub precipitation_mm: Option<f64>,
+    pub precipitation_probability: Option<f64>,
+    pub wind_gusts_max_kmh: Option<f64>,
+    pub sunrise: Option<i64>,
+    pub sunset: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Forecast {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub hourly: Vec<HourlyForecast>,
+    pub daily: Vec<DailyForecast>,
+}
+
+/// Sky conditions at an observer for one moment, from the nearest forecast
+/// hour.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalConditions {
+    pub time: i64,
+    pub cloud_cover_pct: Option<f64>,
+    pub visibility_m: Option<f64>,
+    pub precipitation_probability: Option<f64>,
+    pub weather_description: Option<String>,
+    pub summary: String,
+}
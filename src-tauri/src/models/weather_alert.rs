@@ -0,0 +1,70 @@
+use crate::models::watchlist::WatchShape;
+use serde::{Deserialize, Serialize};
+
+/// One CAP 1.2 `<info>` block (or Atom entry with CAP fields) from a
+/// national warning service such as the NWS or MeteoAlarm.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeatherAlert {
+    pub id: String,
+    pub feed_url: String,
+    pub sender: Option<String>,
+    pub event: String,
+    pub headline: String,
+    pub description: String,
+    pub instruction: Option<String>,
+    pub msg_type: String,     // "alert", "update" or "cancel"
+    pub severity: String,     // "extreme", "severe", "moderate", "minor" or "unknown"
+    pub urgency: String,      // "immediate", "expected", "future", "past" or "unknown"
+    pub certainty: String,    // "observed", "likely", "possible", "unlikely" or "unknown"
+    pub sent: Option<String>, // RFC 3339
+    pub onset: Option<String>,
+    pub expires: Option<String>,
+    pub areas: Vec<WeatherAlertArea>,
+    pub web: Option<String>,
+    #[serde(default)]
+    pub references: Vec<String>, // identifiers of the messages an update or cancel replaces
+}
+
+/// A CAP `<area>`. Geocode-only areas (counties, EMMA regions) have no
+/// shapes and are listed but not located.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeatherAlertArea {
+    pub description: String,
+    pub shapes: Vec<WatchShape>,
+    pub geocodes: Vec<Geocode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Geocode {
+    pub name: String, // e.g. "UGC", "FIPS6", "EMMA_ID"
+    pub value: String,
+}
+
+impl WeatherAlert {
+    /// Unix seconds of `expires`, when present and parseable.
+    pub fn expires_at(&self) -> Option<i64> {
+        let expires = self.expires.as_deref()?;
+        chrono::DateTime::parse_from_rfc3339(expires)
+            .ok()
+            .map(|t| t.timestamp())
+    }
+
+    /// Still in force at `now` (unix seconds): not cancelled and not expired.
+    /// Alerts without an expiry stay active while their feed lists them.
+    pub fn is_active(&self, now: i64) -> bool {
+        self.msg_type != "cancel" && self.expires_at().is_none_or(|t| t > now)
+    }
+
+    /// Message identifier without the feed's URL prefix, so Atom entry ids
+    /// ("https://api.weather.gov/alerts/urn:oid:...") compare equal to the
+    /// CAP identifiers in `references` ("urn:oid:...").
+    pub fn message_id(id: &str) -> &str {
+        id.rsplit('/').next().unwrap_or(id)
+    }
+
+    /// Warnings proper, as opposed to advisories and statements.
+    pub fn is_warning(&self) -> bool {
+        matches!(self.severity.as_str(), "extreme" | "severe")
+            || self.event.to_ascii_lowercase().contains("warning")
+    }
+}
@@ -1,55 +1,242 @@
-use crate::db::Database;
+use crate::calculations::rule_engine::RuleEvent;
+use crate::calculations::{alert_policy, geometry, watch_rules};
+use crate::commands::alert_rule;
+use crate::db::{Database, UserSettings};
+use crate::delivery;
 use crate::models::asteroid::Asteroid;
 use crate::models::earthquake::Earthquake;
-use crate::models::satellite::PassPrediction;
+use crate::models::eonet::NaturalEvent;
+use crate::models::gdacs::{GdacsAlert, GdacsEpisode};
+use crate::models::notification::{NotificationRecord, NotificationRegion, NotificationSnooze};
+use crate::models::observer::ObserverProfile;
+use crate::models::satellite::{Conjunction, PassPrediction};
 use crate::models::solar_event::SolarActivity;
-use std::collections::HashSet;
+use crate::models::tsunami::TsunamiBulletin;
+use crate::models::volcano::{Volcano, VolcanoStatusChange};
+use crate::models::watchlist::{WatchShape, Watchlist};
+use crate::models::weather::LocalConditions;
+use crate::models::weather_alert::WeatherAlert;
+use std::collections::BTreeMap;
 use std::sync::Mutex;
-use tauri::AppHandle;
+use tauri::{AppHandle, Manager};
 use tauri_plugin_notification::NotificationExt;
 
+/// In-memory state for alerts that depend on the previous reading rather than
+/// on event ids. Per-event dedup lives in the database so it survives restarts.
 pub struct NotificationTracker {
-    notified_quake_ids: Mutex<HashSet<String>>,
-    notified_asteroid_ids: Mutex<HashSet<String>>,
-    notified_flare_ids: Mutex<HashSet<String>>,
-    notified_cme_cycle: Mutex<bool>,
     last_kp_notified: Mutex<Option<f64>>,
-    last_pass_notified: Mutex<Option<i64>>,
 }
 
 impl NotificationTracker {
     pub fn new() -> Self {
         Self {
-            notified_quake_ids: Mutex::new(HashSet::new()),
-            notified_asteroid_ids: Mutex::new(HashSet::new()),
-            notified_flare_ids: Mutex::new(HashSet::new()),
-            notified_cme_cycle: Mutex::new(false),
             last_kp_notified: Mutex::new(None),
-            last_pass_notified: Mutex::new(None),
         }
     }
 }
 
+/// What a notification is about, before it is shown and recorded.
+struct Notice<'a> {
+    source: &'a str,
+    event_id: &'a str,
+    rule: &'a str,
+    title: String,
+    body: String,
+    location: Option<(f64, f64)>,
+    critical: bool, // breaks through quiet hours, rate limits and digests
+    severity: Option<f64>,
+}
+
+/// Notifications are held for at most this long when digest mode is off.
+const DEFAULT_DIGEST_MINUTES: f64 = 60.0;
+
+/// A conjunction of the same pair whose TCA moved by at most this much
+/// between screenings is the same event.
+const CONJUNCTION_TCA_TOLERANCE_SECS: i64 = 1800;
+
+/// Show a toast and forward to the alert channels unless a snooze covers it
+/// or it is held for the next digest, and record it in the history either way.
+fn notify(app: &AppHandle, notice: Notice) {
+    let db = app.state::<Database>();
+    let now = chrono::Utc::now();
+    let snoozed = db
+        .get_active_snoozes(now.timestamp())
+        .iter()
+        .any(|s| snooze_covers(s, notice.source, notice.location));
+    let held = !snoozed && !notice.critical && should_hold(&db, notice.source, now);
+
+    publish(
+        app,
+        &db,
+        NotificationRecord {
+            id: 0,
+            source: notice.source.to_string(),
+            event_id: notice.event_id.to_string(),
+            rule: notice.rule.to_string(),
+            title: notice.title,
+            body: notice.body,
+            latitude: notice.location.map(|l| l.0),
+            longitude: notice.location.map(|l| l.1),
+            suppressed: snoozed || held,
+            held,
+            severity: notice.severity,
+            acknowledged_at: None,
+            created_at: now.timestamp(),
+        },
+    );
+}
+
+/// Record a notification, and unless it is suppressed show the toast and
+/// send it to the enabled alert channels.
+fn publish(app: &AppHandle, db: &Database, record: NotificationRecord) {
+    if !record.suppressed {
+        app.notification()
+            .builder()
+            .title(&record.title)
+            .body(&record.body)
+            .show()
+            .ok();
+    }
+
+    let record = match db.add_notification(&record) {
+        Ok(id) => NotificationRecord { id, ..record },
+        Err(e) => {
+            log::error!("Failed to record notification: {}", e);
+            record
+        }
+    };
+
+    if !record.suppressed {
+        let channels: Vec<_> = db
+            .get_alert_channels()
+            .into_iter()
+            .filter(|c| c.enabled)
+            .collect();
+        if !channels.is_empty() {
+            tauri::async_runtime::spawn(delivery::dispatch(channels, record));
+        }
+    }
+}
+
+/// Digest mode, quiet hours and the per-source rate limit all hold
+/// notifications back for the next digest.
+fn should_hold(db: &Database, source: &str, now: chrono::DateTime<chrono::Utc>) -> bool {
+    let settings = db.get_settings();
+    if settings.digest_minutes.is_some_and(|m| m > 0.0) || quiet_hours_now(db, &settings, now) {
+        return true;
+    }
+    settings
+        .rate_limit_per_hour
+        .filter(|limit| *limit > 0.0)
+        .is_some_and(|limit| {
+            db.count_shown_notifications(source, now.timestamp() - 3600) as f64 >= limit
+        })
+}
+
+/// Quiet hours are read in the active observer profile's timezone.
+fn quiet_hours_now(
+    db: &Database,
+    settings: &UserSettings,
+    now: chrono::DateTime<chrono::Utc>,
+) -> bool {
+    let (Some(start), Some(end)) = (&settings.quiet_hours_start, &settings.quiet_hours_end) else {
+        return false;
+    };
+    alert_policy::quiet_hours_active(start, end, &observer_timezone(db), now)
+}
+
+/// IANA timezone of the active observer profile, which quiet hours are read
+/// in unless a watchlist names its own.
+fn observer_timezone(db: &Database) -> String {
+    db.get_active_observer_profile()
+        .map(|o| o.timezone)
+        .unwrap_or_else(|| "UTC".to_string())
+}
+
+/// Summarise held notifications, one toast per source, once the oldest has
+/// waited a full digest window and quiet hours are over.
+pub fn flush_digest(app: &AppHandle) {
+    let db = app.state::<Database>();
+    let held = db.get_held_notifications();
+    let Some(oldest) = held.first().map(|r| r.created_at) else {
+        return;
+    };
+    let now = chrono::Utc::now();
+    let settings = db.get_settings();
+    let window = settings
+        .digest_minutes
+        .filter(|m| *m > 0.0)
+        .unwrap_or(DEFAULT_DIGEST_MINUTES);
+    if ((now.timestamp() - oldest) as f64) < window * 60.0 || quiet_hours_now(&db, &settings, now) {
+        return;
+    }
+
+    let ids: Vec<i64> = held.iter().map(|r| r.id).collect();
+    let mut by_source: BTreeMap<String, Vec<NotificationRecord>> = BTreeMap::new();
+    for record in held {
+        by_source
+            .entry(record.source.clone())
+            .or_default()
+            .push(record);
+    }
+    // Release first so a failure can't send the same digest every minute
+    if let Err(e) = db.release_held_notifications(&ids) {
+        log::error!("Failed to release held notifications: {}", e);
+        return;
+    }
+
+    let snoozes = db.get_active_snoozes(now.timestamp());
+    for (source, records) in by_source {
+        let (title, body) = alert_policy::digest_summary(&source, &records, now.timestamp());
+        let event_ids: Vec<&str> = records.iter().map(|r| r.event_id.as_str()).collect();
+        publish(
+            app,
+            &db,
+            NotificationRecord {
+                id: 0,
+                event_id: event_ids.join(","),
+                rule: "digest".to_string(),
+                title,
+                body,
+                latitude: None,
+                longitude: None,
+                suppressed: snoozes.iter().any(|s| snooze_covers(s, &source, None)),
+                held: false,
+                severity: records.iter().filter_map(|r| r.severity).reduce(f64::max),
+                acknowledged_at: None,
+                created_at: now.timestamp(),
+                source,
+            },
+        );
+    }
+}
+
+/// Claim a dedup key; false means this event was already notified about.
+fn claim(app: &AppHandle, key: &str) -> bool {
+    app.state::<Database>().claim_notification_key(key)
+}
+
+fn snooze_covers(snooze: &NotificationSnooze, source: &str, location: Option<(f64, f64)>) -> bool {
+    let source_matches = snooze.source.as_deref().is_none_or(|s| s == source);
+    let region_matches = match &snooze.region {
+        None => true,
+        Some(region) => location.is_some_and(|loc| in_region(region, loc)),
+    };
+    source_matches && region_matches
+}
+
+pub fn in_region(region: &NotificationRegion, (lat, lon): (f64, f64)) -> bool {
+    haversine_km(region.latitude, region.longitude, lat, lon) <= region.radius_km
+}
+
 pub fn check_earthquake_notifications(
     app: &AppHandle,
-    tracker: &NotificationTracker,
     quakes: &[Earthquake],
     min_magnitude: f64,
-    user_lat: f64,
-    user_lon: f64,
+    observers: &[ObserverProfile],
     proximity_km: f64,
 ) {
     let now = chrono::Utc::now().timestamp_millis();
-    let mut notified = tracker.notified_quake_ids.lock().unwrap();
-
-    // Prune old IDs (keep only IDs of quakes from the last hour)
-    let one_hour_ago = now - 3600 * 1000;
-    let current_quake_ids: HashSet<String> = quakes
-        .iter()
-        .filter(|q| q.time > one_hour_ago)
-        .map(|q| q.id.clone())
-        .collect();
-    notified.retain(|id| current_quake_ids.contains(id));
 
     for quake in quakes {
         // Only notify for quakes in the last 5 minutes
@@ -57,36 +244,69 @@ pub fn check_earthquake_notifications(
             continue;
         }
 
-        // Skip if already notified
-        if notified.contains(&quake.id) {
-            continue;
-        }
-
-        let distance = haversine_km(user_lat, user_lon, quake.latitude, quake.longitude);
+        // Closest observer profile decides whether the quake counts as nearby
+        let nearest = observers
+            .iter()
+            .map(|o| {
+                (
+                    o,
+                    haversine_km(o.latitude, o.longitude, quake.latitude, quake.longitude),
+                )
+            })
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        let nearby = nearest.filter(|(_, distance)| *distance <= proximity_km);
 
-        let should_notify = quake.magnitude >= min_magnitude
-            || (quake.magnitude >= 3.0 && distance <= proximity_km);
+        let should_notify =
+            quake.magnitude >= min_magnitude || (quake.magnitude >= 3.0 && nearby.is_some());
 
-        if should_notify {
-            let title = if distance <= proximity_km {
-                format!("Nearby Earthquake M{:.1}", quake.magnitude)
+        if should_notify && claim(app, &format!("quake:{}", quake.id)) {
+            let title = if let Some((observer, _)) = nearby {
+                if observers.len() > 1 {
+                    format!(
+                        "Nearby Earthquake M{:.1} ({})",
+                        quake.magnitude, observer.name
+                    )
+                } else {
+                    format!("Nearby Earthquake M{:.1}", quake.magnitude)
+                }
             } else {
                 format!("Earthquake M{:.1}", quake.magnitude)
             };
 
-            app.notification()
-                .builder()
-                .title(&title)
-                .body(&quake.place)
-                .show()
-                .ok();
-
-            notified.insert(quake.id.clone());
+            notify(
+                app,
+                Notice {
+                    source: "earthquake",
+                    event_id: &quake.id,
+                    rule: if nearby.is_some() {
+                        "proximity"
+                    } else {
+                        "magnitude_threshold"
+                    },
+                    title,
+                    body: quake.place.clone(),
+                    location: Some((quake.latitude, quake.longitude)),
+                    critical: is_critical_quake(quake),
+                    severity: Some(quake.magnitude),
+                },
+            );
         }
     }
 }
 
-pub fn check_kp_notification(app: &AppHandle, tracker: &NotificationTracker, kp: f64) {
+/// Tsunami-flagged quakes and PAGER red alerts are never held back.
+fn is_critical_quake(quake: &Earthquake) -> bool {
+    quake.tsunami || quake.alert.as_deref() == Some("red")
+}
+
+/// `sky` is the current forecast at the active observer, so the alert can
+/// say whether there is any chance of seeing the aurora.
+pub fn check_kp_notification(
+    app: &AppHandle,
+    tracker: &NotificationTracker,
+    kp: f64,
+    sky: Option<&LocalConditions>,
+) {
     let mut last_kp = tracker.last_kp_notified.lock().unwrap();
 
     if kp >= 5.0 {
@@ -97,12 +317,25 @@ pub fn check_kp_notification(app: &AppHandle, tracker: &NotificationTracker, kp:
         };
 
         if should_notify {
-            app.notification()
-                .builder()
-                .title(&format!("Geomagnetic Storm - Kp {:.0}", kp))
-                .body("Aurora may be visible at lower latitudes")
-                .show()
-                .ok();
+            notify(
+                app,
+                Notice {
+                    source: "kp",
+                    event_id: &format!("{:.1}", kp),
+                    rule: "geomagnetic_storm",
+                    title: format!("Geomagnetic Storm - Kp {:.0}", kp),
+                    body: match sky {
+                        Some(sky) => format!(
+                            "Aurora may be visible at lower latitudes. Sky here: {}",
+                            sky.summary
+                        ),
+                        None => "Aurora may be visible at lower latitudes".to_string(),
+                    },
+                    location: None,
+                    critical: false,
+                    severity: Some(kp),
+                },
+            );
             *last_kp = Some(kp);
         }
     } else {
@@ -111,13 +344,8 @@ pub fn check_kp_notification(app: &AppHandle, tracker: &NotificationTracker, kp:
     }
 }
 
-pub fn check_pass_notification(
-    app: &AppHandle,
-    tracker: &NotificationTracker,
-    passes: &[PassPrediction],
-) {
+pub fn check_pass_notification(app: &AppHandle, passes: &[PassPrediction]) {
     let now = chrono::Utc::now().timestamp();
-    let mut last_notified = tracker.last_pass_notified.lock().unwrap();
 
     // Find next visible pass within 15 minutes
     let upcoming = passes
@@ -126,145 +354,382 @@ pub fn check_pass_notification(
 
     if let Some(pass) = upcoming {
         // Don't re-notify for the same pass
-        if *last_notified == Some(pass.start_time) {
+        let event_id = pass.event_key();
+        if !claim(app, &format!("pass:{}", event_id)) {
             return;
         }
 
         let mins = (pass.start_time - now) / 60;
         let direction = azimuth_to_cardinal(pass.start_azimuth);
+        let mut body = format!(
+            "Max elevation: {:.0}°, look {}",
+            pass.max_elevation, direction
+        );
+        if let Some(cover) = pass.cloud_cover_pct {
+            body.push_str(&format!(", {:.0}% cloud forecast", cover));
+        }
 
-        app.notification()
-            .builder()
-            .title(&format!("{} Pass in {}min", pass.name, mins))
-            .body(&format!(
-                "Max elevation: {:.0}°, look {}",
-                pass.max_elevation, direction
-            ))
-            .show()
-            .ok();
-
-        *last_notified = Some(pass.start_time);
+        notify(
+            app,
+            Notice {
+                source: "pass",
+                event_id: &event_id,
+                rule: "visible_pass",
+                title: format!("{} Pass in {}min", pass.name, mins),
+                body,
+                location: None,
+                critical: false,
+                severity: None,
+            },
+        );
     }
 }
 
-pub fn check_asteroid_notification(
-    app: &AppHandle,
-    tracker: &NotificationTracker,
-    asteroids: &[Asteroid],
-) {
-    let mut notified = tracker.notified_asteroid_ids.lock().unwrap();
+pub fn check_asteroid_notification(app: &AppHandle, asteroids: &[Asteroid]) {
     let now_ms = chrono::Utc::now().timestamp_millis();
 
-    // Prune IDs for asteroids no longer in the dataset
-    let current_ids: HashSet<String> = asteroids.iter().map(|a| a.id.clone()).collect();
-    notified.retain(|id| current_ids.contains(id));
-
     for asteroid in asteroids {
-        if asteroid.is_hazardous && asteroid.miss_distance_lunar < 20.0 {
-            // Only notify for approaches within next 48 hours
-            if asteroid.approach_time > now_ms
-                && asteroid.approach_time - now_ms < 172_800_000
-                && !notified.contains(&asteroid.id)
-            {
-                app.notification()
-                    .builder()
-                    .title(&format!(
-                        "Hazardous Asteroid: {}",
-                        asteroid.name.replace(['(', ')'], "")
-                    ))
-                    .body(&format!(
-                        "Close approach at {:.1} lunar distances ({:.0} km)",
-                        asteroid.miss_distance_lunar, asteroid.miss_distance_km
-                    ))
-                    .show()
-                    .ok();
-
-                notified.insert(asteroid.id.clone());
-            }
+        // A hazardous flyby is worth a heads-up; a Sentry entry means JPL has
+        // not ruled out an impact, whatever the distance of this approach
+        let close_hazard = asteroid.is_hazardous && asteroid.miss_distance_lunar < 20.0;
+        if !close_hazard && asteroid.impact_risk.is_none() {
+            continue;
         }
+        // Only notify for approaches within next 48 hours
+        if asteroid.approach_time <= now_ms
+            || asteroid.approach_time - now_ms >= 172_800_000
+            || !claim(app, &format!("asteroid:{}", asteroid.id))
+        {
+            continue;
+        }
+
+        let name = asteroid.name.replace(['(', ')'], "");
+        let approach = format!(
+            "Close approach at {:.1} lunar distances ({:.0} km)",
+            asteroid.miss_distance_lunar, asteroid.miss_distance_km
+        );
+        let (rule, title, body) = match &asteroid.impact_risk {
+            Some(risk) => (
+                "impact_risk",
+                format!("Asteroid with impact risk: {}", name),
+                format!(
+                    "Impact probability {} over {} (Palermo {:.2}, Torino {}). {}",
+                    format_probability(risk.impact_probability),
+                    risk.years,
+                    risk.palermo_cumulative,
+                    risk.torino_max,
+                    approach
+                ),
+            ),
+            None => (
+                "hazardous_approach",
+                format!("Hazardous Asteroid: {}", name),
+                format!("{}. No known impact risk", approach),
+            ),
+        };
+        notify(
+            app,
+            Notice {
+                source: "asteroid",
+                event_id: &asteroid.id,
+                rule,
+                title,
+                body,
+                location: None,
+                // Torino 5 and above is a "threatening" event
+                critical: asteroid
+                    .impact_risk
+                    .as_ref()
+                    .is_some_and(|r| r.torino_max >= 5),
+                severity: asteroid.impact_risk.as_ref().map(|r| r.palermo_cumulative),
+            },
+        );
     }
 }
 
-pub fn check_solar_flare_notification(
-    app: &AppHandle,
-    tracker: &NotificationTracker,
-    activity: &SolarActivity,
-) {
-    let mut notified_flares = tracker.notified_flare_ids.lock().unwrap();
-
-    // Prune IDs for flares no longer in the dataset
-    let current_flare_ids: HashSet<String> = activity.flares.iter().map(|f| f.id.clone()).collect();
-    notified_flares.retain(|id| current_flare_ids.contains(id));
+/// "1 in 37,000" style odds for small probabilities.
+fn format_probability(p: f64) -> String {
+    if p <= 0.0 || !p.is_finite() {
+        return "unknown".to_string();
+    }
+    if p >= 0.01 {
+        return format!("{:.1}%", p * 100.0);
+    }
+    let odds = (1.0 / p).round() as u64;
+    let digits = odds.to_string();
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    format!("1 in {}", grouped)
+}
 
+pub fn check_solar_flare_notification(app: &AppHandle, activity: &SolarActivity) {
     // Notify on M-class or X-class flares
     for flare in &activity.flares {
         if (flare.class_type.starts_with('X') || flare.class_type.starts_with('M'))
-            && !notified_flares.contains(&flare.id)
+            && claim(app, &format!("flare:{}", flare.id))
         {
             let peak_display = &flare.peak_time[..16.min(flare.peak_time.len())];
-            app.notification()
-                .builder()
-                .title(&format!("Solar Flare: {}", flare.class_type))
-                .body(&format!(
-                    "Peak time: {}{}",
-                    peak_display,
-                    flare
-                        .source_location
-                        .as_ref()
-                        .map(|s| format!(" at {}", s))
-                        .unwrap_or_default()
-                ))
-                .show()
-                .ok();
-
-            notified_flares.insert(flare.id.clone());
+            notify(
+                app,
+                Notice {
+                    source: "flare",
+                    event_id: &flare.id,
+                    rule: "flare_class",
+                    title: format!("Solar Flare: {}", flare.class_type),
+                    body: format!(
+                        "Peak time: {}{}",
+                        peak_display,
+                        flare
+                            .source_location
+                            .as_ref()
+                            .map(|s| format!(" at {}", s))
+                            .unwrap_or_default()
+                    ),
+                    location: None,
+                    critical: false,
+                    severity: None,
+                },
+            );
             break; // Only one notification per update cycle
         }
     }
-    drop(notified_flares);
 
-    // Notify on earth-directed CMEs (once per batch)
-    let earth_cmes: Vec<_> = activity
+    // Notify once per batch for earth-directed CMEs not seen before
+    let new_cmes: Vec<_> = activity
         .cmes
         .iter()
         .filter(|c| c.is_earth_directed)
+        .filter(|c| claim(app, &format!("cme:{}", c.id)))
         .collect();
-    let mut cme_notified = tracker.notified_cme_cycle.lock().unwrap();
-    if !earth_cmes.is_empty() && !*cme_notified {
-        let speed_text = earth_cmes
-            .first()
-            .and_then(|c| c.speed_kps)
-            .map(|s| format!(" at {:.0} km/s", s))
-            .unwrap_or_default();
-        app.notification()
-            .builder()
-            .title("Earth-Directed CME Detected")
-            .body(&format!(
+    if new_cmes.is_empty() {
+        return;
+    }
+    let batch_id = new_cmes
+        .iter()
+        .map(|c| c.id.as_str())
+        .collect::<Vec<_>>()
+        .join(",");
+    let speed_text = new_cmes
+        .first()
+        .and_then(|c| c.speed_kps)
+        .map(|s| format!(" at {:.0} km/s", s))
+        .unwrap_or_default();
+    notify(
+        app,
+        Notice {
+            source: "cme",
+            event_id: &batch_id,
+            rule: "earth_directed_cme",
+            title: "Earth-Directed CME Detected".to_string(),
+            body: format!(
                 "{} CME(s) headed toward Earth{}",
-                earth_cmes.len(),
+                new_cmes.len(),
                 speed_text
-            ))
-            .show()
-            .ok();
-        *cme_notified = true;
-    } else if earth_cmes.is_empty() {
-        *cme_notified = false;
+            ),
+            location: None,
+            critical: false,
+            severity: None,
+        },
+    );
+}
+
+pub fn check_conjunction_notification(app: &AppHandle, conjunctions: &[Conjunction]) {
+    let now = chrono::Utc::now().timestamp();
+
+    for c in conjunctions {
+        let db = app.state::<Database>();
+        if c.tca < now
+            || !db.claim_notification_key_near(
+                &format!("conjunction:{}", c.id),
+                c.tca,
+                CONJUNCTION_TCA_TOLERANCE_SECS,
+            )
+        {
+            continue;
+        }
+
+        let tca_display = chrono::DateTime::from_timestamp(c.tca, 0)
+            .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_default();
+
+        notify(
+            app,
+            Notice {
+                source: "conjunction",
+                event_id: &c.id,
+                rule: "conjunction_threshold",
+                title: format!("Conjunction: {} / {}", c.primary_name, c.secondary_name),
+                body: format!(
+                    "Miss distance {:.2} km at {}, relative velocity {:.1} km/s",
+                    c.miss_distance_km, tca_display, c.relative_velocity_kms
+                ),
+                location: None,
+                critical: false,
+                severity: None,
+            },
+        );
     }
 }
 
-pub fn check_watchlist_notifications(
+/// High-priority alert when an observer location lies inside a threat zone
+/// of a tsunami warning, advisory or watch.
+pub fn check_tsunami_notification(
     app: &AppHandle,
-    tracker: &NotificationTracker,
-    quakes: &[Earthquake],
+    bulletins: &[TsunamiBulletin],
+    observers: &[ObserverProfile],
+) {
+    for bulletin in bulletins.iter().filter(|b| b.is_actionable()) {
+        for observer in observers {
+            let Some(zone) = bulletin.zones.iter().find(|z| {
+                z.shapes
+                    .iter()
+                    .any(|s| geometry::contains(s, observer.latitude, observer.longitude))
+            }) else {
+                continue;
+            };
+            if !claim(app, &format!("tsunami:{}:{}", bulletin.id, observer.id)) {
+                continue;
+            }
+
+            let arrival = bulletin
+                .arrivals
+                .first()
+                .map(|a| format!(". First arrival {} at {}", a.time, a.location))
+                .unwrap_or_default();
+            let level = bulletin.threat_level.as_str();
+            notify(
+                app,
+                Notice {
+                    source: "tsunami",
+                    event_id: &bulletin.id,
+                    rule: "threat_zone",
+                    title: format!(
+                        "Tsunami {}{} - {}",
+                        level[..1].to_ascii_uppercase(),
+                        &level[1..],
+                        observer.name
+                    ),
+                    body: format!("{}: {}{}", zone.description, bulletin.headline, arrival),
+                    location: Some((observer.latitude, observer.longitude)),
+                    critical: true,
+                    severity: bulletin.magnitude,
+                },
+            );
+        }
+    }
+}
+
+/// Notify when a CAP warning area covers an observer location or overlaps a
+/// watchlist. Alerts located only by geocodes can't be matched and are
+/// skipped.
+pub fn check_weather_alert_notifications(
+    app: &AppHandle,
+    alerts: &[WeatherAlert],
+    observers: &[ObserverProfile],
     db: &Database,
 ) {
+    let watchlists = db.get_watchlists();
+    let now = chrono::Utc::now();
+    let timezone = observer_timezone(db);
+
+    for alert in alerts
+        .iter()
+        .filter(|a| a.is_warning() && a.is_active(now.timestamp()))
+    {
+        let until = alert
+            .expires
+            .as_deref()
+            .map(|t| format!(" until {}", t))
+            .unwrap_or_default();
+
+        for observer in observers {
+            let Some(area) = alert.areas.iter().find(|area| {
+                area.shapes
+                    .iter()
+                    .any(|s| geometry::contains(s, observer.latitude, observer.longitude))
+            }) else {
+                continue;
+            };
+            if !claim_weather_alert(db, &format!("observer:{}", observer.id), alert) {
+                continue;
+            }
+            notify(
+                app,
+                Notice {
+                    source: "weather_alert",
+                    event_id: &alert.id,
+                    rule: "observer_area",
+                    title: format!("{} - {}", alert.event, observer.name),
+                    body: format!("{}: {}{}", area.description, alert.headline, until),
+                    location: Some((observer.latitude, observer.longitude)),
+                    critical: alert.severity == "extreme",
+                    severity: None,
+                },
+            );
+        }
+
+        let shapes: Vec<&WatchShape> = alert.areas.iter().flat_map(|a| &a.shapes).collect();
+        for wl in &watchlists {
+            if !wl.rules.weather_warnings
+                || watch_rules::in_quiet_hours(&wl.rules, &timezone, now)
+                || !shapes.iter().any(|s| geometry::overlaps(s, &wl.shape))
+                || !claim_weather_alert(db, &format!("wl:{}", wl.id), alert)
+            {
+                continue;
+            }
+            notify(
+                app,
+                Notice {
+                    source: "weather_alert",
+                    event_id: &alert.id,
+                    rule: &format!("watchlist:{}", wl.name),
+                    title: format!("{} in watchlist \"{}\"", alert.event, wl.name),
+                    body: format!("{}{}", alert.headline, until),
+                    location: Some((wl.latitude, wl.longitude)),
+                    critical: alert.severity == "extreme",
+                    severity: None,
+                },
+            );
+        }
+    }
+}
+
+/// Claim an alert for one recipient; false when it, or the alert it updates,
+/// was notified before. NWS issues a new identifier for every Update, so
+/// updates are tied to the original through the CAP references, or by event
+/// and area when the feed leaves those out.
+fn claim_weather_alert(db: &Database, recipient: &str, alert: &WeatherAlert) -> bool {
+    let claim =
+        |key: &str| db.claim_notification_key(&format!("weather_alert:{}:{}", recipient, key));
+    let areas: Vec<&str> = alert.areas.iter().map(|a| a.description.as_str()).collect();
+    let event_area = format!("{}|{}", alert.event, areas.join(";"));
+
+    // Every key is claimed, so later updates find the whole chain
+    let new_message = claim(WeatherAlert::message_id(&alert.id));
+    let new_event_area = claim(&event_area);
+    let mut new_chain = true;
+    for reference in &alert.references {
+        new_chain &= claim(WeatherAlert::message_id(reference));
+    }
+    match alert.msg_type.as_str() {
+        "update" if alert.references.is_empty() => new_message && new_event_area,
+        _ => new_message && new_chain,
+    }
+}
+
+pub fn check_watchlist_notifications(app: &AppHandle, quakes: &[Earthquake], db: &Database) {
     let now = chrono::Utc::now().timestamp_millis();
-    let mut notified = tracker.notified_quake_ids.lock().unwrap();
     let watchlists = db.get_watchlists();
 
     if watchlists.is_empty() {
         return;
     }
+    let timezone = observer_timezone(db);
 
     for quake in quakes {
         // Only check quakes from the last 5 minutes
@@ -272,32 +737,346 @@ pub fn check_watchlist_notifications(
             continue;
         }
 
-        let watchlist_key = format!("wl:{}", quake.id);
-        if notified.contains(&watchlist_key) {
+        // Quakes suppressed by quiet hours are not replayed afterwards
+        if !db.claim_notification_key(&format!("wl:quake:{}", quake.id)) {
             continue;
         }
 
+        let quiet_now = chrono::Utc::now();
         for wl in &watchlists {
-            let distance = haversine_km(wl.latitude, wl.longitude, quake.latitude, quake.longitude);
-            if distance <= wl.radius_km {
-                app.notification()
-                    .builder()
-                    .title(&format!(
-                        "M{:.1} in watchlist \"{}\"",
-                        quake.magnitude, wl.name
-                    ))
-                    .body(&format!("{} ({:.0}km from center)", quake.place, distance))
-                    .show()
-                    .ok();
-
-                notified.insert(watchlist_key.clone());
-                break; // One notification per quake
+            if !geometry::contains(&wl.shape, quake.latitude, quake.longitude)
+                || !watch_rules::earthquake_matches(&wl.rules, quake.magnitude, quake.depth)
+                || watch_rules::in_quiet_hours(&wl.rules, &timezone, quiet_now)
+            {
+                continue;
+            }
+            let body = match wl.shape {
+                WatchShape::Circle { .. } => {
+                    let distance =
+                        haversine_km(wl.latitude, wl.longitude, quake.latitude, quake.longitude);
+                    format!("{} ({:.0}km from center)", quake.place, distance)
+                }
+                _ => quake.place.clone(),
+            };
+            notify(
+                app,
+                Notice {
+                    source: "earthquake",
+                    event_id: &quake.id,
+                    rule: &format!("watchlist:{}", wl.name),
+                    title: format!("M{:.1} in watchlist \"{}\"", quake.magnitude, wl.name),
+                    body,
+                    location: Some((quake.latitude, quake.longitude)),
+                    critical: is_critical_quake(quake),
+                    severity: Some(quake.magnitude),
+                },
+            );
+            break; // One notification per quake
+        }
+    }
+}
+
+/// Notify for GDACS alerts inside a watchlist at or above its minimum level.
+pub fn check_watchlist_gdacs(app: &AppHandle, alerts: &[GdacsAlert], db: &Database) {
+    let watchlists = db.get_watchlists();
+    let now = chrono::Utc::now();
+    let timezone = observer_timezone(db);
+
+    for alert in alerts {
+        let level = alert.severity.to_ascii_lowercase();
+        for wl in matching_watchlists(
+            &watchlists,
+            (alert.latitude, alert.longitude),
+            now,
+            &timezone,
+            |wl| watch_rules::gdacs_matches(&wl.rules, &alert.severity),
+        ) {
+            // Claimed only once the event is inside the watchlist, so one
+            // seen earlier elsewhere still notifies; a higher level re-notifies
+            if !db.claim_notification_key(&format!("wl:gdacs:{}:{}:{}", alert.id, level, wl.id)) {
+                continue;
+            }
+            notify(
+                app,
+                Notice {
+                    source: "gdacs",
+                    event_id: &alert.id,
+                    rule: &format!("watchlist:{}", wl.name),
+                    title: format!("{} alert in watchlist \"{}\"", alert.severity, wl.name),
+                    body: alert.title.clone(),
+                    location: Some((alert.latitude, alert.longitude)),
+                    critical: level == "red",
+                    severity: None,
+                },
+            );
+        }
+    }
+}
+
+/// Record each alert's episode and notify when an event's alert level rises
+/// above that of every earlier episode.
+pub fn check_gdacs_escalation(app: &AppHandle, alerts: &[GdacsAlert], db: &Database) {
+    for alert in alerts {
+        let episodes = db.get_gdacs_episodes(&alert.id);
+        if !db.add_gdacs_episode(alert) {
+            continue;
+        }
+        let Some(old) = gdacs_escalated_from(&episodes, alert) else {
+            continue;
+        };
+        let level = alert.severity.to_ascii_lowercase();
+        if !claim(app, &format!("gdacs:escalation:{}:{}", alert.id, level)) {
+            continue;
+        }
+
+        let exposure = if alert.population_text.is_empty() {
+            String::new()
+        } else {
+            format!(". {}", alert.population_text)
+        };
+        notify(
+            app,
+            Notice {
+                source: "gdacs",
+                event_id: &alert.id,
+                rule: "escalation",
+                title: format!(
+                    "GDACS alert raised from {} to {}",
+                    old.alert_level, alert.severity
+                ),
+                body: format!("{}{}", alert.title, exposure),
+                location: Some((alert.latitude, alert.longitude)),
+                critical: level == "red",
+                severity: None,
+            },
+        );
+    }
+}
+
+/// The highest-level earlier episode when `alert` raises the level above it.
+/// The first episode seen is a baseline, not an escalation.
+fn gdacs_escalated_from<'a>(
+    episodes: &'a [GdacsEpisode],
+    alert: &GdacsAlert,
+) -> Option<&'a GdacsEpisode> {
+    let (old_rank, old) = episodes
+        .iter()
+        .filter_map(|e| watch_rules::gdacs_level_rank(&e.alert_level).map(|r| (r, e)))
+        .max_by_key(|(rank, _)| *rank)?;
+    let new_rank = watch_rules::gdacs_level_rank(&alert.severity)?;
+    (new_rank > old_rank).then_some(old)
+}
+
+/// Record volcano status changes and notify, if volcano notifications are on,
+/// when the alert level or aviation colour code rises. The first status seen
+/// for a volcano is a baseline.
+pub fn check_volcano_escalation(app: &AppHandle, volcanoes: &[Volcano], db: &Database) {
+    let enabled = db.get_settings().notify_volcanoes.unwrap_or(true);
+
+    for volcano in volcanoes {
+        let previous = db.get_last_volcano_status(&volcano.id, volcano.status_source());
+        if !db.add_volcano_status(volcano) {
+            continue;
+        }
+        let Some(old) = previous else {
+            continue;
+        };
+        if !enabled || !volcano_escalated(&old, volcano) {
+            continue;
+        }
+        let color = volcano.aviation_color.as_deref().unwrap_or("none");
+        if !claim(
+            app,
+            &format!(
+                "volcano:escalation:{}:{}:{}",
+                volcano.id, volcano.status, color
+            ),
+        ) {
+            continue;
+        }
+
+        let change = match (&old.aviation_color, &volcano.aviation_color) {
+            (Some(from), Some(to)) if from != to => format!(
+                "{} to {}, aviation code {} to {}",
+                old.status, volcano.status, from, to
+            ),
+            _ => format!("{} to {}", old.status, volcano.status),
+        };
+        notify(
+            app,
+            Notice {
+                source: "volcano",
+                event_id: &volcano.id,
+                rule: "escalation",
+                title: format!("{} alert raised", volcano.name),
+                body: match &volcano.activity {
+                    Some(activity) => format!("Raised from {}. {}", change, activity),
+                    None => format!("Raised from {}", change),
+                },
+                location: Some((volcano.latitude, volcano.longitude)),
+                critical: volcano.status == "warning" || color == "red",
+                severity: None,
+            },
+        );
+    }
+}
+
+/// Whether the official alert level or aviation colour code rose. Statuses
+/// derived from the GVP weekly report never count, on either side.
+fn volcano_escalated(old: &VolcanoStatusChange, volcano: &Volcano) -> bool {
+    if old.source != "usgs" || !volcano.has_official_status() {
+        return false;
+    }
+    let rank = |level: &str, color: Option<&str>| {
+        (
+            watch_rules::volcano_level_rank(level).unwrap_or(0),
+            color
+                .and_then(watch_rules::aviation_color_rank)
+                .unwrap_or(0),
+        )
+    };
+    let (old_level, old_color) = rank(&old.status, old.aviation_color.as_deref());
+    let (new_level, new_color) = rank(&volcano.status, volcano.aviation_color.as_deref());
+    new_level > old_level || new_color > old_color
+}
+
+/// Notify for EONET events inside a watchlist in one of its categories.
+pub fn check_watchlist_eonet(app: &AppHandle, events: &[NaturalEvent], db: &Database) {
+    let watchlists = db.get_watchlists();
+    let now = chrono::Utc::now();
+    let timezone = observer_timezone(db);
+
+    for event in events {
+        for wl in matching_watchlists(
+            &watchlists,
+            (event.latitude, event.longitude),
+            now,
+            &timezone,
+            |wl| watch_rules::eonet_matches(&wl.rules, &event.category_id),
+        ) {
+            // Claimed only once the event is inside the watchlist, so one
+            // that moves in later still notifies
+            if !db.claim_notification_key(&format!("wl:eonet:{}:{}", event.id, wl.id)) {
+                continue;
             }
+            notify(
+                app,
+                Notice {
+                    source: "eonet",
+                    event_id: &event.id,
+                    rule: &format!("watchlist:{}", wl.name),
+                    title: format!("{} in watchlist \"{}\"", event.category, wl.name),
+                    body: event.title.clone(),
+                    location: Some((event.latitude, event.longitude)),
+                    critical: false,
+                    severity: None,
+                },
+            );
+        }
+    }
+}
+
+/// Notify when a volcano inside a watchlist changes status, judged against
+/// the status history so changes survive restarts and cache cleanup.
+pub fn check_watchlist_volcanoes(app: &AppHandle, volcanoes: &[Volcano], db: &Database) {
+    let watchlists = db.get_watchlists();
+    let now = chrono::Utc::now();
+    let timezone = observer_timezone(db);
+    for volcano in volcanoes {
+        // Compared against the last status recorded from the same source, so
+        // this must run before check_volcano_escalation records the new one,
+        // and a GVP fallback during a HANS outage is not taken for a change.
+        // A volcano without history only gets its baseline recorded there.
+        let Some(old) = db.get_last_volcano_status(&volcano.id, volcano.status_source()) else {
+            continue;
+        };
+        if old.status.eq_ignore_ascii_case(&volcano.status) {
+            continue;
+        }
+        let Some(wl) = matching_watchlists(
+            &watchlists,
+            (volcano.latitude, volcano.longitude),
+            now,
+            &timezone,
+            |wl| wl.rules.volcano_status_changes,
+        )
+        .next() else {
+            continue;
+        };
+        if !db.claim_notification_key(&format!(
+            "wl:volcano:{}:{}:{}",
+            volcano.id, old.recorded_at, volcano.status
+        )) {
+            continue;
         }
+        notify(
+            app,
+            Notice {
+                source: "volcano",
+                event_id: &volcano.id,
+                rule: &format!("watchlist:{}", wl.name),
+                title: format!("{} in watchlist \"{}\"", volcano.name, wl.name),
+                body: format!("Status changed from {} to {}", old.status, volcano.status),
+                location: Some((volcano.latitude, volcano.longitude)),
+                critical: false,
+                severity: None,
+            },
+        );
     }
 }
 
-fn azimuth_to_cardinal(az: f64) -> &'static str {
+/// Watchlists containing the point whose rules accept the event and which
+/// are not in their quiet hours.
+fn matching_watchlists<'a>(
+    watchlists: &'a [Watchlist],
+    (lat, lon): (f64, f64),
+    now: chrono::DateTime<chrono::Utc>,
+    timezone: &'a str,
+    accepts: impl Fn(&Watchlist) -> bool + 'a,
+) -> impl Iterator<Item = &'a Watchlist> + 'a {
+    watchlists.iter().filter(move |wl| {
+        accepts(wl)
+            && !watch_rules::in_quiet_hours(&wl.rules, timezone, now)
+            && geometry::contains(&wl.shape, lat, lon)
+    })
+}
+
+/// Evaluate the user's alert rules against a batch of events. Each rule fires
+/// at most once per event.
+pub fn check_alert_rules(app: &AppHandle, events: &[RuleEvent], db: &Database) {
+    if events.is_empty() {
+        return;
+    }
+    let rules = alert_rule::enabled_rules(db);
+    let locations = alert_rule::rule_locations(db);
+
+    for event in events {
+        for (rule, compiled) in &rules {
+            if !compiled.matches(event, &locations) {
+                continue;
+            }
+            let key = format!("rule:{}:{}:{}", rule.id, event.kind.as_str(), event.id);
+            if !db.claim_notification_key(&key) {
+                continue;
+            }
+            notify(
+                app,
+                Notice {
+                    source: event.kind.as_str(),
+                    event_id: &event.id,
+                    rule: &format!("rule:{}", rule.name),
+                    title: format!("{}: {}", rule.name, event.title),
+                    body: event.body.clone(),
+                    location: event.location,
+                    critical: false,
+                    severity: None,
+                },
+            );
+        }
+    }
+}
+
+pub fn azimuth_to_cardinal(az: f64) -> &'static str {
     let az = ((az % 360.0) + 360.0) % 360.0;
     match az as u32 {
         0..=22 | 338..=360 => "N",
@@ -325,7 +1104,13 @@ fn haversine_km(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
 
 #[cfg(test)]
 mod tests {
-    use super::{azimuth_to_cardinal, haversine_km};
+    use super::{
+        azimuth_to_cardinal, format_probability, gdacs_escalated_from, haversine_km, snooze_covers,
+        volcano_escalated,
+    };
+    use crate::models::gdacs::{GdacsAlert, GdacsEpisode};
+    use crate::models::notification::{NotificationRegion, NotificationSnooze};
+    use crate::models::volcano::{Volcano, VolcanoStatusChange};
 
     #[test]
     fn azimuth_to_cardinal_boundaries() {
@@ -337,6 +1122,134 @@ mod tests {
         assert_eq!(azimuth_to_cardinal(359.0), "N");
     }
 
+    #[test]
+    fn impact_probability_reads_as_odds() {
+        assert_eq!(format_probability(2.7e-5), "1 in 37,037");
+        assert_eq!(format_probability(1.0 / 850.0), "1 in 850");
+        assert_eq!(format_probability(0.031), "3.1%");
+        assert_eq!(format_probability(0.0), "unknown");
+    }
+
+    fn episode(id: &str, level: &str) -> GdacsEpisode {
+        GdacsEpisode {
+            episode_id: id.to_string(),
+            alert_level: level.to_string(),
+            severity_value: None,
+            severity_unit: None,
+            population: None,
+            from_date: String::new(),
+            to_date: String::new(),
+            recorded_at: 0,
+        }
+    }
+
+    fn gdacs_alert(episode_id: &str, level: &str) -> GdacsAlert {
+        serde_json::from_value(serde_json::json!({
+            "id": "TC1001",
+            "title": "Tropical Cyclone",
+            "description": "",
+            "alert_type": "TC",
+            "severity": level,
+            "latitude": 15.0,
+            "longitude": 125.0,
+            "pub_date": "",
+            "link": "",
+            "country": "",
+            "episode_id": episode_id,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn gdacs_escalation_needs_a_higher_level() {
+        // The first episode is only a baseline
+        assert!(gdacs_escalated_from(&[], &gdacs_alert("1", "Orange")).is_none());
+
+        let episodes = [episode("1", "Green")];
+        let old = gdacs_escalated_from(&episodes, &gdacs_alert("2", "Orange")).unwrap();
+        assert_eq!(old.alert_level, "Green");
+        // A new episode at the same level is not an escalation
+        assert!(gdacs_escalated_from(&episodes, &gdacs_alert("2", "green")).is_none());
+    }
+
+    #[test]
+    fn gdacs_escalation_compares_with_the_peak_episode() {
+        let episodes = [
+            episode("1", "Green"),
+            episode("2", "Red"),
+            episode("3", "Orange"),
+        ];
+        // Returning to red after an orange episode is not a new escalation
+        assert!(gdacs_escalated_from(&episodes, &gdacs_alert("4", "Red")).is_none());
+
+        let episodes = [episode("1", "Green"), episode("2", "Orange")];
+        let old = gdacs_escalated_from(&episodes, &gdacs_alert("3", "Red")).unwrap();
+        assert_eq!(old.episode_id, "2");
+    }
+
+    #[test]
+    fn gdacs_alerts_cached_without_is_current_are_current() {
+        assert!(gdacs_alert("1", "Green").is_current);
+    }
+
+    fn volcano(status: &str, color: Option<&str>, sources: &[&str]) -> Volcano {
+        Volcano {
+            id: "332010".to_string(),
+            name: "Kilauea".to_string(),
+            latitude: 19.42,
+            longitude: -155.29,
+            status: status.to_string(),
+            last_eruption: String::new(),
+            description: String::new(),
+            aviation_color: color.map(String::from),
+            observatory: None,
+            activity: None,
+            report_url: None,
+            updated: None,
+            sources: sources.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn recorded(status: &str, color: Option<&str>, source: &str) -> VolcanoStatusChange {
+        VolcanoStatusChange {
+            status: status.to_string(),
+            aviation_color: color.map(String::from),
+            source: source.to_string(),
+            recorded_at: 0,
+        }
+    }
+
+    #[test]
+    fn volcano_escalation_uses_official_levels_only() {
+        let official = recorded("advisory", Some("yellow"), "usgs");
+        assert!(volcano_escalated(
+            &official,
+            &volcano("watch", Some("orange"), &["gvp", "usgs"])
+        ));
+        assert!(volcano_escalated(
+            &official,
+            &volcano("advisory", Some("orange"), &["usgs"])
+        ));
+        assert!(!volcano_escalated(
+            &official,
+            &volcano("advisory", Some("yellow"), &["usgs"])
+        ));
+
+        // "watch" from the weekly report outranks nothing
+        assert!(!volcano_escalated(
+            &recorded("advisory", None, "gvp"),
+            &volcano("watch", None, &["gvp"])
+        ));
+        assert!(!volcano_escalated(
+            &official,
+            &volcano("watch", None, &["gvp"])
+        ));
+        assert!(!volcano_escalated(
+            &recorded("watch", None, "gvp"),
+            &volcano("warning", Some("red"), &["gvp", "usgs"])
+        ));
+    }
+
     #[test]
     fn haversine_is_stable() {
         let sf_to_sf = haversine_km(37.7749, -122.4194, 37.7749, -122.4194);
@@ -345,4 +1258,31 @@ mod tests {
         let sf_to_ny = haversine_km(37.7749, -122.4194, 40.7128, -74.0060);
         assert!(sf_to_ny > 4100.0 && sf_to_ny < 4200.0);
     }
+
+    #[test]
+    fn snoozes_match_by_source_and_region() {
+        let snooze = NotificationSnooze {
+            id: 1,
+            source: Some("earthquake".to_string()),
+            region: Some(NotificationRegion {
+                latitude: 35.68,
+                longitude: 139.69,
+                radius_km: 500.0,
+            }),
+            until: 0,
+            created_at: 0,
+        };
+        assert!(snooze_covers(&snooze, "earthquake", Some((36.0, 140.0))));
+        assert!(!snooze_covers(&snooze, "earthquake", Some((-33.0, -71.0))));
+        assert!(!snooze_covers(&snooze, "gdacs", Some((36.0, 140.0))));
+        // Events without a position are outside every region
+        assert!(!snooze_covers(&snooze, "earthquake", None));
+
+        let everything = NotificationSnooze {
+            source: None,
+            region: None,
+            ..snooze
+        };
+        assert!(snooze_covers(&everything, "kp", None));
+    }
 }
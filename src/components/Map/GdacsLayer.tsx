@@ -61,8 +61,8 @@ export function GdacsLayer() {
                 {alert.severity} Alert
               </div>
               <div className="text-gray-400 text-xs">{alert.description.slice(0, 200)}</div>
-              {alert.country && (
-                <div className="text-xs text-gray-500">{alert.country}</div>
+              {(alert.country || alert.place) && (
+                <div className="text-xs text-gray-500">{alert.country || alert.place}</div>
               )}
               <div className="text-xs text-gray-500">{alert.pub_date}</div>
             </div>
@@ -55,9 +55,22 @@ export function VolcanoLayer() {
                   {v.status}
                 </span>
               </div>
-              <div className="text-gray-400">
-                Last eruption: {v.last_eruption}
-              </div>
+              {v.aviation_color && (
+                <div>
+                  Aviation code:{" "}
+                  <span className="font-semibold capitalize">
+                    {v.aviation_color}
+                  </span>
+                </div>
+              )}
+              {v.last_eruption && (
+                <div className="text-gray-400">
+                  Last eruption: {v.last_eruption}
+                </div>
+              )}
+              {v.activity && (
+                <div className="text-gray-400 max-w-[240px]">{v.activity}</div>
+              )}
             </div>
           </Popup>
         </Marker>
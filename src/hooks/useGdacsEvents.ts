@@ -25,7 +25,7 @@ export function useGdacsEvents() {
         id: `gdacs-${a.id}`,
         type: "hazard",
         title: a.title,
-        description: `${a.severity} ${a.alert_type} - ${a.country || "Global"}`,
+        description: `${a.severity} ${a.alert_type} - ${a.country || a.place || "Global"}`,
         latitude: a.latitude,
         longitude: a.longitude,
         time: a.pub_date ? new Date(a.pub_date).getTime() || Date.now() : Date.now(),
@@ -43,7 +43,7 @@ export function useGdacsEvents() {
       id: `gdacs-${a.id}`,
       type: "hazard",
       title: a.title,
-      description: `${a.severity} ${a.alert_type} - ${a.country || "Global"}`,
+      description: `${a.severity} ${a.alert_type} - ${a.country || a.place || "Global"}`,
       latitude: a.latitude,
       longitude: a.longitude,
       time: a.pub_date ? new Date(a.pub_date).getTime() || Date.now() : Date.now(),
@@ -10,6 +10,23 @@ export interface PersistedSettings {
   notify_volcanoes?: boolean;
   sonification_enabled?: boolean;
   ollama_model?: string;
+  notify_conjunctions?: boolean;
+  conjunction_days?: number;
+  conjunction_threshold_km?: number;
+  decay_source?: string;
+  tle_max_age_hours?: number;
+  iss_crosscheck?: boolean;
+  calendar_server_addr?: string;
+  geocoder_url?: string;
+  quiet_hours_start?: string;
+  quiet_hours_end?: string;
+  rate_limit_per_hour?: number;
+  digest_minutes?: number;
+  eonet_categories?: string[] | null; // null: the default categories; [] selects none
+  vaac_url?: string;
+  neo_lookahead_days?: number;
+  neo_max_distance_lunar?: number;
+  weather_alert_feeds?: string[] | null; // null: the NWS feed; [] turns weather alerts off
 }
 
 interface SettingsState {
@@ -5,6 +5,7 @@ export interface SourceHealthEvent {
   ok: boolean;
   timestamp_ms: number;
   error?: string | null;
+  discrepancy_km?: number | null;
 }
 
 interface SourceHealthState {
@@ -0,0 +1,7 @@
+export interface AlertRule {
+  id: number;
+  name: string;
+  expression: string;
+  enabled: boolean;
+  created_at: number;
+}
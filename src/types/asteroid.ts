@@ -1,3 +1,29 @@
+export interface AsteroidApproach {
+  date: string;
+  time: number;
+  velocity_kps: number;
+  miss_distance_km: number;
+  miss_distance_lunar: number;
+  orbiting_body: string;
+  source: "neows" | "jpl";
+}
+
+export interface OrbitClass {
+  code: string; // e.g. "APO"
+  name: string; // e.g. "Apollo"
+}
+
+/** JPL Sentry impact-risk entry; absent when no impact solutions are known. */
+export interface ImpactRisk {
+  designation: string;
+  impact_probability: number;
+  palermo_cumulative: number;
+  palermo_max: number;
+  torino_max: number;
+  potential_impacts: number;
+  years: string;
+}
+
 export interface Asteroid {
   id: string;
   name: string;
@@ -9,4 +35,20 @@ export interface Asteroid {
   velocity_kps: number;
   miss_distance_km: number;
   miss_distance_lunar: number;
+  orbiting_body: string;
+  absolute_magnitude_h?: number | null;
+  spk_id: string;
+  designation: string;
+  orbit_class?: OrbitClass | null;
+  approaches: AsteroidApproach[];
+  impact_risk?: ImpactRisk | null;
+}
+
+/** Options for `get_asteroids`; sort defaults to "time". */
+export interface AsteroidQuery {
+  sort?: "time" | "distance" | "size" | "velocity";
+  max_distance_lunar?: number;
+  min_diameter_km?: number;
+  min_velocity_kps?: number;
+  hazardous_only?: boolean;
 }
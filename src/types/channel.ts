@@ -0,0 +1,31 @@
+export type ChannelConfig =
+  | { type: "webhook"; url: string }
+  | { type: "slack"; webhook_url: string }
+  | { type: "discord"; webhook_url: string }
+  | { type: "ntfy"; server: string; topic: string; token?: string | null }
+  | { type: "gotify"; server: string; app_token: string }
+  | {
+      type: "email";
+      host: string;
+      port: number;
+      from: string;
+      to: string[];
+      username?: string | null;
+      password?: string | null;
+      security?: SmtpSecurity | null; // null: TLS on 465, plain for localhost, else STARTTLS
+    };
+
+export type SmtpSecurity = "tls" | "start_tls" | "none";
+
+// Secrets (webhook URLs, tokens, passwords) come back as this placeholder;
+// sending it back unchanged keeps the stored value.
+export const REDACTED = "********";
+
+export interface AlertChannel {
+  id: number;
+  name: string;
+  config: ChannelConfig;
+  template: string | null; // placeholders: {title} {body} {source} {rule} {event_id} {time} {location}
+  enabled: boolean;
+  created_at: number;
+}
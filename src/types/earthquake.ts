@@ -8,4 +8,5 @@ export interface Earthquake {
   time: number;
   tsunami: boolean;
   title: string;
+  alert?: "green" | "yellow" | "orange" | "red" | null;
 }
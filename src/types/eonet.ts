@@ -1,3 +1,14 @@
+import type { WatchShape } from "./watchlist";
+
+export interface EventGeometry {
+  date: string;
+  latitude: number;
+  longitude: number;
+  magnitude_value?: number | null;
+  magnitude_unit?: string | null;
+  shape?: WatchShape | null;
+}
+
 export interface NaturalEvent {
   id: string;
   title: string;
@@ -6,4 +17,16 @@ export interface NaturalEvent {
   latitude: number;
   longitude: number;
   date: string;
+  place?: string | null;
+  description?: string | null;
+  magnitude_value?: number | null;
+  magnitude_unit?: string | null;
+  closed?: string | null;
+  track: EventGeometry[];
+}
+
+export interface EonetCategoryInfo {
+  id: string;
+  title: string;
+  description?: string | null;
 }
@@ -9,4 +9,36 @@ export interface GdacsAlert {
   pub_date: string;
   link: string;
   country: string;
+  place?: string | null;
+  event_id: string;
+  episode_id: string | null;
+  severity_value: number | null;
+  severity_unit: string | null;
+  severity_text: string;
+  population: number | null;
+  population_text: string;
+  from_date: string;
+  to_date: string;
+  is_current: boolean;
 }
+
+export interface GdacsEpisode {
+  episode_id: string;
+  alert_level: string;
+  severity_value: number | null;
+  severity_unit: string | null;
+  population: number | null;
+  from_date: string;
+  to_date: string;
+  recorded_at: number;
+}
+
+/** `properties.kind` of features returned by `get_gdacs_geometry`. */
+export type GdacsGeometryKind =
+  | "track"
+  | "forecast_track"
+  | "wind_buffer"
+  | "intensity"
+  | "flood_area"
+  | "area"
+  | "point";
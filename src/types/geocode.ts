@@ -0,0 +1,15 @@
+export interface Place {
+  name: string;
+  country: string;
+  country_code: string;
+  latitude: number;
+  longitude: number;
+  population: number | null;
+  source: "gazetteer" | "nominatim" | "coordinates";
+}
+
+export interface PlaceLabel {
+  label: string;
+  place: Place;
+  distance_km: number;
+}
@@ -0,0 +1,29 @@
+export interface NotificationRecord {
+  id: number;
+  source: string;
+  event_id: string;
+  rule: string;
+  title: string;
+  body: string;
+  latitude: number | null;
+  longitude: number | null;
+  suppressed: boolean;
+  held: boolean;
+  severity: number | null;
+  acknowledged_at: number | null;
+  created_at: number;
+}
+
+export interface NotificationRegion {
+  latitude: number;
+  longitude: number;
+  radius_km: number;
+}
+
+export interface NotificationSnooze {
+  id: number;
+  source: string | null;
+  region: NotificationRegion | null;
+  until: number;
+  created_at: number;
+}
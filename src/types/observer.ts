@@ -0,0 +1,10 @@
+export interface ObserverProfile {
+  id: number;
+  name: string;
+  latitude: number;
+  longitude: number;
+  elevation_m: number;
+  timezone: string;
+  is_active: boolean;
+  created_at: number;
+}
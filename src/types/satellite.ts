@@ -6,6 +6,8 @@ export interface SatellitePosition {
   altitude_km: number;
   velocity_kmh: number;
   timestamp: number;
+  footprint_radius_km: number;
+  footprint: [number, number][];
 }
 
 export interface OrbitTrack {
@@ -16,6 +18,15 @@ export interface OrbitTrack {
 export interface SatelliteData {
   positions: SatellitePosition[];
   orbits: OrbitTrack[];
+  warnings: TleWarning[];
+}
+
+export interface TleWarning {
+  satellite_id: string;
+  name: string;
+  tle_epoch: number;
+  age_hours: number;
+  message: string;
 }
 
 export interface PassPrediction {
@@ -25,5 +36,90 @@ export interface PassPrediction {
   end_time: number;
   max_elevation: number;
   start_azimuth: number;
+  max_azimuth: number;
+  end_azimuth: number;
   is_visible: boolean;
+  cloud_cover_pct?: number | null;
+}
+
+export interface SwathSample {
+  timestamp: number;
+  latitude: number;
+  longitude: number;
+  radius_km: number;
+}
+
+export interface CoverageWindow {
+  enter_time: number;
+  exit_time: number;
+}
+
+export interface SatelliteCoverage {
+  satellite_id: string;
+  name: string;
+  start_time: number;
+  end_time: number;
+  min_elevation: number;
+  swath: SwathSample[];
+  left_edge: [number, number][];
+  right_edge: [number, number][];
+  windows: CoverageWindow[];
+}
+
+export interface Conjunction {
+  id: string;
+  primary_id: string;
+  primary_name: string;
+  secondary_id: string;
+  secondary_name: string;
+  tca: number;
+  miss_distance_km: number;
+  relative_velocity_kms: number;
+}
+
+export interface DecayStatus {
+  satellite_id: string;
+  name: string;
+  perigee_km: number;
+  apogee_km: number;
+  decay_rate_km_per_day: number;
+  history_points: number;
+  estimated_reentry: number | null;
+}
+
+export interface ReentryWindow {
+  satellite_id: string;
+  name: string;
+  source: "tip" | "decay" | "estimate";
+  predicted_time: number;
+  window_start: number;
+  window_end: number;
+  latitude_band: [number, number];
+  ground_track: [number, number][];
+  predicted_point: [number, number] | null;
+}
+
+export interface DecayReport {
+  objects: DecayStatus[];
+  reentries: ReentryWindow[];
+}
+
+export interface TleHistoryPoint {
+  epoch: number;
+  semi_major_axis_km: number;
+  perigee_km: number;
+  apogee_km: number;
+  mean_altitude_km: number;
+}
+
+export interface Maneuver {
+  epoch: number;
+  delta_sma_km: number;
+}
+
+export interface TleHistory {
+  satellite_id: string;
+  name: string;
+  points: TleHistoryPoint[];
+  maneuvers: Maneuver[];
 }
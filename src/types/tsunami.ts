@@ -0,0 +1,30 @@
+import type { WatchShape } from "./watchlist";
+
+export type TsunamiThreatLevel = "warning" | "advisory" | "watch" | "threat" | "information";
+
+export interface TsunamiZone {
+  description: string;
+  shapes: WatchShape[];
+}
+
+export interface TsunamiArrival {
+  location: string;
+  time: string;
+}
+
+export interface TsunamiBulletin {
+  id: string;
+  center: "NTWC" | "PTWC";
+  threat_level: TsunamiThreatLevel;
+  title: string;
+  headline: string;
+  issued: string;
+  latitude: number;
+  longitude: number;
+  magnitude: number | null;
+  affected_region: string;
+  zones: TsunamiZone[];
+  arrivals: TsunamiArrival[];
+  cap_url: string | null;
+  earthquake_id: string | null;
+}
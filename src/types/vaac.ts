@@ -0,0 +1,43 @@
+import type { WatchShape } from "./watchlist";
+
+export interface AshCloud {
+  kind: "observed" | "forecast";
+  hours_ahead: number;
+  valid_time?: string | null;
+  base_fl: number;
+  top_fl: number;
+  shape: WatchShape;
+  movement?: string | null;
+}
+
+export interface AshAdvisory {
+  id: string;
+  vaac: string;
+  volcano_name: string;
+  volcano_number?: string | null;
+  latitude?: number | null;
+  longitude?: number | null;
+  issued: string;
+  advisory_number: string;
+  aviation_color?: string | null;
+  eruption_details: string;
+  clouds: AshCloud[];
+  remarks: string;
+  next_advisory?: string | null;
+  volcano_id?: string | null;
+}
+
+/** Properties of each feature returned by `get_ash_layers`. */
+export interface AshLayerProperties {
+  layer: string; // "observed", "forecast_6h", "forecast_12h", ...
+  advisory_id: string;
+  vaac: string;
+  volcano_name: string;
+  volcano_id?: string | null;
+  issued: string;
+  aviation_color?: string | null;
+  valid_time?: string | null;
+  base_fl: number;
+  top_fl: number;
+  movement?: string | null;
+}
@@ -6,4 +6,17 @@ export interface Volcano {
   status: string;
   last_eruption: string;
   description: string;
+  aviation_color?: "green" | "yellow" | "orange" | "red" | null;
+  observatory?: string | null;
+  activity?: string | null;
+  report_url?: string | null;
+  updated?: string | null;
+  sources: ("gvp" | "usgs")[];
+}
+
+export interface VolcanoStatusChange {
+  status: string;
+  aviation_color?: string | null;
+  source: "gvp" | "usgs";
+  recorded_at: number;
 }
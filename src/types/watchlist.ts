@@ -1,8 +1,42 @@
+export type LonLat = [number, number];
+
+export type WatchShape =
+  | { type: "circle"; latitude: number; longitude: number; radius_km: number }
+  | { type: "polygon"; rings: LonLat[][] }
+  | { type: "multi_polygon"; polygons: LonLat[][][] }
+  | {
+      type: "bounding_box";
+      min_lon: number;
+      min_lat: number;
+      max_lon: number;
+      max_lat: number;
+    }
+  | { type: "corridor"; line: LonLat[]; buffer_km: number };
+
+export interface QuietHours {
+  start: string; // "HH:MM"
+  end: string;
+  timezone?: string | null; // IANA zone; defaults to the active observer's
+}
+
+export interface WatchRules {
+  earthquakes: boolean;
+  min_magnitude: number | null;
+  max_depth_km: number | null;
+  gdacs_min_level: "green" | "orange" | "red" | null;
+  eonet_categories: string[];
+  volcano_status_changes: boolean;
+  weather_warnings: boolean; // opt-in, off by default
+  quiet_hours: QuietHours | null;
+}
+
 export interface Watchlist {
   id: number;
   name: string;
   latitude: number;
   longitude: number;
   radius_km: number;
+  shape: WatchShape;
+  rules: WatchRules;
   created_at: number;
 }
@@ -8,3 +8,46 @@ export interface Weather {
   wind_direction: number;
   humidity_pct: number;
 }
+
+export interface HourlyForecast {
+  time: number;
+  temperature_c: number | null;
+  precipitation_mm: number | null;
+  precipitation_probability: number | null;
+  cloud_cover_pct: number | null;
+  visibility_m: number | null;
+  wind_speed_kmh: number | null;
+  wind_gusts_kmh: number | null;
+  pressure_hpa: number | null;
+  weather_code: number | null;
+  weather_description: string | null;
+}
+
+export interface DailyForecast {
+  date: number;
+  weather_code: number | null;
+  weather_description: string | null;
+  temperature_max_c: number | null;
+  temperature_min_c: number | null;
+  precipitation_mm: number | null;
+  precipitation_probability: number | null;
+  wind_gusts_max_kmh: number | null;
+  sunrise: number | null;
+  sunset: number | null;
+}
+
+export interface Forecast {
+  latitude: number;
+  longitude: number;
+  hourly: HourlyForecast[];
+  daily: DailyForecast[];
+}
+
+export interface LocalConditions {
+  time: number;
+  cloud_cover_pct: number | null;
+  visibility_m: number | null;
+  precipitation_probability: number | null;
+  weather_description: string | null;
+  summary: string;
+}
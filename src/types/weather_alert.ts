@@ -0,0 +1,36 @@
+import type { WatchShape } from "./watchlist";
+
+export type CapSeverity = "extreme" | "severe" | "moderate" | "minor" | "unknown";
+export type CapUrgency = "immediate" | "expected" | "future" | "past" | "unknown";
+export type CapCertainty = "observed" | "likely" | "possible" | "unlikely" | "unknown";
+
+export interface Geocode {
+  name: string;
+  value: string;
+}
+
+export interface WeatherAlertArea {
+  description: string;
+  shapes: WatchShape[];
+  geocodes: Geocode[];
+}
+
+export interface WeatherAlert {
+  id: string;
+  feed_url: string;
+  sender: string | null;
+  event: string;
+  headline: string;
+  description: string;
+  instruction: string | null;
+  msg_type: "alert" | "update" | "cancel";
+  severity: CapSeverity;
+  urgency: CapUrgency;
+  certainty: CapCertainty;
+  sent: string | null;
+  onset: string | null;
+  expires: string | null;
+  areas: WeatherAlertArea[];
+  web: string | null;
+  references: string[]; // identifiers of the messages an update or cancel replaces
+}